@@ -0,0 +1,13 @@
+import 'dart:io';
+import 'package:autonomix/cli.dart';
+
+/// The command line interface as a plain Dart program. The Linux runner
+/// hands `autonomix <command>` over to it before GTK starts, so commands
+/// work without a display, e.g. over SSH or from cron.
+Future<void> main(List<String> args) async {
+  if (!Cli.handles(args)) {
+    stderr.writeln(Cli.usage);
+    exit(2);
+  }
+  exit(await Cli.create().run(args));
+}
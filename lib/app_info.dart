@@ -0,0 +1,10 @@
+/// Keep in sync with `version` in pubspec.yaml.
+const String appVersion = '0.3.5';
+
+/// The repository Autonomix itself is released from.
+const String selfRepoOwner = 'plebone';
+const String selfRepoName = 'autonomix';
+
+/// Keep in sync with `APPLICATION_ID` in linux/CMakeLists.txt; names the
+/// data directory.
+const String applicationId = 'com.example.autonomix';
@@ -0,0 +1,330 @@
+import 'dart:io';
+import 'models/release_provider.dart';
+import 'models/tracked_app.dart';
+import 'services/database_service.dart';
+import 'services/github_service.dart';
+import 'services/install_manager.dart';
+import 'services/install_tracker.dart';
+import 'services/installer_service.dart';
+import 'services/notification_service.dart';
+import 'services/proxy_config.dart';
+import 'services/release_selector.dart';
+
+/// Headless commands, run instead of the GUI when Autonomix is started with
+/// one of [commands] as its first argument. The Linux runner hands those to
+/// the plain Dart build in bin/autonomix_cli.dart before GTK starts, so
+/// nothing reachable from here may import Flutter. [run] returns the exit
+/// code.
+class Cli {
+  /// Keep in sync with `kCliCommands` in linux/runner/main.cc.
+  static const Set<String> commands = {'add', 'check', 'export', 'import', 'install', 'list', 'update-all'};
+
+  /// Exit code of `check` when updates are available, as with
+  /// `yum check-update`, so scripts can tell it apart from errors.
+  static const int updatesAvailableExitCode = 100;
+
+  static const String usage = '''Usage:
+  autonomix list                     Show tracked apps and their versions
+  autonomix add <repo> [--name <name>]
+                                     Track a repo given as owner/repo, host/owner/repo or URL
+  autonomix check [--notify]         Check every app for updates; exits $updatesAvailableExitCode if any
+  autonomix update-all               Check every app and install the pending updates
+  autonomix export --script          Print a shell script that restores installed apps
+  autonomix import <snippet>         Track an app from a shared snippet
+  autonomix install <owner/repo[@tag-prefix]> [--tag <tag>]
+                                     Install the latest (or a specific) release''';
+
+  final DatabaseService db;
+  final InstallManager manager;
+  final NotificationService notifications;
+
+  Cli(this.db, this.manager, this.notifications);
+
+  factory Cli.create() {
+    final db = DatabaseService();
+    return Cli(
+      db,
+      InstallManager(db, GitHubService(), InstallerService(), InstallTracker()),
+      NotificationService(),
+    );
+  }
+
+  static bool handles(List<String> args) => args.isNotEmpty && commands.contains(args.first);
+
+  Future<int> run(List<String> args) async {
+    try {
+      await db.migrate();
+      final settings = await db.getSettings();
+      ReleaseSelector.defaultChannel = ReleaseSelector.channelIn(settings);
+      manager.gh
+        ..customHeaders = GitHubService.headersFromSetting(settings[GitHubService.headersSettingKey])
+        ..token = GitHubService.tokenFrom(settings, Platform.environment)
+        ..etags = GitHubService.etagsFromJson(await db.getEtagCache());
+      manager.installer.verifyChecksums =
+          DatabaseService.settingOr(settings, InstallerService.checksumSettingKey, true);
+      manager.installer.preferMusl = DatabaseService.settingOr(
+          settings, InstallerService.preferMuslSettingKey, await InstallerService.hostLibc() == 'musl');
+      manager.refreshConcurrency =
+          DatabaseService.settingOr(settings, InstallManager.refreshConcurrencySettingKey, 5);
+      final proxy = ProxyConfig.fromSettings(settings);
+      if (proxy != null) {
+        manager.gh.useProxy(proxy);
+        manager.installer.proxy = proxy;
+      }
+      switch (args.first) {
+        case 'add':
+          return await _add(args.skip(1).toList());
+        case 'list':
+          return await _list(args.skip(1).toList());
+        case 'update-all':
+          return await _updateAll(args.skip(1).toList());
+        case 'check':
+          return await _check(args.skip(1).toList());
+        case 'export':
+          return await _export(args.skip(1).toList());
+        case 'import':
+          return await _import(args.skip(1).toList());
+        case 'install':
+          return await _install(args.skip(1).toList());
+      }
+    } catch (e) {
+      stderr.writeln('Error: $e');
+      return 1;
+    }
+    return _usageError();
+  }
+
+  int _usageError() {
+    stderr.writeln(usage);
+    return 2;
+  }
+
+  Future<int> _check(List<String> args) async {
+    if (args.any((a) => a != '--notify')) return _usageError();
+
+    var failed = false;
+    final updates = <TrackedApp>[];
+    final now = DateTime.now();
+    final summary = await manager.refreshAll(await db.getAllApps(), onError: (app, e) {
+      failed = true;
+      stderr.writeln('${app.trackingKey}: $e');
+    });
+    for (final refreshed in summary.refreshed) {
+      if (refreshed.showsUpdate(now)) {
+        updates.add(refreshed);
+        stdout.writeln('${refreshed.trackingKey}: ${refreshed.installedVersion} -> ${refreshed.latestVersion}');
+      }
+    }
+    if (summary.rateLimit != null) {
+      failed = true;
+      stderr.writeln('${summary.rateLimit}; skipped the remaining apps');
+    }
+
+    await db.saveEtagCache(GitHubService.etagsToJson(manager.gh.etags));
+
+    final message = NotificationService.describeUpdates(updates);
+    if (args.contains('--notify') && message != null &&
+        NotificationService.enabledIn(await db.getSettings())) {
+      await notifications.notify(message.summary, message.body);
+    }
+    if (updates.isNotEmpty) return updatesAvailableExitCode;
+    return failed ? 1 : 0;
+  }
+
+  Future<int> _list(List<String> args) async {
+    if (args.isNotEmpty) return _usageError();
+    final apps = await db.getAllApps();
+    if (apps.isEmpty) {
+      stdout.writeln('No apps tracked yet');
+      return 0;
+    }
+    formatList(apps, DateTime.now()).forEach(stdout.writeln);
+    return 0;
+  }
+
+  Future<int> _add(List<String> args) async {
+    final nameIndex = args.indexOf('--name');
+    if (nameIndex != -1 && nameIndex + 1 >= args.length) return _usageError();
+    final name = nameIndex != -1 ? args[nameIndex + 1] : null;
+    final rest = [
+      for (var i = 0; i < args.length; i++)
+        if (nameIndex == -1 || (i != nameIndex && i != nameIndex + 1)) args[i],
+    ];
+    if (rest.length != 1) return _usageError();
+
+    final parsed = parseRepoArgument(rest.first);
+    if (parsed == null) {
+      stderr.writeln('${rest.first} is not a repository');
+      return 2;
+    }
+    final provider = parsed.provider ?? await manager.gh.detectProvider(parsed.host);
+    // Apps on a public instance don't store its host, as in the add dialog
+    final host = parsed.provider == null ? parsed.host : null;
+
+    final info = await manager.gh.sourceOf(provider, host: host).getRepository(parsed.owner, parsed.repo);
+    final displayName = name ?? info['name'] as String;
+    await db.addApp(
+      info['owner']['login'] as String,
+      info['name'] as String,
+      displayName,
+      tagPrefix: parsed.tagPrefix,
+      provider: provider,
+      host: host,
+    );
+    stdout.writeln('Tracking $displayName');
+    return 0;
+  }
+
+  Future<int> _updateAll(List<String> args) async {
+    if (args.isNotEmpty) return _usageError();
+
+    var failed = false;
+    final now = DateTime.now();
+    final summary = await manager.refreshAll(await db.getAllApps(), onError: (app, e) {
+      failed = true;
+      stderr.writeln('${app.trackingKey}: $e');
+    });
+    if (summary.rateLimit != null) {
+      failed = true;
+      stderr.writeln('${summary.rateLimit}; skipped the remaining apps');
+    }
+    await db.saveEtagCache(GitHubService.etagsToJson(manager.gh.etags));
+
+    // Autonomix can't replace itself while running
+    final pending = summary.refreshed.where((app) => app.showsUpdate(now) && !app.isSelf).toList();
+    if (pending.isEmpty) {
+      stdout.writeln('Everything is up to date');
+      return failed ? 1 : 0;
+    }
+    for (final app in pending) {
+      stdout.writeln('${app.trackingKey}: ${app.installedVersion} -> ${app.latestVersion}');
+    }
+    final result = await manager.installAll(pending);
+    stdout.writeln(result);
+    return failed || result.failed.isNotEmpty ? 1 : 0;
+  }
+
+  Future<int> _export(List<String> args) async {
+    if (!args.contains('--script')) return _usageError();
+    stdout.write(restoreScript(await db.getAllApps(), generatedAt: DateTime.now()));
+    return 0;
+  }
+
+  Future<int> _import(List<String> args) async {
+    if (args.length != 1) return _usageError();
+    final app = TrackedApp.fromSnippet(args.first);
+    if (await _find(app.trackingKey) != null) {
+      stdout.writeln('${app.trackingKey} is already tracked');
+      return 0;
+    }
+    await db.importApp(app);
+    stdout.writeln('Tracking ${app.displayName}');
+    return 0;
+  }
+
+  Future<int> _install(List<String> args) async {
+    if (args.isEmpty) return _usageError();
+    final tagIndex = args.indexOf('--tag');
+    if (tagIndex != -1 && tagIndex + 1 >= args.length) return _usageError();
+    final tag = tagIndex != -1 ? args[tagIndex + 1] : null;
+
+    final app = await _find(args.first);
+    if (app == null) {
+      stderr.writeln('${args.first} is not tracked');
+      return 1;
+    }
+
+    final choice = await manager.resolve(app, tag: tag);
+    if (choice == null) {
+      stderr.writeln('No compatible package for ${app.displayName}');
+      return 1;
+    }
+    stdout.writeln('Installing ${app.displayName} ${choice.release.tagName} (${choice.asset.name})');
+    await manager.install(app, choice.release, choice.asset, choice.type,
+        reason: choice.reason,
+        onStatus: stdout.writeln,
+        onWarning: (warning) => stderr.writeln('Warning: $warning'));
+    return 0;
+  }
+
+  Future<TrackedApp?> _find(String key) async {
+    for (final app in await db.getAllApps()) {
+      if (app.trackingKey == key) return app;
+    }
+    return null;
+  }
+
+  /// A POSIX shell script that re-tracks each installed app (keeping its
+  /// shareable settings) and reinstalls the recorded version. Autonomix
+  /// itself is left out since it has to be present to run the script.
+  static String restoreScript(List<TrackedApp> apps, {required DateTime generatedAt}) {
+    final buffer = StringBuffer()
+      ..writeln('#!/bin/sh')
+      ..writeln('# Autonomix restore script, generated ${generatedAt.toIso8601String()}')
+      ..writeln('set -e');
+    for (final app in apps) {
+      if (!app.isInstalled || app.isSelf) continue;
+      final repo = shellQuote(app.trackingKey);
+      buffer
+        ..writeln()
+        ..writeln('# ${app.displayName.replaceAll('\n', ' ')}')
+        ..writeln('autonomix import ${shellQuote(app.toSnippet())}')
+        ..writeln('autonomix install $repo --tag ${shellQuote(app.installedVersion!)}');
+    }
+    return buffer.toString();
+  }
+
+  /// Reads the repo argument of `add`: `owner/repo` on GitHub, a repository
+  /// URL, or `host/owner/repo` as printed by `list`. Any of them may end in
+  /// `@tag-prefix`. Returns null for anything else.
+  static ({ReleaseProvider? provider, String host, String owner, String repo, String? tagPrefix})?
+      parseRepoArgument(String value) {
+    var text = value.trim();
+    String? tagPrefix;
+    final at = text.lastIndexOf('@');
+    if (at > text.lastIndexOf('/')) {
+      tagPrefix = text.substring(at + 1);
+      text = text.substring(0, at);
+      if (tagPrefix.isEmpty) tagPrefix = null;
+    }
+
+    final segments = text.split('/');
+    if (!text.contains('://')) {
+      if (segments.length == 2) {
+        if (segments.any((s) => s.isEmpty)) return null;
+        text = 'https://${ReleaseProvider.github.host}/$text';
+      } else if (segments.first.contains('.')) {
+        text = 'https://$text';
+      } else {
+        return null;
+      }
+    }
+
+    final parsed = ReleaseProvider.parseRepoUrl(text);
+    if (parsed == null) return null;
+    return (
+      provider: parsed.provider,
+      host: parsed.host,
+      owner: parsed.owner,
+      repo: parsed.repo,
+      tagPrefix: tagPrefix,
+    );
+  }
+
+  /// One tab separated line per app for `list`: its tracking key, the
+  /// installed and latest versions, and what stands out about it.
+  static List<String> formatList(List<TrackedApp> apps, DateTime now) {
+    return [
+      for (final app in apps)
+        [
+          app.trackingKey,
+          app.installedVersion ?? 'not installed',
+          app.latestVersion ?? 'unknown',
+          if (app.showsUpdate(now)) 'update available',
+          if (app.pinned) 'pinned',
+        ].join('\t'),
+    ];
+  }
+
+  static String shellQuote(String value) => "'${value.replaceAll("'", "'\\''")}'";
+}
@@ -1,12 +1,23 @@
+import 'dart:io';
 import 'package:flutter/material.dart';
 import 'package:provider/provider.dart';
 
+import 'cli.dart';
 import 'ui/home_screen.dart';
 import 'services/database_service.dart';
+import 'services/error_log_service.dart';
 import 'services/github_service.dart';
+import 'services/install_manager.dart';
+import 'services/install_tracker.dart';
 import 'services/installer_service.dart';
+import 'services/notification_service.dart';
+import 'services/self_update_service.dart';
 
-void main() {
+Future<void> main(List<String> args) async {
+  if (Cli.handles(args)) {
+    WidgetsFlutterBinding.ensureInitialized();
+    exit(await Cli.create().run(args));
+  }
   runApp(const AutonomixApp());
 }
 
@@ -20,6 +31,15 @@ class AutonomixApp extends StatelessWidget {
         Provider(create: (_) => DatabaseService()),
         Provider(create: (_) => GitHubService()),
         Provider(create: (_) => InstallerService()),
+        Provider(create: (_) => ErrorLogService()),
+        Provider(create: (_) => SelfUpdateService()),
+        Provider(create: (_) => NotificationService()),
+        ChangeNotifierProvider(create: (_) => InstallTracker()),
+        ProxyProvider4<DatabaseService, GitHubService, InstallerService, InstallTracker, InstallManager>(
+          // Keep the first instance so its install queue survives rebuilds
+          update: (_, db, gh, installer, tracker, previous) =>
+              previous ?? InstallManager(db, gh, installer, tracker),
+        ),
       ],
       child: MaterialApp(
         title: 'Autonomix',
@@ -0,0 +1,51 @@
+import 'release.dart';
+
+/// Per-app details that are too expensive to fetch for every row on load,
+/// fetched when a details view asks for them.
+class AppMetadata {
+  final int? stars;
+  final String? description;
+  final String? homepage;
+
+  /// Primary language as detected by GitHub, e.g. "Rust".
+  final String? language;
+
+  /// SPDX id of the repository's license, e.g. "MIT".
+  final String? license;
+  final String? latestReleaseName;
+  final DateTime? latestPublishedAt;
+
+  AppMetadata({
+    this.stars,
+    this.description,
+    this.homepage,
+    this.language,
+    this.license,
+    this.latestReleaseName,
+    this.latestPublishedAt,
+  });
+
+  factory AppMetadata.fromJson(Map<String, dynamic> repository, Release? latest) {
+    final homepage = repository['homepage'] as String?;
+    final license = repository['license'] is Map ? repository['license']['spdx_id'] as String? : null;
+    return AppMetadata(
+      stars: repository['stargazers_count'] as int?,
+      description: repository['description'] as String?,
+      homepage: homepage == null || homepage.isEmpty ? null : homepage,
+      language: repository['language'] as String?,
+      // GitHub reports licenses it can't identify as NOASSERTION
+      license: license == 'NOASSERTION' ? null : license,
+      latestReleaseName: latest?.name,
+      latestPublishedAt: latest?.publishedAt,
+    );
+  }
+
+  /// "Rust • MIT", or whichever half is known; null when neither is.
+  String? get languageAndLicense {
+    final parts = [
+      if (language != null) language!,
+      if (license != null) license!,
+    ];
+    return parts.isEmpty ? null : parts.join(' • ');
+  }
+}
@@ -0,0 +1,60 @@
+import 'tracked_app.dart';
+
+/// Orders of the app list.
+enum AppSort {
+  name,
+
+  /// Apps with a pending update first, then by name.
+  updatesFirst,
+
+  /// Most recently checked first; never checked apps last.
+  lastChecked;
+
+  static const String settingKey = 'app_sort';
+
+  static AppSort? fromString(String? value) {
+    switch (value) {
+      case 'name':
+        return AppSort.name;
+      case 'updatesFirst':
+        return AppSort.updatesFirst;
+      case 'lastChecked':
+        return AppSort.lastChecked;
+      default:
+        return null;
+    }
+  }
+
+  String get displayName {
+    switch (this) {
+      case AppSort.name:
+        return 'Name';
+      case AppSort.updatesFirst:
+        return 'Updates first';
+      case AppSort.lastChecked:
+        return 'Recently checked';
+    }
+  }
+
+  /// A sorted copy of [apps]. Ties are broken by name so the order is
+  /// stable between refreshes.
+  List<TrackedApp> sort(List<TrackedApp> apps, DateTime now) {
+    int byName(TrackedApp a, TrackedApp b) => a.displayName.toLowerCase().compareTo(b.displayName.toLowerCase());
+    int compare(TrackedApp a, TrackedApp b) {
+      switch (this) {
+        case AppSort.name:
+          return 0;
+        case AppSort.updatesFirst:
+          return (b.showsUpdate(now) ? 1 : 0) - (a.showsUpdate(now) ? 1 : 0);
+        case AppSort.lastChecked:
+          final epoch = DateTime.fromMillisecondsSinceEpoch(0);
+          return (b.lastChecked ?? epoch).compareTo(a.lastChecked ?? epoch);
+      }
+    }
+
+    return [...apps]..sort((a, b) {
+        final result = compare(a, b);
+        return result != 0 ? result : byName(a, b);
+      });
+  }
+}
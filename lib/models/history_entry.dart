@@ -0,0 +1,45 @@
+import 'install_type.dart';
+
+class HistoryEntry {
+  final int appId;
+  final String action;
+  final String? version;
+  final InstallType? installType;
+  final DateTime timestamp;
+  final bool success;
+  final String? details;
+
+  HistoryEntry({
+    required this.appId,
+    required this.action,
+    this.version,
+    this.installType,
+    required this.timestamp,
+    required this.success,
+    this.details,
+  });
+
+  Map<String, dynamic> toMap() {
+    return {
+      'app_id': appId,
+      'action': action,
+      'version': version,
+      'install_type': installType?.name,
+      'timestamp': timestamp.toIso8601String(),
+      'success': success,
+      'details': details,
+    };
+  }
+
+  factory HistoryEntry.fromMap(Map<String, dynamic> map) {
+    return HistoryEntry(
+      appId: map['app_id'] as int,
+      action: map['action'] as String,
+      version: map['version'] as String?,
+      installType: InstallType.fromString(map['install_type'] as String?),
+      timestamp: DateTime.parse(map['timestamp'] as String),
+      success: map['success'] as bool? ?? true,
+      details: map['details'] as String?,
+    );
+  }
+}
@@ -5,7 +5,8 @@ enum InstallType {
   flatpak,
   snap,
   binary,
-  source;
+  source,
+  pacman;
 
   String get name {
     switch (this) {
@@ -23,6 +24,8 @@ enum InstallType {
         return 'binary';
       case InstallType.source:
         return 'source';
+      case InstallType.pacman:
+        return 'pacman';
     }
   }
 
@@ -43,6 +46,8 @@ enum InstallType {
         return InstallType.binary;
       case 'source':
         return InstallType.source;
+      case 'pacman':
+        return InstallType.pacman;
       default:
         return null;
     }
@@ -64,6 +69,8 @@ enum InstallType {
         return 'Binary';
       case InstallType.source:
         return 'Source';
+      case InstallType.pacman:
+        return 'Pacman';
     }
   }
 }
@@ -37,21 +37,101 @@ class Release {
 class ReleaseAsset {
   final String name;
   final String browserDownloadUrl;
+
+  /// API endpoint for the asset, which (unlike [browserDownloadUrl]) accepts
+  /// a token, so private repos' assets can be downloaded.
+  final String? apiUrl;
   final String contentType;
   final int size;
 
   ReleaseAsset({
     required this.name,
     required this.browserDownloadUrl,
+    this.apiUrl,
     required this.contentType,
     required this.size,
   });
 
+  /// Architecture the asset name says it was built for, if any.
+  String? get architecture => architectureOf(name);
+
+  /// C library the asset name says it links against, if any.
+  String? get libc => libcOf(name);
+
+  /// Names used like `InstallerService.hostArchitecture`. A bare `arm`
+  /// usually means ARMv7 hard-float, as in Go's `GOARCH=arm` builds.
+  static const Map<String, List<String>> _archAliases = {
+    'x86_64': ['x86_64', 'x86-64', 'amd64', 'x64'],
+    'aarch64': ['aarch64', 'arm64'],
+    'x86': ['i386', 'i686', 'x86', 'ia32'],
+    'armv7': ['armv7l', 'armv7', 'armhf', 'arm'],
+    'riscv64': ['riscv64', 'riscv64gc'],
+    'ppc64le': ['ppc64le', 'ppc64el', 'powerpc64le'],
+    's390x': ['s390x'],
+  };
+
+  static String? architectureOf(String assetName) {
+    final lower = assetName.toLowerCase();
+    for (final entry in _archAliases.entries) {
+      for (final alias in entry.value) {
+        if (_containsToken(lower, alias)) return entry.key;
+      }
+    }
+    return null;
+  }
+
+  static String? libcOf(String assetName) {
+    final lower = assetName.toLowerCase();
+    if (lower.contains('musl')) return 'musl';
+    if (lower.contains('glibc') || lower.contains('gnueabi') || _containsToken(lower, 'gnu')) {
+      return 'glibc';
+    }
+    return null;
+  }
+
+  /// Matches [token] only when it isn't glued to other letters or digits,
+  /// so `x86` doesn't match inside `x86_64`.
+  static bool _containsToken(String haystack, String token) {
+    return RegExp('(?<![a-z0-9])${RegExp.escape(token)}(?![a-z0-9_])').hasMatch(haystack);
+  }
+
+  /// A per-app asset pattern as a [RegExp]: a regex when written between
+  /// slashes, otherwise a glob over the whole name where `*` and `?` are
+  /// wildcards and `[...]` (or `[!...]`) a character class. Both ignore
+  /// case. Throws [FormatException] for a pattern that doesn't compile.
+  static RegExp patternRegExp(String pattern) {
+    final trimmed = pattern.trim();
+    if (trimmed.length > 2 && trimmed.startsWith('/') && trimmed.endsWith('/')) {
+      return RegExp(trimmed.substring(1, trimmed.length - 1), caseSensitive: false);
+    }
+    final regex = StringBuffer('^');
+    for (var i = 0; i < trimmed.length; i++) {
+      final char = trimmed[i];
+      if (char == '*') {
+        regex.write('.*');
+      } else if (char == '?') {
+        regex.write('.');
+      } else if (char == '[') {
+        final end = trimmed.indexOf(']', i + 1);
+        if (end == -1) throw FormatException('Unclosed [ in pattern', pattern, i);
+        final set = trimmed.substring(i + 1, end).replaceAll(r'\', r'\\');
+        regex.write(set.startsWith('!') ? '[^${set.substring(1)}]' : '[$set]');
+        i = end;
+      } else {
+        regex.write(RegExp.escape(char));
+      }
+    }
+    regex.write(r'$');
+    return RegExp(regex.toString(), caseSensitive: false);
+  }
+
   factory ReleaseAsset.fromJson(Map<String, dynamic> json) {
     return ReleaseAsset(
       name: json['name'] as String,
       browserDownloadUrl: json['browser_download_url'] as String,
-      contentType: json['content_type'] as String,
+      apiUrl: json['url'] as String?,
+      // Gitea and Forgejo leave the content type out
+      contentType: json['content_type'] as String? ?? 'application/octet-stream',
       size: json['size'] as int,
     );
   }
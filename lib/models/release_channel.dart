@@ -0,0 +1,29 @@
+/// Which releases count as updates: stable ones only, or pre-releases too.
+enum ReleaseChannel {
+  stable,
+  prerelease;
+
+  /// Settings key for the global default channel. Apps without an override
+  /// of their own follow it.
+  static const String settingKey = 'release_channel';
+
+  static ReleaseChannel? fromString(String? value) {
+    switch (value?.toLowerCase()) {
+      case 'stable':
+        return ReleaseChannel.stable;
+      case 'prerelease':
+        return ReleaseChannel.prerelease;
+      default:
+        return null;
+    }
+  }
+
+  String get displayName {
+    switch (this) {
+      case ReleaseChannel.stable:
+        return 'Stable';
+      case ReleaseChannel.prerelease:
+        return 'Include pre-releases';
+    }
+  }
+}
@@ -0,0 +1,76 @@
+/// The forge an app's releases are published on.
+enum ReleaseProvider {
+  github,
+  gitlab,
+
+  /// Gitea and its fork Forgejo, which share an API.
+  gitea;
+
+  static ReleaseProvider? fromString(String? value) {
+    switch (value?.toLowerCase()) {
+      case 'github':
+        return ReleaseProvider.github;
+      case 'gitlab':
+        return ReleaseProvider.gitlab;
+      case 'gitea':
+      case 'forgejo':
+        return ReleaseProvider.gitea;
+      default:
+        return null;
+    }
+  }
+
+  String get displayName {
+    switch (this) {
+      case ReleaseProvider.github:
+        return 'GitHub';
+      case ReleaseProvider.gitlab:
+        return 'GitLab';
+      case ReleaseProvider.gitea:
+        return 'Gitea / Forgejo';
+    }
+  }
+
+  /// Web host of the public instance; apps on self-hosted instances keep
+  /// their own host.
+  String get host {
+    switch (this) {
+      case ReleaseProvider.github:
+        return 'github.com';
+      case ReleaseProvider.gitlab:
+        return 'gitlab.com';
+      case ReleaseProvider.gitea:
+        return 'codeberg.org';
+    }
+  }
+
+  /// Reads the host, owner and repo from a repository (or release page)
+  /// URL. The provider is null for hosts that are not a known public
+  /// instance, which may be self-hosted Gitea, Forgejo or GitLab. GitLab
+  /// owners may be nested groups, e.g. `group/subgroup`. Returns null for
+  /// URLs without an owner and repo.
+  static ({ReleaseProvider? provider, String host, String owner, String repo})? parseRepoUrl(String url) {
+    final uri = Uri.tryParse(url.trim());
+    if (uri == null || uri.host.isEmpty) return null;
+    final host = uri.host.toLowerCase().replaceFirst(RegExp(r'^www\.'), '');
+    final provider = ReleaseProvider.values.where((p) => p.host == host).firstOrNull;
+
+    var segments = uri.pathSegments.where((s) => s.isNotEmpty).toList();
+    final dash = segments.indexOf('-');
+    if (dash != -1) {
+      // Everything after /-/ is a page of a GitLab project
+      segments = segments.sublist(0, dash);
+    } else if (provider != ReleaseProvider.gitlab && segments.length > 2) {
+      segments = segments.sublist(0, 2);
+    }
+    if (segments.length < 2) return null;
+
+    final repo = segments.last.replaceFirst(RegExp(r'\.git$'), '');
+    return (
+      provider: provider,
+      host: uri.hasPort ? '$host:${uri.port}' : host,
+      owner: segments.sublist(0, segments.length - 1).join('/'),
+      repo: repo,
+    );
+  }
+}
@@ -1,4 +1,9 @@
+import 'dart:convert';
+import '../app_info.dart';
 import 'install_type.dart';
+import 'release.dart';
+import 'release_channel.dart';
+import 'release_provider.dart';
 
 class TrackedApp {
   final int? id;
@@ -10,6 +15,52 @@ class TrackedApp {
   final InstallType? installType;
   final String? launchCommand;
   final String? packageName;
+  final String? changelogUrl;
+  final String? tagPrefix;
+  final String? installedAsset;
+  final Duration? checkInterval;
+  final String? versionCommand;
+  final String? reportedVersion;
+  final DateTime? deletedAt;
+  final List<String>? assetIncludeKeywords;
+  final List<String>? assetExcludeKeywords;
+  final DateTime? snoozeUntil;
+  final String? snoozedVersion;
+  final Map<String, String>? env;
+  /// Set when the release the installed version came from has since been
+  /// deleted upstream, so it can't be reinstalled.
+  final bool installedUnavailable;
+  final String? preferredAssetSignature;
+  /// Overrides the global release channel; null follows it.
+  final ReleaseChannel? channel;
+  /// Every file a binary install put in `~/.local/bin`, removed again on
+  /// uninstall. Null for other install types.
+  final List<String>? installedFiles;
+  /// Shell command run after each successful install or update, with the
+  /// new version in `AUTONOMIX_VERSION`. Never shared in snippets.
+  final String? postUpdateCommand;
+  /// Where the releases are published; null means GitHub.
+  final ReleaseProvider? provider;
+  /// Host of a self-hosted instance; null means the public one of
+  /// [provider].
+  final String? host;
+  /// Kept at the installed version: updates are neither flagged nor
+  /// included in Update All, though they can still be installed by hand.
+  final bool pinned;
+  /// Releases the user chose to skip; a newer one is flagged as usual.
+  final List<String>? ignoredVersions;
+  /// Notes of the [latestVersion] release as published, kept so they can
+  /// be read before updating without another request.
+  final String? latestReleaseNotes;
+  /// Public key (minisign, or an armored OpenPGP key) that must have signed
+  /// each download. Apps without one skip signature checks.
+  final String? signingKey;
+  /// Glob (`*-gtk-*.AppImage`) or regex between slashes (`/-gtk-.*\.AppImage$/`)
+  /// that asset names must match to be installed.
+  final String? assetPattern;
+  /// [installedVersion] when [installedUnavailable] was last worked out,
+  /// so it is only asked again once either version changes.
+  final String? checkedInstalledVersion;
   final DateTime? lastChecked;
   final DateTime createdAt;
 
@@ -23,16 +74,69 @@ class TrackedApp {
     this.installType,
     this.launchCommand,
     this.packageName,
+    this.changelogUrl,
+    this.tagPrefix,
+    this.installedAsset,
+    this.checkInterval,
+    this.versionCommand,
+    this.reportedVersion,
+    this.deletedAt,
+    this.assetIncludeKeywords,
+    this.assetExcludeKeywords,
+    this.snoozeUntil,
+    this.snoozedVersion,
+    this.env,
+    this.installedUnavailable = false,
+    this.preferredAssetSignature,
+    this.channel,
+    this.installedFiles,
+    this.postUpdateCommand,
+    this.provider,
+    this.host,
+    this.pinned = false,
+    this.ignoredVersions,
+    this.latestReleaseNotes,
+    this.signingKey,
+    this.assetPattern,
+    this.checkedInstalledVersion,
     this.lastChecked,
     required this.createdAt,
   });
 
-  String get repoUrl => 'https://github.com/$repoOwner/$repoName';
+  /// How often automatic checks look at apps without their own interval.
+  /// Follows the automatic check setting.
+  static Duration defaultCheckInterval = const Duration(hours: 24);
+
+  /// Whether an automatic update check should query this app now. Manual
+  /// refreshes ignore this and check everything.
+  bool isDueForCheck(DateTime now) {
+    if (lastChecked == null) return true;
+    return now.difference(lastChecked!) >= (checkInterval ?? defaultCheckInterval);
+  }
+
+  ReleaseProvider get source => provider ?? ReleaseProvider.github;
+
+  /// Web host the repo lives on.
+  String get webHost => host ?? source.host;
+
+  String get repoUrl => 'https://$webHost/$repoOwner/$repoName';
+
+  /// Where the "Release notes" action points. A per-app changelog URL wins,
+  /// otherwise we fall back to the release page on the forge.
+  String get releaseNotesUrl {
+    if (changelogUrl != null && changelogUrl!.isNotEmpty) return changelogUrl!;
+    if (source == ReleaseProvider.gitlab) {
+      return latestVersion != null ? '$repoUrl/-/releases/$latestVersion' : '$repoUrl/-/releases';
+    }
+    if (latestVersion != null) return '$repoUrl/releases/tag/$latestVersion';
+    return '$repoUrl/releases/latest';
+  }
 
   bool get hasUpdate {
     if (installedVersion == null || latestVersion == null) return false;
     
-    final installed = _normalizeVersion(installedVersion!);
+    // What the binary says about itself beats a tag that may not be a version
+    final installed = _normalizeVersion(reportedVersion ?? installedVersion!);
     final latest = _normalizeVersion(latestVersion!);
     
     if (installed == latest) return false;
@@ -40,8 +144,48 @@ class TrackedApp {
     return _isNewerVersion(latest, installed);
   }
 
+  /// Whether the update badge is hidden by a snooze. The snooze lapses when
+  /// [snoozeUntil] passes or a release newer than [snoozedVersion] arrives.
+  bool isSnoozed(DateTime now) {
+    if (snoozeUntil == null || !now.isBefore(snoozeUntil!)) return false;
+    if (snoozedVersion == null || latestVersion == null) return true;
+    return _normalizeVersion(latestVersion!) == _normalizeVersion(snoozedVersion!);
+  }
+
+  /// Whether the latest release is one the user skipped.
+  bool get latestIgnored {
+    final latest = latestVersion;
+    if (latest == null || ignoredVersions == null) return false;
+    return ignoredVersions!.any((v) => _normalizeVersion(v) == _normalizeVersion(latest));
+  }
+
+  /// [hasUpdate] as the UI should show it, respecting any pin, snooze or
+  /// skipped version.
+  bool showsUpdate(DateTime now) => hasUpdate && !pinned && !latestIgnored && !isSnoozed(now);
+
   bool get isInstalled => installedVersion != null;
 
+  /// Architecture of the installed asset, as read from its file name.
+  String? get installedArchitecture =>
+      installedAsset == null ? null : ReleaseAsset.architectureOf(installedAsset!);
+
+  /// C library of the installed asset, as read from its file name.
+  String? get installedLibc => installedAsset == null ? null : ReleaseAsset.libcOf(installedAsset!);
+
+  /// Identifies the entry on the command line: `owner/repo` (led by the
+  /// host outside GitHub), plus `@prefix` for one product of a monorepo
+  /// tracked by tag prefix.
+  String get trackingKey {
+    final path = '$repoOwner/$repoName';
+    final repo = source == ReleaseProvider.github && host == null ? path : '$webHost/$path';
+    return tagPrefix == null || tagPrefix!.isEmpty ? repo : '$repo@$tagPrefix';
+  }
+
+  /// Whether this entry is Autonomix tracking itself.
+  bool get isSelf =>
+      source == ReleaseProvider.github &&
+      repoOwner.toLowerCase() == selfRepoOwner && repoName.toLowerCase() == selfRepoName;
+
   static String _normalizeVersion(String version) {
     var v = version.trim();
     if (v.startsWith('v') || v.startsWith('V')) {
@@ -56,6 +200,76 @@ class TrackedApp {
     return newVersion.compareTo(oldVersion) > 0;
   }
 
+  /// Per-app settings that travel with a shared snippet. Anything tied to
+  /// this machine (installed version, paths, ids) stays out, and so do
+  /// commands, which would run a stranger's shell code on import.
+  static const List<String> shareableFields = [
+    'tag_prefix',
+    'changelog_url',
+    'check_interval',
+    'asset_include_keywords',
+    'asset_exclude_keywords',
+    'asset_pattern',
+    'channel',
+    'provider',
+    'host',
+  ];
+
+  /// A compact one-line descriptor another user can paste into
+  /// "Import from snippet" to track this app the same way.
+  String toSnippet() {
+    final map = toMap();
+    return jsonEncode({
+      'repo': '$repoOwner/$repoName',
+      'display_name': displayName,
+      for (final key in shareableFields)
+        if (map[key] != null) key: map[key],
+    });
+  }
+
+  /// Parses a snippet produced by [toSnippet] into a new, untracked app.
+  factory TrackedApp.fromSnippet(String snippet) {
+    final decoded = jsonDecode(snippet.trim());
+    if (decoded is! Map<String, dynamic>) {
+      throw const FormatException('Snippet is not a JSON object');
+    }
+
+    // GitLab owners can be nested groups, so the name is after the last /
+    final repo = decoded['repo'];
+    final parts = repo is String ? repo.split('/') : const <String>[];
+    if (parts.length < 2 || parts.any((p) => p.isEmpty)) {
+      throw const FormatException('Snippet is missing "repo": "owner/name"');
+    }
+    final name = parts.last;
+
+    return TrackedApp.fromMap({
+      for (final key in shareableFields)
+        if (_isSnippetValue(decoded[key]) && (key != 'asset_pattern' || _isAssetPattern(decoded[key])))
+          key: decoded[key],
+      'repo_owner': parts.sublist(0, parts.length - 1).join('/'),
+      'repo_name': name,
+      'display_name': decoded['display_name'] as String? ?? name,
+      'created_at': DateTime.now().toIso8601String(),
+    });
+  }
+
+  /// Strings, numbers and keyword lists; anything else in a snippet is
+  /// dropped rather than trusted.
+  static bool _isSnippetValue(Object? value) =>
+      value is String || value is int || (value is List && value.every((e) => e is String));
+
+  /// Whether [value] is an asset pattern that compiles; see
+  /// [ReleaseAsset.patternRegExp].
+  static bool _isAssetPattern(Object? value) {
+    if (value is! String) return false;
+    try {
+      ReleaseAsset.patternRegExp(value);
+      return true;
+    } on FormatException {
+      return false;
+    }
+  }
+
   Map<String, dynamic> toMap() {
     return {
       'id': id,
@@ -67,6 +281,31 @@ class TrackedApp {
       'install_type': installType?.name,
       'launch_command': launchCommand,
       'package_name': packageName,
+      'changelog_url': changelogUrl,
+      'tag_prefix': tagPrefix,
+      'installed_asset': installedAsset,
+      'check_interval': checkInterval?.inSeconds,
+      'version_command': versionCommand,
+      'reported_version': reportedVersion,
+      'deleted_at': deletedAt?.toIso8601String(),
+      'asset_include_keywords': assetIncludeKeywords,
+      'asset_exclude_keywords': assetExcludeKeywords,
+      'snooze_until': snoozeUntil?.toIso8601String(),
+      'snoozed_version': snoozedVersion,
+      'env': env,
+      'installed_unavailable': installedUnavailable,
+      'preferred_asset_signature': preferredAssetSignature,
+      'channel': channel?.name,
+      'installed_files': installedFiles,
+      'post_update_command': postUpdateCommand,
+      'provider': provider?.name,
+      'host': host,
+      'pinned': pinned,
+      'ignored_versions': ignoredVersions,
+      'latest_release_notes': latestReleaseNotes,
+      'signing_key': signingKey,
+      'asset_pattern': assetPattern,
+      'checked_installed_version': checkedInstalledVersion,
       'last_checked': lastChecked?.toIso8601String(),
       'created_at': createdAt.toIso8601String(),
     };
@@ -83,6 +322,37 @@ class TrackedApp {
       installType: InstallType.fromString(map['install_type'] as String?),
       launchCommand: map['launch_command'] as String?,
       packageName: map['package_name'] as String?,
+      changelogUrl: map['changelog_url'] as String?,
+      tagPrefix: map['tag_prefix'] as String?,
+      installedAsset: map['installed_asset'] as String?,
+      checkInterval: map['check_interval'] != null
+          ? Duration(seconds: map['check_interval'] as int)
+          : null,
+      versionCommand: map['version_command'] as String?,
+      reportedVersion: map['reported_version'] as String?,
+      deletedAt: map['deleted_at'] != null
+          ? DateTime.parse(map['deleted_at'] as String)
+          : null,
+      assetIncludeKeywords: (map['asset_include_keywords'] as List?)?.cast<String>(),
+      assetExcludeKeywords: (map['asset_exclude_keywords'] as List?)?.cast<String>(),
+      snoozeUntil: map['snooze_until'] != null
+          ? DateTime.parse(map['snooze_until'] as String)
+          : null,
+      snoozedVersion: map['snoozed_version'] as String?,
+      env: (map['env'] as Map?)?.cast<String, String>(),
+      installedUnavailable: map['installed_unavailable'] as bool? ?? false,
+      preferredAssetSignature: map['preferred_asset_signature'] as String?,
+      channel: ReleaseChannel.fromString(map['channel'] as String?),
+      installedFiles: (map['installed_files'] as List?)?.cast<String>(),
+      postUpdateCommand: map['post_update_command'] as String?,
+      provider: ReleaseProvider.fromString(map['provider'] as String?),
+      host: map['host'] as String?,
+      pinned: map['pinned'] as bool? ?? false,
+      ignoredVersions: (map['ignored_versions'] as List?)?.cast<String>(),
+      latestReleaseNotes: map['latest_release_notes'] as String?,
+      signingKey: map['signing_key'] as String?,
+      assetPattern: map['asset_pattern'] as String?,
+      checkedInstalledVersion: map['checked_installed_version'] as String?,
       lastChecked: map['last_checked'] != null 
           ? DateTime.parse(map['last_checked'] as String) 
           : null,
@@ -100,6 +370,31 @@ class TrackedApp {
     InstallType? installType,
     String? launchCommand,
     String? packageName,
+    String? changelogUrl,
+    String? tagPrefix,
+    String? installedAsset,
+    Duration? checkInterval,
+    String? versionCommand,
+    String? reportedVersion,
+    DateTime? deletedAt,
+    List<String>? assetIncludeKeywords,
+    List<String>? assetExcludeKeywords,
+    DateTime? snoozeUntil,
+    String? snoozedVersion,
+    Map<String, String>? env,
+    bool? installedUnavailable,
+    String? preferredAssetSignature,
+    ReleaseChannel? channel,
+    List<String>? installedFiles,
+    String? postUpdateCommand,
+    ReleaseProvider? provider,
+    String? host,
+    bool? pinned,
+    List<String>? ignoredVersions,
+    String? latestReleaseNotes,
+    String? signingKey,
+    String? assetPattern,
+    String? checkedInstalledVersion,
     DateTime? lastChecked,
     DateTime? createdAt,
   }) {
@@ -113,6 +408,31 @@ class TrackedApp {
       installType: installType ?? this.installType,
       launchCommand: launchCommand ?? this.launchCommand,
       packageName: packageName ?? this.packageName,
+      changelogUrl: changelogUrl ?? this.changelogUrl,
+      tagPrefix: tagPrefix ?? this.tagPrefix,
+      installedAsset: installedAsset ?? this.installedAsset,
+      checkInterval: checkInterval ?? this.checkInterval,
+      versionCommand: versionCommand ?? this.versionCommand,
+      reportedVersion: reportedVersion ?? this.reportedVersion,
+      deletedAt: deletedAt ?? this.deletedAt,
+      assetIncludeKeywords: assetIncludeKeywords ?? this.assetIncludeKeywords,
+      assetExcludeKeywords: assetExcludeKeywords ?? this.assetExcludeKeywords,
+      snoozeUntil: snoozeUntil ?? this.snoozeUntil,
+      snoozedVersion: snoozedVersion ?? this.snoozedVersion,
+      env: env ?? this.env,
+      installedUnavailable: installedUnavailable ?? this.installedUnavailable,
+      preferredAssetSignature: preferredAssetSignature ?? this.preferredAssetSignature,
+      channel: channel ?? this.channel,
+      installedFiles: installedFiles ?? this.installedFiles,
+      postUpdateCommand: postUpdateCommand ?? this.postUpdateCommand,
+      provider: provider ?? this.provider,
+      host: host ?? this.host,
+      pinned: pinned ?? this.pinned,
+      ignoredVersions: ignoredVersions ?? this.ignoredVersions,
+      latestReleaseNotes: latestReleaseNotes ?? this.latestReleaseNotes,
+      signingKey: signingKey ?? this.signingKey,
+      assetPattern: assetPattern ?? this.assetPattern,
+      checkedInstalledVersion: checkedInstalledVersion ?? this.checkedInstalledVersion,
       lastChecked: lastChecked ?? this.lastChecked,
       createdAt: createdAt ?? this.createdAt,
     );
@@ -0,0 +1,23 @@
+import 'dart:io';
+import 'package:path/path.dart' as p;
+import 'package:xdg_directories/xdg_directories.dart' as xdg;
+import '../app_info.dart';
+
+/// Where Autonomix keeps its data. Resolved without plugins, the same way
+/// `path_provider` does on Linux, so the command line interface finds the
+/// same files without a Flutter engine.
+class AppPaths {
+  static Future<Directory>? _supportDirectory;
+
+  /// `$XDG_DATA_HOME/<application id>`, or the directory named after the
+  /// executable that older versions used, if that is the one that exists.
+  static Future<Directory> supportDirectory() => _supportDirectory ??= _findSupportDirectory();
+
+  static Future<Directory> _findSupportDirectory() async {
+    final directory = Directory(p.join(xdg.dataHome.path, applicationId));
+    if (await directory.exists()) return directory;
+    final legacy = Directory(p.join(xdg.dataHome.path, selfRepoName));
+    if (await legacy.exists()) return legacy;
+    return directory.create(recursive: true);
+  }
+}
@@ -0,0 +1,80 @@
+import 'dart:async';
+import 'dart:io';
+import 'package:http/http.dart' as http;
+
+/// What kind of failure an [AutonomixException] is, so the UI can word and
+/// mark it accordingly.
+enum ErrorKind {
+  /// The server could not be reached or the connection broke.
+  network,
+  rateLimited,
+
+  /// The repo or release asked for does not exist.
+  notFound,
+
+  /// The release has no package this system can install.
+  noCompatibleAsset,
+  installFailed,
+
+  /// The user dismissed the administrator password prompt.
+  authCancelled,
+
+  /// Something Autonomix can't do for this app or install type.
+  unsupported,
+  unknown;
+
+  String get title {
+    switch (this) {
+      case ErrorKind.network:
+        return 'Network error';
+      case ErrorKind.rateLimited:
+        return 'Rate limited';
+      case ErrorKind.notFound:
+        return 'Not found';
+      case ErrorKind.noCompatibleAsset:
+        return 'No compatible package';
+      case ErrorKind.installFailed:
+        return 'Install failed';
+      case ErrorKind.authCancelled:
+        return 'Authentication cancelled';
+      case ErrorKind.unsupported:
+        return 'Not supported';
+      case ErrorKind.unknown:
+        return 'Error';
+    }
+  }
+}
+
+/// A failure from the services, tagged with its [kind]. Lower level errors
+/// are wrapped with [from] on their way out of the install and refresh
+/// paths, keeping the original as [cause].
+class AutonomixException implements Exception {
+  final ErrorKind kind;
+  final String message;
+  final Object? cause;
+
+  AutonomixException(this.kind, this.message, {this.cause});
+
+  /// [error] as an [AutonomixException]. Connection problems become
+  /// [ErrorKind.network]; anything not recognised gets [fallback].
+  static AutonomixException from(Object error, {ErrorKind fallback = ErrorKind.unknown}) {
+    if (error is AutonomixException) return error;
+    final kind = error is SocketException ||
+            error is HttpException ||
+            error is TlsException ||
+            error is TimeoutException ||
+            error is http.ClientException
+        ? ErrorKind.network
+        : fallback;
+    return AutonomixException(kind, error.toString().replaceFirst(RegExp(r'^Exception: '), ''), cause: error);
+  }
+
+  static ErrorKind kindOf(Object error) => from(error).kind;
+
+  /// E.g. `Network error: Connection refused`; just the message when the
+  /// kind adds nothing.
+  String describe() => kind == ErrorKind.unknown ? message : '${kind.title}: $message';
+
+  @override
+  String toString() => message;
+}
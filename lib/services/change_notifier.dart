@@ -0,0 +1,5 @@
+/// Flutter's [ChangeNotifier] in the app, and a plain Dart stand-in in the
+/// command line interface, which runs without the Flutter engine.
+library;
+
+export 'change_notifier_stub.dart' if (dart.library.ui) 'package:flutter/foundation.dart' show ChangeNotifier;
@@ -0,0 +1,19 @@
+/// The parts of Flutter's `ChangeNotifier` the services use, for when
+/// `dart:ui` is not available; see change_notifier.dart.
+class ChangeNotifier {
+  final List<void Function()> _listeners = [];
+
+  bool get hasListeners => _listeners.isNotEmpty;
+
+  void addListener(void Function() listener) => _listeners.add(listener);
+
+  void removeListener(void Function() listener) => _listeners.remove(listener);
+
+  void notifyListeners() {
+    for (final listener in List.of(_listeners)) {
+      listener();
+    }
+  }
+
+  void dispose() => _listeners.clear();
+}
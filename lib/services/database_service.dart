@@ -1,23 +1,186 @@
 import 'dart:convert';
 import 'dart:io';
 import 'package:path/path.dart';
-import 'package:path_provider/path_provider.dart';
+import '../app_info.dart';
+import '../models/history_entry.dart';
+import '../models/install_type.dart';
+import '../models/release_channel.dart';
+import '../models/release_provider.dart';
 import '../models/tracked_app.dart';
+import 'app_paths.dart';
 
 class DatabaseService {
   File? _file;
+  File? _historyFile;
+  File? _settingsFile;
+  File? _etagFile;
 
-  DatabaseService();
+  /// Where the data files live; the user's data directory (see
+  /// [AppPaths.supportDirectory]) unless given, e.g. in tests.
+  final Directory? directory;
+
+  Future<void> _appsTail = Future.value();
+
+  DatabaseService({this.directory});
+
+  Future<Directory> get _configDir async => directory ?? await AppPaths.supportDirectory();
 
   Future<File> get _dbFile async {
     if (_file != null) return _file!;
-    final configDir = await getApplicationSupportDirectory();
+    final configDir = await _configDir;
     await Directory(configDir.path).create(recursive: true);
     _file = File(join(configDir.path, 'apps.json'));
     return _file!;
   }
 
+  Future<File> get _historyDbFile async {
+    if (_historyFile != null) return _historyFile!;
+    final configDir = await _configDir;
+    await Directory(configDir.path).create(recursive: true);
+    _historyFile = File(join(configDir.path, 'history.json'));
+    return _historyFile!;
+  }
+
+  Future<File> get _settingsDbFile async {
+    if (_settingsFile != null) return _settingsFile!;
+    final configDir = await _configDir;
+    await Directory(configDir.path).create(recursive: true);
+    _settingsFile = File(join(configDir.path, 'settings.json'));
+    return _settingsFile!;
+  }
+
+  Future<File> get _etagDbFile async {
+    if (_etagFile != null) return _etagFile!;
+    final configDir = await _configDir;
+    await Directory(configDir.path).create(recursive: true);
+    _etagFile = File(join(configDir.path, 'etags.json'));
+    return _etagFile!;
+  }
+
+  /// Cached API responses for conditional requests (see
+  /// `GitHubService.etags`). Unreadable caches are simply dropped.
+  Future<Map<String, dynamic>> getEtagCache() async {
+    final file = await _etagDbFile;
+    if (!await file.exists()) return {};
+    try {
+      final decoded = jsonDecode(await file.readAsString());
+      return decoded is Map<String, dynamic> ? decoded : {};
+    } catch (e) {
+      print('Ignoring unreadable ETag cache: $e');
+      return {};
+    }
+  }
+
+  Future<void> saveEtagCache(Map<String, dynamic> cache) async {
+    final file = await _etagDbFile;
+    await file.writeAsString(jsonEncode(cache));
+  }
+
+  /// All settings. A corrupt file (e.g. cut short by a crash) is moved
+  /// aside to `settings.json.corrupt` and the defaults are used instead.
+  Future<Map<String, dynamic>> getSettings() async {
+    final file = await _settingsDbFile;
+    if (!await file.exists()) return {};
+
+    try {
+      return decodeSettings(await file.readAsString());
+    } on FormatException catch (e) {
+      print('Warning: settings file is corrupt, using defaults: $e');
+      try {
+        await file.rename('${file.path}.corrupt');
+      } catch (_) {}
+      return {};
+    } catch (e) {
+      print('Error reading settings: $e');
+      return {};
+    }
+  }
+
+  /// Parses the settings file, throwing a [FormatException] unless it holds
+  /// a JSON object. An empty file means no settings.
+  static Map<String, dynamic> decodeSettings(String content) {
+    if (content.trim().isEmpty) return {};
+    final decoded = jsonDecode(content);
+    if (decoded is! Map<String, dynamic>) {
+      throw const FormatException('Settings are not a JSON object');
+    }
+    return decoded;
+  }
+
+  /// The value of [key] in [settings] if it has the expected type, else
+  /// [fallback]. Malformed values are logged rather than thrown.
+  static T settingOr<T>(Map<String, dynamic> settings, String key, T fallback) {
+    final value = settings[key];
+    if (value == null) return fallback;
+    if (value is T) return value;
+    print('Warning: ignoring malformed setting $key: $value');
+    return fallback;
+  }
+
+  Future<dynamic> getSetting(String key) async => (await getSettings())[key];
+
+  /// Typed read of [key] that falls back to [fallback] when the stored
+  /// value is malformed, and rewrites it so the warning isn't repeated.
+  Future<T> getSettingOr<T>(String key, T fallback) async {
+    final settings = await getSettings();
+    final value = settingOr(settings, key, fallback);
+    if (settings[key] != null && settings[key] is! T) await setSetting(key, fallback);
+    return value;
+  }
+
+  /// Stores [value] under [key]; a null value removes the setting.
+  Future<void> setSetting(String key, dynamic value) async {
+    final settings = await getSettings();
+    if (value == null) {
+      settings.remove(key);
+    } else {
+      settings[key] = value;
+    }
+    final file = await _settingsDbFile;
+    await file.writeAsString(jsonEncode(settings));
+  }
+
+  /// Settings key holding how many of the [_migrations] have been applied.
+  static const String schemaVersionKey = 'schema_version';
+
+  /// Upgrades of data written by older versions, in order. Only ever append
+  /// to this list; each step must also be safe to run on data that already
+  /// has it, since files from before versioning start at zero.
+  late final List<Future<void> Function()> _migrations = [
+    _addReleaseChannelSetting,
+  ];
+
+  int get schemaVersion => _migrations.length;
+
+  /// Applies the migrations the stored data hasn't had yet, recording each
+  /// one as it completes. Run on every start, before anything is read.
+  Future<void> migrate() async {
+    final stored = settingOr(await getSettings(), schemaVersionKey, 0);
+    if (stored > schemaVersion) {
+      print('Warning: data was written by a newer Autonomix (schema $stored, expected $schemaVersion)');
+      return;
+    }
+    for (var version = stored; version < schemaVersion; version++) {
+      await _migrations[version]();
+      await setSetting(schemaVersionKey, version + 1);
+    }
+  }
+
+  Future<void> _addReleaseChannelSetting() async {
+    final settings = await getSettings();
+    if (!settings.containsKey(ReleaseChannel.settingKey)) {
+      // Only stable releases were ever offered before channels existed
+      await setSetting(ReleaseChannel.settingKey, ReleaseChannel.stable.name);
+    }
+  }
+
+  /// Tracked apps, excluding any that are waiting out their undo window.
   Future<List<TrackedApp>> getAllApps() async {
+    final apps = await _getAllRows();
+    return apps.where((a) => a.deletedAt == null).toList();
+  }
+
+  Future<List<TrackedApp>> _getAllRows() async {
     final file = await _dbFile;
     if (!await file.exists()) return [];
 
@@ -34,18 +197,62 @@ class DatabaseService {
     }
   }
 
+  Future<TrackedApp?> getApp(int id) async {
+    final apps = await getAllApps();
+    for (final app in apps) {
+      if (app.id == id) return app;
+    }
+    return null;
+  }
+
+  /// Writes [apps] to a temporary file renamed over apps.json, so a crash
+  /// mid-write never leaves it half written.
   Future<void> _saveApps(List<TrackedApp> apps) async {
     final file = await _dbFile;
     final jsonList = apps.map((e) => e.toMap()).toList();
-    await file.writeAsString(jsonEncode(jsonList));
+    final temp = File('${file.path}.tmp');
+    await temp.writeAsString(jsonEncode(jsonList), flush: true);
+    await temp.rename(file.path);
   }
 
-  Future<int> addApp(String repoOwner, String repoName, String displayName) async {
-    final apps = await getAllApps();
-    
+  /// Runs [change] after the changes to apps.json queued before it. Each
+  /// change reads, edits and rewrites the whole file, so concurrent ones,
+  /// like overlapping update checks, would otherwise overwrite each other.
+  Future<T> _changeApps<T>(Future<T> Function() change) {
+    final result = _appsTail.then((_) => change());
+    _appsTail = result.then((_) {}, onError: (_) {});
+    return result;
+  }
+
+  /// Adds a tracked app. The same repo may be tracked more than once as
+  /// long as each entry follows its own [tagPrefix], e.g. the `cli-v` and
+  /// `gui-v` products of a monorepo.
+  /// [channel] is left null to follow the global release channel.
+  Future<int> addApp(
+    String repoOwner,
+    String repoName,
+    String displayName, {
+    String? tagPrefix,
+    ReleaseChannel? channel,
+    ReleaseProvider? provider,
+    String? host,
+  }) => _changeApps(() async {
+    final apps = await _getAllRows();
+    final prefix = (tagPrefix == null || tagPrefix.isEmpty) ? null : tagPrefix;
+    // GitHub is stored as null, like in entries from before other forges
+    final source = provider == ReleaseProvider.github ? null : provider;
+
     // Check for duplicates
-    if (apps.any((a) => a.repoOwner == repoOwner && a.repoName == repoName)) {
-      throw Exception('App already exists');
+    if (apps.any((a) =>
+        a.deletedAt == null &&
+        a.provider == source &&
+        a.host == host &&
+        a.repoOwner == repoOwner &&
+        a.repoName == repoName &&
+        (a.tagPrefix ?? '') == (prefix ?? ''))) {
+      throw Exception(prefix == null
+          ? 'App already exists'
+          : 'App already exists for tag prefix "$prefix"');
     }
 
     // Generate ID
@@ -56,27 +263,203 @@ class DatabaseService {
       repoOwner: repoOwner,
       repoName: repoName,
       displayName: displayName,
+      tagPrefix: prefix,
+      channel: channel,
+      provider: source,
+      host: host,
       createdAt: DateTime.now(),
     );
 
     apps.add(newApp);
     await _saveApps(apps);
     return id;
+  });
+
+  /// Tracks [app] (e.g. from a shared snippet) keeping its per-app settings.
+  Future<int> importApp(TrackedApp app) async {
+    final id = await addApp(
+      app.repoOwner,
+      app.repoName,
+      app.displayName,
+      tagPrefix: app.tagPrefix,
+      provider: app.provider,
+      host: app.host,
+    );
+    await updateApp(TrackedApp.fromMap({...app.toMap(), 'id': id}));
+    return id;
   }
 
-  Future<void> updateApp(TrackedApp app) async {
-    final apps = await getAllApps();
+  Future<void> updateApp(TrackedApp app) => _changeApps(() async {
+    final apps = await _getAllRows();
     final index = apps.indexWhere((a) => a.id == app.id);
     
     if (index != -1) {
       apps[index] = app;
       await _saveApps(apps);
     }
-  }
+  });
 
-  Future<void> deleteApp(int id) async {
-    final apps = await getAllApps();
+  /// Pins [id] at its installed version, or releases the pin.
+  Future<void> setPinned(int id, bool pinned) => _changeApps(() async {
+    final apps = await _getAllRows();
+    final index = apps.indexWhere((a) => a.id == id);
+    if (index == -1) return;
+    apps[index] = apps[index].copyWith(pinned: pinned);
+    await _saveApps(apps);
+  });
+
+  /// Stops flagging [version] of [id] as an update. Returns the updated
+  /// app, or null if it no longer exists.
+  Future<TrackedApp?> ignoreVersion(int id, String version) => _changeApps(() async {
+    final apps = await _getAllRows();
+    final index = apps.indexWhere((a) => a.id == id);
+    if (index == -1) return null;
+    final ignored = apps[index].ignoredVersions ?? const [];
+    if (!ignored.contains(version)) {
+      apps[index] = apps[index].copyWith(ignoredVersions: [...ignored, version]);
+      await _saveApps(apps);
+    }
+    return apps[index];
+  });
+
+  /// Forgets every version of [id] skipped with [ignoreVersion].
+  Future<void> clearIgnoredVersions(int id) => _changeApps(() async {
+    final apps = await _getAllRows();
+    final index = apps.indexWhere((a) => a.id == id);
+    if (index == -1) return;
+    apps[index] = TrackedApp.fromMap({...apps[index].toMap(), 'ignored_versions': null});
+    await _saveApps(apps);
+  });
+
+  /// Hides [id] from listings until it is restored or purged.
+  Future<void> softDeleteApp(int id) => _setDeletedAt(id, DateTime.now());
+
+  Future<void> restoreApp(int id) => _setDeletedAt(id, null);
+
+  Future<void> _setDeletedAt(int id, DateTime? deletedAt) => _changeApps(() async {
+    final apps = await _getAllRows();
+    final index = apps.indexWhere((a) => a.id == id);
+    if (index == -1) return;
+    apps[index] = TrackedApp.fromMap({
+      ...apps[index].toMap(),
+      'deleted_at': deletedAt?.toIso8601String(),
+    });
+    await _saveApps(apps);
+  });
+
+  /// Hard-deletes rows left soft-deleted, e.g. if the app quit during the
+  /// undo window.
+  Future<void> purgeDeletedApps() => _changeApps(() async {
+    final apps = await _getAllRows();
+    final purged = {for (final app in apps) if (app.deletedAt != null && app.id != null) app.id!};
+    if (purged.isEmpty) return;
+    apps.removeWhere((a) => a.deletedAt != null);
+    await _saveApps(apps);
+    await _dropHistory(purged);
+  });
+
+  /// Removes [id] and its history.
+  Future<void> deleteApp(int id) => _changeApps(() async {
+    final apps = await _getAllRows();
     apps.removeWhere((a) => a.id == id);
     await _saveApps(apps);
+    await _dropHistory({id});
+  });
+
+  Future<List<HistoryEntry>> _getAllHistory() async {
+    final file = await _historyDbFile;
+    if (!await file.exists()) return [];
+
+    try {
+      final content = await file.readAsString();
+      if (content.isEmpty) return [];
+
+      final List<dynamic> jsonList = jsonDecode(content);
+      return jsonList.map((e) => HistoryEntry.fromMap(e)).toList();
+    } catch (e) {
+      print('Error reading history: $e');
+      return [];
+    }
+  }
+
+  /// Most history entries kept per app; older ones are dropped as new ones
+  /// come in, since entries can carry long build logs and the whole file is
+  /// rewritten each time.
+  static const int maxHistoryPerApp = 50;
+
+  Future<void> addHistory(HistoryEntry entry) async {
+    final history = await _getAllHistory();
+    history.add(entry);
+    final own = history.where((e) => e.appId == entry.appId).toList()
+      ..sort((a, b) => b.timestamp.compareTo(a.timestamp));
+    if (own.length > maxHistoryPerApp) {
+      final dropped = own.sublist(maxHistoryPerApp).toSet();
+      history.removeWhere(dropped.contains);
+    }
+    await _saveHistory(history);
+  }
+
+  Future<void> _saveHistory(List<HistoryEntry> history) async {
+    final file = await _historyDbFile;
+    await file.writeAsString(jsonEncode(history.map((e) => e.toMap()).toList()));
+  }
+
+  /// Forgets the history of the apps in [ids], once they are gone for good.
+  Future<void> _dropHistory(Set<int> ids) async {
+    final history = await _getAllHistory();
+    final before = history.length;
+    history.removeWhere((e) => ids.contains(e.appId));
+    if (history.length != before) await _saveHistory(history);
+  }
+
+  /// History for one app, newest first.
+  Future<List<HistoryEntry>> getHistory(int appId) async {
+    final history = await _getAllHistory();
+    return history.where((e) => e.appId == appId).toList()
+      ..sort((a, b) => b.timestamp.compareTo(a.timestamp));
+  }
+
+  /// Makes sure Autonomix tracks itself. Safe to call on every startup: the
+  /// entry is only written when something actually changed, in which case
+  /// this returns true.
+  Future<bool> registerSelf(String version, InstallType? installType) async {
+    final apps = await getAllApps();
+    TrackedApp? existing;
+    for (final app in apps) {
+      if (app.isSelf) existing = app;
+    }
+
+    if (existing == null) {
+      final id = await addApp(selfRepoOwner, selfRepoName, 'Autonomix');
+      final created = await getApp(id);
+      await updateApp(reconcileSelf(created!, version, installType)!);
+      return true;
+    }
+
+    final updated = reconcileSelf(existing, version, installType);
+    if (updated == null) return false;
+    await updateApp(updated);
+    return true;
+  }
+
+  /// Returns the self entry with the running version and detected install
+  /// type applied, or null when nothing needs writing. The version always
+  /// follows the running binary, but a recorded install type is never
+  /// flipped and user-set preferences are left alone.
+  static TrackedApp? reconcileSelf(TrackedApp existing, String version, InstallType? installType) {
+    final versionChanged = existing.installedVersion == null ||
+        _stripV(existing.installedVersion!) != _stripV(version);
+    final typeMissing = existing.installType == null && installType != null;
+    if (!versionChanged && !typeMissing) return null;
+
+    return existing.copyWith(
+      installedVersion: versionChanged ? version : null,
+      installType: typeMissing ? installType : null,
+    );
+  }
+
+  static String _stripV(String version) {
+    final v = version.trim().toLowerCase();
+    return v.startsWith('v') ? v.substring(1) : v;
   }
 }
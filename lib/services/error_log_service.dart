@@ -0,0 +1,52 @@
+import 'dart:collection';
+import 'autonomix_exception.dart';
+
+class ErrorLogEntry {
+  final DateTime timestamp;
+  final String operation;
+  final String message;
+  final ErrorKind kind;
+
+  ErrorLogEntry({
+    required this.timestamp,
+    required this.operation,
+    required this.message,
+    this.kind = ErrorKind.unknown,
+  });
+
+  @override
+  String toString() => '[${timestamp.toIso8601String()}] $operation: $message';
+}
+
+/// Keeps the most recent errors in memory so they can be reviewed after the
+/// snackbar that reported them is gone.
+class ErrorLogService {
+  static const int defaultCapacity = 100;
+
+  final int capacity;
+  final ListQueue<ErrorLogEntry> _entries = ListQueue();
+
+  ErrorLogService({this.capacity = defaultCapacity});
+
+  /// Newest first.
+  List<ErrorLogEntry> get entries => _entries.toList().reversed.toList();
+
+  bool get isEmpty => _entries.isEmpty;
+
+  void record(String operation, Object error) {
+    _entries.addLast(ErrorLogEntry(
+      timestamp: DateTime.now(),
+      operation: operation,
+      message: error.toString(),
+      kind: AutonomixException.kindOf(error),
+    ));
+    while (_entries.length > capacity) {
+      _entries.removeFirst();
+    }
+  }
+
+  void clear() => _entries.clear();
+
+  /// Plain-text dump of the log, newest first.
+  String format() => entries.map((e) => e.toString()).join('\n');
+}
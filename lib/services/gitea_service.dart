@@ -0,0 +1,108 @@
+import 'dart:convert';
+import 'package:http/http.dart' as http;
+import '../models/release.dart';
+import 'github_service.dart';
+import 'release_source.dart';
+
+/// Releases of repos on a Gitea or Forgejo instance. Their v1 API mirrors
+/// GitHub's release format, so [Release.fromJson] reads it unchanged.
+class GiteaService implements ReleaseSource {
+  final String host;
+  final http.Client _client;
+
+  /// Request headers for a URL, such as the user agent and any custom
+  /// headers configured for the host (e.g. `Authorization: token ...`).
+  final Map<String, String> Function(Uri url) _headersFor;
+
+  GiteaService({
+    required this.host,
+    required http.Client client,
+    required Map<String, String> Function(Uri url) headersFor,
+  })  : _client = client,
+        _headersFor = headersFor;
+
+  Uri _repoUrl(String owner, String repo, [String path = '']) =>
+      Uri.parse('https://$host/api/v1/repos/$owner/$repo$path');
+
+  Future<http.Response> _get(Uri url) => _client.get(url, headers: _headersFor(url));
+
+  /// Whether [host] answers like a Gitea or Forgejo instance, for URLs of
+  /// hosts that are not a known public forge.
+  static Future<bool> isGitea(String host, http.Client client, Map<String, String> headers) async {
+    try {
+      final response = await client.get(Uri.parse('https://$host/api/v1/version'), headers: headers);
+      if (response.statusCode != 200) return false;
+      final json = jsonDecode(response.body);
+      return json is Map && json['version'] is String;
+    } catch (_) {
+      return false;
+    }
+  }
+
+  @override
+  Future<Release> getLatestRelease(String owner, String repo) async {
+    final response = await _get(_repoUrl(owner, repo, '/releases/latest'));
+
+    if (response.statusCode == 200) {
+      return Release.fromJson(jsonDecode(response.body));
+    } else if (response.statusCode == 404) {
+      throw ReleaseNotFoundException('No published release in $owner/$repo');
+    } else {
+      throw Exception('Failed to load latest release: ${response.statusCode}');
+    }
+  }
+
+  @override
+  Future<Release> getReleaseByTag(String owner, String repo, String tag) async {
+    final response = await _get(_repoUrl(owner, repo, '/releases/tags/${Uri.encodeComponent(tag)}'));
+
+    if (response.statusCode == 200) {
+      return Release.fromJson(jsonDecode(response.body));
+    } else if (response.statusCode == 404) {
+      throw ReleaseNotFoundException('No release tagged "$tag" in $owner/$repo');
+    } else {
+      throw Exception('Failed to load release $tag: ${response.statusCode}');
+    }
+  }
+
+  @override
+  Future<List<Release>> getReleases(String owner, String repo, {int perPage = 10, int page = 1}) async {
+    // Gitea calls the page size "limit"
+    final response = await _get(_repoUrl(owner, repo, '/releases?limit=$perPage&page=$page'));
+
+    if (response.statusCode == 200) {
+      final List<dynamic> list = jsonDecode(response.body);
+      return list.map((e) => Release.fromJson(e)).toList();
+    } else {
+      throw Exception('Failed to load releases: ${response.statusCode}');
+    }
+  }
+
+  @override
+  Future<Map<String, dynamic>> getRepository(String owner, String repo) async {
+    final response = await _get(_repoUrl(owner, repo));
+
+    if (response.statusCode == 200) {
+      return repositoryFromJson(jsonDecode(response.body) as Map<String, dynamic>);
+    } else {
+      throw Exception('Failed to load repository: ${response.statusCode}');
+    }
+  }
+
+  @override
+  ({String url, Map<String, String> headers}) downloadRequestFor(ReleaseAsset asset) {
+    return (url: asset.browserDownloadUrl, headers: _headersFor(Uri.parse(asset.browserDownloadUrl)));
+  }
+
+  /// Renames the few repository fields that differ from GitHub's.
+  static Map<String, dynamic> repositoryFromJson(Map<String, dynamic> json) {
+    final description = json['description'] as String?;
+    final website = json['website'] as String?;
+    return {
+      ...json,
+      'description': description == null || description.isEmpty ? null : description,
+      'stargazers_count': json['stars_count'],
+      'homepage': website,
+    };
+  }
+}
@@ -1,33 +1,399 @@
+import 'dart:async';
 import 'dart:convert';
 import 'package:http/http.dart' as http;
+import '../app_info.dart';
+import '../models/app_metadata.dart';
 import '../models/release.dart';
+import '../models/release_provider.dart';
+import '../models/tracked_app.dart';
+import 'autonomix_exception.dart';
+import 'gitea_service.dart';
+import 'gitlab_service.dart';
+import 'proxy_config.dart';
+import 'release_selector.dart';
+import 'release_source.dart';
 
-class GitHubService {
+/// Thrown when the API refuses a request because the rate limit is used up.
+class RateLimitException extends AutonomixException {
+  /// When the limit resets, if the server said.
+  final DateTime? resetAt;
+
+  RateLimitException(this.resetAt)
+      : super(
+          ErrorKind.rateLimited,
+          resetAt == null
+              ? 'GitHub API rate limit exceeded'
+              : 'GitHub API rate limit exceeded until ${resetAt.toLocal()}',
+        );
+}
+
+/// Thrown when a release the caller asked for by tag does not exist.
+class ReleaseNotFoundException extends AutonomixException {
+  ReleaseNotFoundException(String message) : super(ErrorKind.notFound, message);
+}
+
+class GitHubService implements ReleaseSource {
   static const String _baseUrl = 'https://api.github.com';
-  static const String _userAgent = 'Autonomix/0.3.4';
+  static const String _userAgent = 'Autonomix/$appVersion';
+
+  /// Settings key for the extra headers sent to each API host.
+  static const String headersSettingKey = 'provider_headers';
+
+  /// Extra headers keyed by host, for forges or proxies that need e.g. a
+  /// custom auth header. Loaded from settings on startup.
+  Map<String, Map<String, String>> customHeaders = {};
+
+  /// Settings key for [token].
+  static const String tokenSettingKey = 'github_token';
+
+  /// Personal access token sent to the GitHub API, for private repos and the
+  /// higher authenticated rate limit. Without one, requests are anonymous.
+  String? token;
+
+  http.Client _client;
+
+  /// The API quota as of the last response, when the server reported it.
+  ({int remaining, DateTime? resetAt})? rateLimit;
+
+  /// Validator and body of earlier responses, keyed by URL, so repeated
+  /// requests can be conditional. A 304 reply doesn't count against the
+  /// rate limit. Persisted by the caller between runs.
+  Map<String, ({String etag, String body})> etags = {};
+
+  GitHubService({http.Client? client}) : _client = client ?? http.Client();
+
+  /// Sends all further requests, including other forges', through [proxy],
+  /// or back to the environment's proxy settings when null.
+  void useProxy(ProxyConfig? proxy) {
+    _client.close();
+    _client = proxy?.createClient() ?? http.Client();
+    // They hold on to the old client
+    _sources.clear();
+  }
+
+  /// Only small, frequently polled responses are worth keeping around.
+  static bool _conditional(Uri url) =>
+      url.path.endsWith('/releases/latest') || RegExp(r'^/repos/[^/]+/[^/]+$').hasMatch(url.path);
+
+  Future<http.Response> _get(Uri url) async {
+    // Once the quota is used up, every further request would fail anyway
+    final limit = rateLimit;
+    if (limit != null && limit.remaining == 0 && url.host == Uri.parse(_baseUrl).host) {
+      final resetAt = limit.resetAt;
+      if (resetAt == null || resetAt.isAfter(DateTime.now())) throw RateLimitException(resetAt);
+    }
+
+    final key = url.toString();
+    final cached = _conditional(url) ? etags[key] : null;
+    final response = await _client.get(url, headers: {
+      ..._headersFor(url),
+      if (cached != null) 'If-None-Match': cached.etag,
+    });
+    rateLimit = rateLimitStatusFrom(response.headers) ?? rateLimit;
+    final limited = rateLimitFrom(response.statusCode, response.headers);
+    if (limited != null) throw limited;
+
+    if (response.statusCode == 304 && cached != null) {
+      return http.Response.bytes(utf8.encode(cached.body), 200,
+          headers: {'content-type': 'application/json; charset=utf-8'}, request: response.request);
+    }
+    final etag = response.headers['etag'];
+    if (response.statusCode == 200 && etag != null && _conditional(url)) {
+      etags[key] = (etag: etag, body: response.body);
+    }
+    return response;
+  }
+
+  static Map<String, ({String etag, String body})> etagsFromJson(dynamic json) {
+    if (json is! Map) return {};
+    return {
+      for (final e in json.entries)
+        if (e.key is String && e.value is Map && e.value['etag'] is String && e.value['body'] is String)
+          e.key as String: (etag: e.value['etag'] as String, body: e.value['body'] as String),
+    };
+  }
 
+  static Map<String, dynamic> etagsToJson(Map<String, ({String etag, String body})> etags) => {
+        for (final e in etags.entries) e.key: {'etag': e.value.etag, 'body': e.value.body},
+      };
+
+  /// The `x-ratelimit-remaining`/`x-ratelimit-reset` pair from [headers],
+  /// or null when the response didn't carry them.
+  static ({int remaining, DateTime? resetAt})? rateLimitStatusFrom(Map<String, String> headers) {
+    final remaining = int.tryParse(headers['x-ratelimit-remaining'] ?? '');
+    if (remaining == null) return null;
+    final reset = int.tryParse(headers['x-ratelimit-reset'] ?? '');
+    return (
+      remaining: remaining,
+      resetAt: reset == null ? null : DateTime.fromMillisecondsSinceEpoch(reset * 1000, isUtc: true),
+    );
+  }
+
+  /// A [RateLimitException] if a response with [statusCode] and [headers]
+  /// means the rate limit was hit, covering both the primary limit
+  /// (`x-ratelimit-remaining: 0`) and secondary limits (`retry-after`).
+  static RateLimitException? rateLimitFrom(int statusCode, Map<String, String> headers, {DateTime? now}) {
+    if (statusCode != 403 && statusCode != 429) return null;
+
+    final retryAfter = int.tryParse(headers['retry-after'] ?? '');
+    if (retryAfter != null) {
+      return RateLimitException((now ?? DateTime.now()).add(Duration(seconds: retryAfter)));
+    }
+    if (headers['x-ratelimit-remaining'] != '0') return null;
+    final reset = int.tryParse(headers['x-ratelimit-reset'] ?? '');
+    return RateLimitException(
+      reset == null ? null : DateTime.fromMillisecondsSinceEpoch(reset * 1000, isUtc: true),
+    );
+  }
+
+  Map<String, String> _headersFor(Uri url) => {
+        'User-Agent': _userAgent,
+        if (token != null && url.host == Uri.parse(_baseUrl).host) 'Authorization': 'Bearer $token',
+        ...?customHeaders[url.host],
+      };
+
+  /// The token to use: `GITHUB_TOKEN` from [environment] wins over the
+  /// stored setting. Blank values count as no token.
+  static String? tokenFrom(Map<String, dynamic> settings, Map<String, String> environment) {
+    for (final candidate in [environment['GITHUB_TOKEN'], settings[tokenSettingKey]]) {
+      if (candidate is String && candidate.trim().isNotEmpty) return candidate.trim();
+    }
+    return null;
+  }
+
+  /// Where and how to download [asset]. With a token, the API endpoint is
+  /// used so assets of private repos work too; otherwise the public URL is
+  /// fetched with the host's custom headers but never the token.
+  @override
+  ({String url, Map<String, String> headers}) downloadRequestFor(ReleaseAsset asset) {
+    final apiUrl = asset.apiUrl;
+    if (token == null || apiUrl == null) {
+      final headers = _headersFor(Uri.parse(asset.browserDownloadUrl))..remove('Authorization');
+      return (url: asset.browserDownloadUrl, headers: headers);
+    }
+    return (
+      url: apiUrl,
+      headers: {..._headersFor(Uri.parse(apiUrl)), 'Accept': 'application/octet-stream'},
+    );
+  }
+
+  /// Reads the `provider_headers` setting, ignoring malformed entries.
+  static Map<String, Map<String, String>> headersFromSetting(dynamic value) {
+    if (value is! Map) return {};
+    final result = <String, Map<String, String>>{};
+    value.forEach((host, headers) {
+      if (host is! String || headers is! Map) return;
+      result[host] = {
+        for (final e in headers.entries)
+          if (e.key is String && e.value is String) e.key as String: e.value as String,
+      };
+    });
+    return result;
+  }
+
+  /// Parses one `Name: Value` header per line, skipping blank lines.
+  static Map<String, String> parseHeaderLines(String text) {
+    final headers = <String, String>{};
+    for (final line in text.split('\n')) {
+      if (line.trim().isEmpty) continue;
+      final colon = line.indexOf(':');
+      if (colon <= 0) throw FormatException('Expected "Name: Value"', line);
+      final name = line.substring(0, colon).trim();
+      if (name.isEmpty || name.contains(' ')) throw FormatException('Invalid header name', line);
+      headers[name] = line.substring(colon + 1).trim();
+    }
+    return headers;
+  }
+
+  static String formatHeaderLines(Map<String, String> headers) =>
+      headers.entries.map((e) => '${e.key}: ${e.value}').join('\n');
+
+  @override
   Future<Release> getLatestRelease(String owner, String repo) async {
     final url = Uri.parse('$_baseUrl/repos/$owner/$repo/releases/latest');
     
-    final response = await http.get(
-      url,
-      headers: {'User-Agent': _userAgent},
-    );
+    final response = await _get(url);
 
     if (response.statusCode == 200) {
-      return Release.fromJson(jsonDecode(response.body));
+      final release = Release.fromJson(jsonDecode(response.body));
+      if (!ReleaseSelector.isSelectable(release)) {
+        throw Exception('Latest release ${release.tagName} is a draft');
+      }
+      return release;
     } else {
       throw Exception('Failed to load latest release: ${response.statusCode}');
     }
   }
 
-  Future<List<Release>> getReleases(String owner, String repo) async {
-    final url = Uri.parse('$_baseUrl/repos/$owner/$repo/releases?per_page=10');
-    
-    final response = await http.get(
-      url,
-      headers: {'User-Agent': _userAgent},
+  /// The release tagged [tag], e.g. to reinstall a recorded version.
+  @override
+  Future<Release> getReleaseByTag(String owner, String repo, String tag) async {
+    final url = Uri.parse('$_baseUrl/repos/$owner/$repo/releases/tags/${Uri.encodeComponent(tag)}');
+
+    final response = await _get(url);
+
+    if (response.statusCode == 200) {
+      return Release.fromJson(jsonDecode(response.body));
+    } else if (response.statusCode == 404) {
+      throw ReleaseNotFoundException('No release tagged "$tag" in $owner/$repo');
+    } else {
+      throw Exception('Failed to load release $tag: ${response.statusCode}');
+    }
+  }
+
+  /// How long [getLatestReleaseFor] reuses a fetched release, so one user
+  /// action (check, pick a package, install) hits the API once.
+  static const Duration releaseCacheTtl = Duration(minutes: 2);
+
+  final Map<String, ({DateTime fetchedAt, Future<Release> release})> _latestCache = {};
+
+  final Map<String, Future<AppMetadata>> _metadataCache = {};
+
+  /// Forgets cached releases, e.g. when the user explicitly refreshes.
+  void invalidateReleaseCache() {
+    _latestCache.clear();
+    _metadataCache.clear();
+  }
+
+  /// Stars, description and latest release details for [app]. Only
+  /// fetched on demand (e.g. by the details view) and cached until the next
+  /// explicit refresh.
+  Future<AppMetadata> getAppMetadata(TrackedApp app) {
+    final key = app.trackingKey;
+    final cached = _metadataCache[key];
+    if (cached != null) return cached;
+
+    final metadata = _fetchAppMetadata(app);
+    _metadataCache[key] = metadata;
+    metadata.then((_) {}, onError: (Object _) {
+      if (identical(_metadataCache[key], metadata)) _metadataCache.remove(key);
+    });
+    return metadata;
+  }
+
+  final Map<String, ReleaseSource> _sources = {};
+
+  /// The API serving [app]'s releases: this service for GitHub apps.
+  ReleaseSource sourceFor(TrackedApp app) => sourceOf(app.source, host: app.host);
+
+  /// The API of [provider] at [host], or at its public instance.
+  ReleaseSource sourceOf(ReleaseProvider provider, {String? host}) {
+    if (provider == ReleaseProvider.github) return this;
+    final instance = host ?? provider.host;
+    return _sources.putIfAbsent(
+      '${provider.name} $instance',
+      () => provider == ReleaseProvider.gitlab
+          ? GitLabService(host: instance, client: _client, headersFor: _headersFor)
+          : GiteaService(host: instance, client: _client, headersFor: _headersFor),
     );
+  }
+
+  /// Whether [host] runs Gitea or Forgejo, for repo URLs of unknown hosts.
+  Future<bool> isGiteaHost(String host) =>
+      GiteaService.isGitea(host, _client, _headersFor(Uri.https(host)));
+
+  /// The forge running on [host], which is not a known public instance.
+  /// Throws when it is neither Gitea/Forgejo nor named like GitLab.
+  Future<ReleaseProvider> detectProvider(String host) async {
+    if (await isGiteaHost(host)) return ReleaseProvider.gitea;
+    if (host.contains('gitlab')) return ReleaseProvider.gitlab;
+    throw Exception('$host does not look like a GitHub, GitLab, Gitea or Forgejo instance');
+  }
+
+  Future<AppMetadata> _fetchAppMetadata(TrackedApp app) async {
+    final repository = await sourceFor(app).getRepository(app.repoOwner, app.repoName);
+    Release? latest;
+    try {
+      latest = await getLatestReleaseFor(app);
+    } catch (_) {
+      // Repos without releases still have useful metadata
+    }
+    return AppMetadata.fromJson(repository, latest);
+  }
+
+  /// Latest release for [app], honouring its tag prefix filter and release
+  /// channel. Results are cached for [releaseCacheTtl]; concurrent callers
+  /// share one request.
+  Future<Release> getLatestReleaseFor(TrackedApp app) {
+    final key = '${app.trackingKey} ${ReleaseSelector.effectiveChannel(app).name}';
+    final cached = _latestCache[key];
+    if (cached != null && DateTime.now().difference(cached.fetchedAt) < releaseCacheTtl) {
+      return cached.release;
+    }
+
+    final release = _fetchLatestReleaseFor(app);
+    _latestCache[key] = (fetchedAt: DateTime.now(), release: release);
+    // Failures are not cached
+    release.then((_) {}, onError: (Object _) {
+      if (identical(_latestCache[key]?.release, release)) _latestCache.remove(key);
+    });
+    return release;
+  }
+
+  Future<Release> _fetchLatestReleaseFor(TrackedApp app) async {
+    final source = sourceFor(app);
+    if (!ReleaseSelector.needsReleaseList(app)) {
+      return source.getLatestRelease(app.repoOwner, app.repoName);
+    }
+
+    final result = await getReleasesPaginated(app.repoOwner, app.repoName, source: source);
+    final release = ReleaseSelector.selectLatest(result.releases, app);
+    if (release == null) {
+      final searched = result.truncated ? ' in the newest ${result.releases.length} releases' : '';
+      final prefix = app.tagPrefix;
+      if (prefix == null || prefix.isEmpty) throw Exception('No published release$searched');
+      throw Exception('No release with a tag starting with "$prefix"$searched');
+    }
+    return release;
+  }
+
+  /// Walks the paginated release list until [maxReleases] are collected or
+  /// [deadline] passes, returning whatever was gathered so far instead of
+  /// failing. When `truncated` is set there may be more, starting at
+  /// `nextPage`. Pages come from [source], GitHub by default.
+  Future<({List<Release> releases, bool truncated, int nextPage})> getReleasesPaginated(
+    String owner,
+    String repo, {
+    ReleaseSource? source,
+    int startPage = 1,
+    int maxReleases = 300,
+    Duration deadline = const Duration(seconds: 15),
+  }) async {
+    const perPage = 100;
+    final stopwatch = Stopwatch()..start();
+    final releases = <Release>[];
+    var page = startPage;
+
+    while (true) {
+      final remaining = deadline - stopwatch.elapsed;
+      if (remaining <= Duration.zero) {
+        return (releases: releases, truncated: true, nextPage: page);
+      }
+
+      final List<Release> batch;
+      try {
+        batch = await (source ?? this).getReleases(owner, repo, perPage: perPage, page: page).timeout(remaining);
+      } on TimeoutException {
+        if (releases.isEmpty) rethrow;
+        return (releases: releases, truncated: true, nextPage: page);
+      }
+
+      releases.addAll(batch);
+      page++;
+      if (batch.length < perPage) {
+        return (releases: releases, truncated: false, nextPage: page);
+      }
+      if (releases.length >= maxReleases) {
+        return (releases: releases, truncated: true, nextPage: page);
+      }
+    }
+  }
+
+  @override
+  Future<List<Release>> getReleases(String owner, String repo, {int perPage = 10, int page = 1}) async {
+    final url = Uri.parse('$_baseUrl/repos/$owner/$repo/releases?per_page=$perPage&page=$page');
+    
+    final response = await _get(url);
 
     if (response.statusCode == 200) {
       final List<dynamic> list = jsonDecode(response.body);
@@ -37,13 +403,67 @@ class GitHubService {
     }
   }
 
+  /// First lines of the commit messages between [base] and [head], newest
+  /// first. Used as a changelog for releases published without notes.
+  Future<List<String>> compareCommits(String owner, String repo, String base, String head) async {
+    final url = Uri.parse('$_baseUrl/repos/$owner/$repo/compare/'
+        '${Uri.encodeComponent(base)}...${Uri.encodeComponent(head)}');
+
+    final response = await _get(url);
+
+    if (response.statusCode == 200) {
+      return parseCompareCommits(jsonDecode(response.body) as Map<String, dynamic>);
+    } else {
+      throw Exception('Failed to compare $base...$head: ${response.statusCode}');
+    }
+  }
+
+  static List<String> parseCompareCommits(Map<String, dynamic> json) {
+    final commits = json['commits'] as List<dynamic>? ?? [];
+    return commits
+        .map((c) => ((c as Map<String, dynamic>)['commit']?['message'] as String? ?? '').split('\n').first.trim())
+        .where((message) => message.isNotEmpty)
+        .toList()
+        .reversed
+        .toList();
+  }
+
+  /// The notes for [release], or when it has none, a list of the commits
+  /// since [app]'s installed version. Null when neither is available.
+  Future<({String text, bool generated})?> changelogFor(TrackedApp app, Release release) async {
+    final body = release.body?.trim();
+    if (body != null && body.isNotEmpty) return (text: body, generated: false);
+
+    final installed = app.installedVersion;
+    if (installed == null || installed == release.tagName) return null;
+    if (app.source != ReleaseProvider.github) return null;
+    final commits = await compareCommits(app.repoOwner, app.repoName, installed, release.tagName);
+    if (commits.isEmpty) return null;
+    return (text: commits.map((m) => '- $m').join('\n'), generated: true);
+  }
+
+  /// Hosts worth timing in the connection test: the API, the CDN release
+  /// assets are served from, and any host with custom headers configured.
+  List<Uri> get diagnosticEndpoints => [
+        Uri.parse(_baseUrl),
+        Uri.parse('https://objects.githubusercontent.com'),
+        for (final host in customHeaders.keys)
+          if (host != Uri.parse(_baseUrl).host) Uri.https(host),
+      ];
+
+  /// Round-trip time of a HEAD request to [url]. Any HTTP status counts as
+  /// a response; only network failures and timeouts throw.
+  Future<Duration> measureRoundTrip(Uri url, {Duration timeout = const Duration(seconds: 10)}) async {
+    final stopwatch = Stopwatch()..start();
+    await _client.head(url, headers: _headersFor(url)).timeout(timeout);
+    return stopwatch.elapsed;
+  }
+
+  @override
   Future<Map<String, dynamic>> getRepository(String owner, String repo) async {
     final url = Uri.parse('$_baseUrl/repos/$owner/$repo');
     
-    final response = await http.get(
-      url,
-      headers: {'User-Agent': _userAgent},
-    );
+    final response = await _get(url);
 
     if (response.statusCode == 200) {
       return jsonDecode(response.body) as Map<String, dynamic>;
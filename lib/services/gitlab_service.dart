@@ -0,0 +1,121 @@
+import 'dart:convert';
+import 'package:http/http.dart' as http;
+import '../models/release.dart';
+import 'github_service.dart';
+import 'release_source.dart';
+
+/// Releases of projects on a GitLab instance, via its v4 REST API.
+class GitLabService implements ReleaseSource {
+  final String host;
+  final http.Client _client;
+
+  /// Request headers for a URL, such as the user agent and any custom
+  /// headers configured for the host (e.g. a `PRIVATE-TOKEN`).
+  final Map<String, String> Function(Uri url) _headersFor;
+
+  GitLabService({
+    this.host = 'gitlab.com',
+    required http.Client client,
+    required Map<String, String> Function(Uri url) headersFor,
+  })  : _client = client,
+        _headersFor = headersFor;
+
+  /// API URL for [path] under the project; GitLab wants the full project
+  /// path, groups included, as one encoded id.
+  Uri _projectUrl(String owner, String repo, [String path = '']) =>
+      Uri.parse('https://$host/api/v4/projects/${Uri.encodeComponent('$owner/$repo')}$path');
+
+  Future<http.Response> _get(Uri url) => _client.get(url, headers: _headersFor(url));
+
+  @override
+  Future<Release> getLatestRelease(String owner, String repo) async {
+    final response = await _get(_projectUrl(owner, repo, '/releases/permalink/latest'));
+
+    if (response.statusCode == 200) {
+      return releaseFromJson(jsonDecode(response.body) as Map<String, dynamic>);
+    } else if (response.statusCode == 404) {
+      throw ReleaseNotFoundException('No published release in $owner/$repo');
+    } else {
+      throw Exception('Failed to load latest release: ${response.statusCode}');
+    }
+  }
+
+  @override
+  Future<Release> getReleaseByTag(String owner, String repo, String tag) async {
+    final response = await _get(_projectUrl(owner, repo, '/releases/${Uri.encodeComponent(tag)}'));
+
+    if (response.statusCode == 200) {
+      return releaseFromJson(jsonDecode(response.body) as Map<String, dynamic>);
+    } else if (response.statusCode == 404) {
+      throw ReleaseNotFoundException('No release tagged "$tag" in $owner/$repo');
+    } else {
+      throw Exception('Failed to load release $tag: ${response.statusCode}');
+    }
+  }
+
+  @override
+  Future<List<Release>> getReleases(String owner, String repo, {int perPage = 10, int page = 1}) async {
+    final response = await _get(_projectUrl(owner, repo, '/releases?per_page=$perPage&page=$page'));
+
+    if (response.statusCode == 200) {
+      final List<dynamic> list = jsonDecode(response.body);
+      return list.map((e) => releaseFromJson(e as Map<String, dynamic>)).toList();
+    } else {
+      throw Exception('Failed to load releases: ${response.statusCode}');
+    }
+  }
+
+  @override
+  Future<Map<String, dynamic>> getRepository(String owner, String repo) async {
+    final response = await _get(_projectUrl(owner, repo));
+
+    if (response.statusCode == 200) {
+      return repositoryFromJson(jsonDecode(response.body) as Map<String, dynamic>);
+    } else {
+      throw Exception('Failed to load repository: ${response.statusCode}');
+    }
+  }
+
+  @override
+  ({String url, Map<String, String> headers}) downloadRequestFor(ReleaseAsset asset) {
+    return (url: asset.browserDownloadUrl, headers: _headersFor(Uri.parse(asset.browserDownloadUrl)));
+  }
+
+  /// Converts a GitLab release. Its asset links carry no size or content
+  /// type, and releases with a future release date count as pre-releases.
+  static Release releaseFromJson(Map<String, dynamic> json) {
+    final links = (json['assets'] as Map<String, dynamic>?)?['links'] as List<dynamic>? ?? [];
+    final released = json['released_at'] as String?;
+    return Release(
+      tagName: json['tag_name'] as String,
+      name: json['name'] as String?,
+      body: json['description'] as String?,
+      publishedAt: released != null ? DateTime.parse(released) : null,
+      prerelease: json['upcoming_release'] as bool? ?? false,
+      draft: false,
+      assets: [
+        for (final link in links.cast<Map<String, dynamic>>())
+          ReleaseAsset(
+            name: link['name'] as String,
+            browserDownloadUrl: (link['direct_asset_url'] ?? link['url']) as String,
+            contentType: 'application/octet-stream',
+            size: 0,
+          ),
+      ],
+    );
+  }
+
+  /// Maps a GitLab project onto the fields of a GitHub repository that the
+  /// app reads.
+  static Map<String, dynamic> repositoryFromJson(Map<String, dynamic> json) {
+    final namespace = json['namespace'] as Map<String, dynamic>?;
+    final description = json['description'] as String?;
+    return {
+      'name': json['path'],
+      'description': description == null || description.isEmpty ? null : description,
+      'owner': {'login': namespace?['full_path']},
+      'stargazers_count': json['star_count'],
+      'html_url': json['web_url'],
+    };
+  }
+}
@@ -0,0 +1,825 @@
+import 'dart:io';
+import 'dart:math';
+import '../models/history_entry.dart';
+import '../models/install_type.dart';
+import '../models/release.dart';
+import '../models/tracked_app.dart';
+import 'autonomix_exception.dart';
+import 'database_service.dart';
+import 'github_service.dart';
+import 'install_tracker.dart';
+import 'installer_service.dart';
+import 'release_selector.dart';
+import 'release_source.dart';
+
+/// A release and asset picked for installing, with why that asset won.
+typedef InstallChoice = ({Release release, ReleaseAsset asset, InstallType type, String reason});
+
+/// Runs installs end to end (download, install, database, history) without
+/// any UI, so bulk operations can share one path with the details sheet.
+/// Queued operations run one at a time.
+class InstallManager {
+  final DatabaseService db;
+  final GitHubService gh;
+  final InstallerService installer;
+  final InstallTracker tracker;
+
+  Future<void> _queueTail = Future.value();
+
+  /// [hasCompatibleAsset] answers keyed by app id, remembered for the latest
+  /// version they were computed against.
+  final Map<int, ({String? version, bool compatible})> _compatibility = {};
+
+  /// Longest pause for a rate limit before [installAll] gives up on an app.
+  Duration maxRateLimitWait = const Duration(minutes: 30);
+
+  /// How often [installAll] waits out a rate limit for the same app before
+  /// recording it as failed, e.g. when the server never says when the limit
+  /// resets.
+  int maxRateLimitRetries = 3;
+
+  /// How much of a source build's output is kept in the install history.
+  static const int maxBuildLogLines = 200;
+
+  /// Longest release notes kept with an app; see [releaseNotesOf].
+  static const int maxReleaseNotesLength = 20000;
+
+  static const String refreshConcurrencySettingKey = 'refresh_concurrency';
+
+  /// Most update checks [refreshAll] runs at once. GitHub's secondary rate
+  /// limits ban clients that fire many requests concurrently.
+  int refreshConcurrency = 5;
+
+  InstallManager(this.db, this.gh, this.installer, this.tracker);
+
+  /// Runs [operation] after everything queued before it has finished.
+  Future<T> enqueue<T>(Future<T> Function() operation) {
+    final result = _queueTail.then((_) => operation());
+    _queueTail = result.then((_) {}, onError: (_) {});
+    return result;
+  }
+
+  /// Picks the latest release (or the one tagged [tag]) and best asset for
+  /// [app] without asking. When the latest release has nothing installable
+  /// on this host, an older one is picked instead; see [fallback]. Returns
+  /// null when none has.
+  Future<InstallChoice?> resolve(
+    TrackedApp app, {
+    String? tag,
+  }) async {
+    final release = tag != null
+        ? await gh.sourceFor(app).getReleaseByTag(app.repoOwner, app.repoName, tag)
+        : await gh.getLatestReleaseFor(app);
+    final available = await installer.detectAvailableInstallers();
+    final choice = choose(app, release, available);
+    if (choice != null || tag != null) return choice;
+    return fallback(app, release, available);
+  }
+
+  /// How many releases [fallback] looks through.
+  static const int maxFallbackReleases = 30;
+
+  /// The newest release before [latest] with something installable for
+  /// [app], for when [latest] itself has nothing, e.g. because its Linux
+  /// builds failed. The choice's reason says that it is a fallback.
+  Future<InstallChoice?> fallback(TrackedApp app, Release latest, Set<InstallType> available) async {
+    final releases = await gh.sourceFor(app).getReleases(app.repoOwner, app.repoName, perPage: maxFallbackReleases);
+    for (final release in ReleaseSelector.olderThan(releases, latest, app)) {
+      final choice = choose(app, release, available);
+      if (choice == null) continue;
+      print('${app.displayName} ${latest.tagName} has no compatible package, falling back to ${release.tagName}');
+      return (
+        release: choice.release,
+        asset: choice.asset,
+        type: choice.type,
+        reason: fallbackReason(latest.tagName, choice.reason),
+      );
+    }
+    return null;
+  }
+
+  /// [reason] for an asset from an older release, noting that [latestTag]
+  /// was passed over.
+  static String fallbackReason(String latestTag, String reason) =>
+      'fell back from $latestTag, which has no compatible package; $reason';
+
+  /// The asset [resolve] picks from [release] for [app], given the
+  /// [available] install types. Also used to preview settings before they
+  /// are saved.
+  InstallChoice? choose(TrackedApp app, Release release, Set<InstallType> available) {
+    final signature = app.preferredAssetSignature;
+    if (signature != null) {
+      final found = InstallerService.findBySignature(release.assets, signature, release.tagName);
+      // A pattern set since the asset was picked wins over it
+      final preferred =
+          found != null && InstallerService.matchesAssetPattern(found.name, app.assetPattern) ? found : null;
+      final type = preferred == null ? null : installer.identifyAssetType(preferred.name);
+      if (preferred != null && type != null && available.contains(type)) {
+        return (release: release, asset: preferred, type: type, reason: 'same asset as picked before ($signature)');
+      }
+    }
+
+    final best = installer.findBestAsset(
+      release.assets,
+      available,
+      include: app.assetIncludeKeywords,
+      exclude: app.assetExcludeKeywords,
+      pattern: app.assetPattern,
+    );
+    if (best == null) return null;
+    return (release: release, asset: best.asset, type: best.type, reason: best.reason);
+  }
+
+  /// The best ranked asset of each type in [release] for [app], filtered
+  /// like [choose], for the package chooser. Types this host can't install
+  /// are included so they can still be downloaded by hand.
+  Map<InstallType, ReleaseAsset> candidatesFor(TrackedApp app, Release release, {String? hostArch}) {
+    final candidates = <InstallType, ReleaseAsset>{};
+    final ranked = installer.rankAssets(
+      release.assets,
+      InstallType.values.toSet(),
+      hostArch: hostArch,
+      include: app.assetIncludeKeywords,
+      exclude: app.assetExcludeKeywords,
+      pattern: app.assetPattern,
+    );
+    for (final entry in ranked) {
+      candidates.putIfAbsent(entry.type, () => entry.asset);
+    }
+    return candidates;
+  }
+
+  /// Fetches the latest release for [app] and saves it. Also notices when
+  /// the installed release has been deleted upstream. Returns the saved app.
+  /// Failures are thrown as [AutonomixException]s.
+  Future<TrackedApp> refresh(TrackedApp app) async {
+    try {
+      return await _refresh(app);
+    } catch (e, stackTrace) {
+      Error.throwWithStackTrace(AutonomixException.from(e), stackTrace);
+    }
+  }
+
+  Future<TrackedApp> _refresh(TrackedApp app) async {
+    final release = await gh.getLatestReleaseFor(app);
+    final installed = app.installedVersion;
+    var unavailable = app.installedUnavailable;
+    var checked = installed;
+    if (installed == null || installed == release.tagName || app.isSelf) {
+      unavailable = false;
+    } else if (needsAvailabilityCheck(app, release.tagName)) {
+      try {
+        await gh.sourceFor(app).getReleaseByTag(app.repoOwner, app.repoName, installed);
+        unavailable = false;
+      } on ReleaseNotFoundException {
+        unavailable = true;
+      } catch (e) {
+        // Not known either way: keep the last answer and ask again next time
+        print('Could not check whether ${app.displayName} $installed is still published: $e');
+        checked = null;
+      }
+    }
+
+    final updated = TrackedApp.fromMap({
+      ...app.toMap(),
+      'latest_release_notes': releaseNotesOf(release),
+      'checked_installed_version': checked,
+    }).copyWith(
+      latestVersion: release.tagName,
+      installedUnavailable: unavailable,
+      lastChecked: DateTime.now(),
+    );
+    await db.updateApp(updated);
+    return updated;
+  }
+
+  /// Whether [_refresh] has to ask again if [app]'s installed release is
+  /// still published: only when it or the latest release ([latestTag])
+  /// changed since the last check, so refreshes cost one request.
+  static bool needsAvailabilityCheck(TrackedApp app, String latestTag) =>
+      latestTag != app.latestVersion || app.installedVersion != app.checkedInstalledVersion;
+
+  /// [release]'s notes as stored with the app: null when there are none,
+  /// and cut short past [maxReleaseNotesLength] since the whole app list is
+  /// rewritten on every save.
+  static String? releaseNotesOf(Release release) {
+    final body = release.body?.trim();
+    if (body == null || body.isEmpty) return null;
+    if (body.length <= maxReleaseNotesLength) return body;
+    return '${body.substring(0, maxReleaseNotesLength)}\n\n…';
+  }
+
+  /// Refreshes [apps], at most [refreshConcurrency] at a time; only the
+  /// release fetches overlap, as [db] applies the results one at a time.
+  /// Completes once every started check has finished. A rate limit stops the checks
+  /// not yet started, which stay due; other failures go to [onError] and
+  /// the rest carry on.
+  Future<RefreshSummary> refreshAll(
+    List<TrackedApp> apps, {
+    void Function(TrackedApp app, Object error)? onError,
+  }) async {
+    final summary = RefreshSummary();
+    await runBounded(apps, refreshConcurrency, (app) async {
+      if (summary.rateLimit != null) return;
+      try {
+        summary.refreshed.add(await refresh(app));
+      } on RateLimitException catch (e) {
+        summary.rateLimit ??= e;
+      } catch (e) {
+        summary.failed[app] = e;
+        onError?.call(app, e);
+      }
+    });
+    return summary;
+  }
+
+  /// Runs [action] for each of [items] with no more than [concurrency]
+  /// running at once, in order of [items]. Completes when all are done.
+  static Future<void> runBounded<T>(List<T> items, int concurrency, Future<void> Function(T item) action) {
+    var next = 0;
+    Future<void> worker() async {
+      while (next < items.length) {
+        await action(items[next++]);
+      }
+    }
+
+    final workers = min(max(concurrency, 1), items.length);
+    return Future.wait([for (var i = 0; i < workers; i++) worker()]);
+  }
+
+  /// Whether [resolve] would find something installable for [app], so the
+  /// UI can disable Install up front. Returns null when the release could
+  /// not be fetched within [timeout].
+  Future<bool?> hasCompatibleAsset(
+    TrackedApp app, {
+    Duration timeout = const Duration(seconds: 10),
+  }) async {
+    final cached = _compatibility[app.id];
+    if (cached != null && cached.version == app.latestVersion) return cached.compatible;
+
+    try {
+      final choice = await resolve(app).timeout(timeout);
+      final compatible = choice != null;
+      if (app.id != null) {
+        _compatibility[app.id!] = (version: app.latestVersion, compatible: compatible);
+      }
+      return compatible;
+    } catch (_) {
+      return null;
+    }
+  }
+
+  /// Drops the remembered [hasCompatibleAsset] answer, e.g. after the app's
+  /// asset filters changed.
+  void forgetCompatibility(int id) => _compatibility.remove(id);
+
+  /// Downloads and installs [asset], then records the result. Returns the
+  /// updated app. [onProgress] follows the download (per part for split
+  /// assets); [onWarning] hears about installs that worked but may not
+  /// behave as expected.
+  ///
+  /// When a binary's name is already used by another tracked app,
+  /// [onNameCollision] is asked whether to install under the suggested
+  /// alternative instead; returning false cancels the install. Without it
+  /// the alternative is used and reported through [onWarning].
+  ///
+  /// Failures are recorded in the history before being rethrown as an
+  /// [AutonomixException], except for a dismissed password prompt.
+  Future<TrackedApp> install(
+    TrackedApp app,
+    Release release,
+    ReleaseAsset asset,
+    InstallType type, {
+    required String reason,
+    void Function(String status)? onStatus,
+    void Function(int received, int? total)? onProgress,
+    void Function(String warning)? onWarning,
+    Future<bool> Function(String name, TrackedApp owner, String alternative)? onNameCollision,
+  }) async {
+    try {
+      return await _install(
+        app,
+        release,
+        asset,
+        type,
+        reason: reason,
+        onStatus: onStatus,
+        onProgress: (received, total) {
+          if (app.id != null) tracker.reportProgress(app.id!, received, total);
+          onProgress?.call(received, total);
+        },
+        onWarning: onWarning,
+        onNameCollision: onNameCollision,
+      );
+    } catch (e, stackTrace) {
+      final error = AutonomixException.from(e, fallback: ErrorKind.installFailed);
+      // Dismissing the password prompt changed nothing worth a history entry
+      if (error.kind != ErrorKind.authCancelled) {
+        await _recordFailure(app, app.isInstalled ? 'update' : 'install', e, version: release.tagName, type: type);
+      }
+      Error.throwWithStackTrace(error, stackTrace);
+    }
+  }
+
+  /// Downloads [asset] of [release] for [app] the way [install] does, with
+  /// the source's credentials and headers, progress, and the checksum and
+  /// signature checks, without installing it. Used for Autonomix's own
+  /// updates, which are staged rather than installed.
+  Future<File> download(
+    TrackedApp app,
+    Release release,
+    ReleaseAsset asset, {
+    void Function(String status)? onStatus,
+    void Function(int received, int? total)? onProgress,
+  }) async {
+    try {
+      return await _download(app, release, asset, onStatus: onStatus, onProgress: (received, total) {
+        if (app.id != null) tracker.reportProgress(app.id!, received, total);
+        onProgress?.call(received, total);
+      });
+    } catch (e, stackTrace) {
+      Error.throwWithStackTrace(AutonomixException.from(e, fallback: ErrorKind.installFailed), stackTrace);
+    }
+  }
+
+  Future<File> _download(
+    TrackedApp app,
+    Release release,
+    ReleaseAsset asset, {
+    void Function(String status)? onStatus,
+    void Function(int received, int? total)? onProgress,
+  }) async {
+    final source = gh.sourceFor(app);
+    final split = InstallerService.splitPart(asset.name);
+    final File file;
+    if (split != null) {
+      final parts = InstallerService.partsOf(release.assets, asset);
+      // Each part is kept until it has been appended to the joined file
+      final sizes = parts.map((part) => part.size);
+      await installer.ensureFreeSpace(sizes.fold<int>(0, (sum, size) => sum + size) + sizes.fold<int>(0, max));
+      file = await installer.downloadParts(
+        parts,
+        split.joinedName,
+        onPart: (part, index) =>
+            onStatus?.call('Downloading ${part.name} (${index + 1} of ${parts.length})...'),
+        onProgress: onProgress,
+        control: tracker.downloadFor(app.id),
+        requestFor: source.downloadRequestFor,
+      );
+    } else {
+      await installer.ensureFreeSpace(asset.size);
+      onStatus?.call('Downloading ${asset.name}...');
+      final request = source.downloadRequestFor(asset);
+      file = await installer.downloadFile(
+        request.url,
+        asset.name,
+        expectedSize: asset.size,
+        onProgress: onProgress,
+        control: tracker.downloadFor(app.id),
+        headers: request.headers,
+      );
+    }
+    if (installer.verifyChecksums) {
+      await _verifyChecksum(file, split?.joinedName ?? asset.name, release, source, onStatus);
+    }
+    await _verifySignature(app, file, split?.joinedName ?? asset.name, release, source, onStatus);
+    return file;
+  }
+
+  Future<TrackedApp> _install(
+    TrackedApp app,
+    Release release,
+    ReleaseAsset asset,
+    InstallType type, {
+    required String reason,
+    void Function(String status)? onStatus,
+    void Function(int received, int? total)? onProgress,
+    void Function(String warning)? onWarning,
+    Future<bool> Function(String name, TrackedApp owner, String alternative)? onNameCollision,
+  }) async {
+    // Archives install their programs under their own names, checked one
+    // by one by the installer
+    final packed = InstallerService.isArchive(asset.name) || InstallerService.isZip(asset.name);
+    final binaryName = type == InstallType.binary && !packed
+        ? await _binaryNameFor(app, onNameCollision, onWarning)
+        : null;
+
+    final file = await _download(app, release, asset, onStatus: onStatus, onProgress: onProgress);
+
+    onStatus?.call(type == InstallType.source ? 'Building from source...' : 'Installing...');
+    // The end of a build log is where the interesting part is
+    final buildLog = <String>[];
+    final result = await installer.installPackage(
+      file,
+      type,
+      displayName: app.displayName,
+      binaryName: binaryName,
+      app: app,
+      tracked: packed ? await db.getAllApps() : const [],
+      onWarning: onWarning,
+      onOutput: (line) {
+        buildLog.add(line);
+        if (buildLog.length > maxBuildLogLines) buildLog.removeAt(0);
+        onStatus?.call('Building: $line');
+      },
+    );
+
+    var updatedApp = app.copyWith(
+      installedVersion: release.tagName,
+      installType: type,
+      launchCommand: result.launchCommand,
+      packageName: result.packageName,
+      installedAsset: asset.name,
+      installedUnavailable: false,
+      // Just downloaded, so known to be published
+      checkedInstalledVersion: release.tagName,
+      lastChecked: DateTime.now(),
+    );
+    updatedApp = TrackedApp.fromMap({
+      ...updatedApp.toMap(),
+      'installed_files': result.installedFiles,
+      'reported_version': await installer.detectInstalledVersion(updatedApp),
+    });
+    await db.updateApp(updatedApp);
+    if (result.failures.isNotEmpty) {
+      final skipped = result.failures.entries.map((e) => '${e.key} (${e.value})');
+      onWarning?.call('Some files were not installed: ${skipped.join(', ')}.');
+    }
+    if (type == InstallType.binary && result.launchCommand != null) {
+      final warning = await installer.pathShadowWarning(result.launchCommand!);
+      if (warning != null) onWarning?.call(warning);
+    }
+
+    // The hook's failure is reported, but the install itself succeeded
+    String? hookLog;
+    if (updatedApp.postUpdateCommand?.isNotEmpty ?? false) {
+      onStatus?.call('Running post-update command...');
+      final hook = await installer.runPostUpdateCommand(updatedApp, release.tagName);
+      hookLog = 'Post-update command exited with ${hook.exitCode}'
+          '${hook.output.isEmpty ? '' : ':\n${hook.output}'}';
+      if (hook.exitCode != 0) onWarning?.call('The post-update command failed (exit code ${hook.exitCode}).');
+    }
+
+    await db.addHistory(HistoryEntry(
+      appId: app.id!,
+      action: app.isInstalled ? 'update' : 'install',
+      version: release.tagName,
+      installType: type,
+      timestamp: DateTime.now(),
+      success: true,
+      details: [
+        installer.describeAssetSelection(
+          assets: release.assets,
+          chosen: asset,
+          reason: reason,
+          available: await installer.detectAvailableInstallers(),
+          include: app.assetIncludeKeywords,
+          exclude: app.assetExcludeKeywords,
+          pattern: app.assetPattern,
+        ),
+        if (buildLog.isNotEmpty) 'Build output:\n${buildLog.join('\n')}',
+        if (hookLog != null) hookLog,
+      ].join('\n'),
+    ));
+    return updatedApp;
+  }
+
+  /// Removes [app] from the system and clears its install record, keeping
+  /// the per-app settings. Returns the updated app. The outcome is recorded
+  /// in the history either way, unless the password prompt was dismissed.
+  Future<TrackedApp> uninstall(TrackedApp app) async {
+    try {
+      await installer.uninstallPackage(app);
+    } catch (e, stackTrace) {
+      final error = AutonomixException.from(e, fallback: ErrorKind.installFailed);
+      if (error.kind != ErrorKind.authCancelled) {
+        await _recordFailure(app, 'uninstall', e, version: app.installedVersion, type: app.installType);
+      }
+      Error.throwWithStackTrace(error, stackTrace);
+    }
+
+    final updated = TrackedApp.fromMap({
+      ...app.toMap(),
+      'installed_version': null,
+      'install_type': null,
+      'launch_command': null,
+      'package_name': null,
+      'installed_asset': null,
+      'installed_files': null,
+      'reported_version': null,
+      'installed_unavailable': false,
+      'pinned': false,
+    });
+    await db.updateApp(updated);
+    await db.addHistory(HistoryEntry(
+      appId: app.id!,
+      action: 'uninstall',
+      version: app.installedVersion,
+      installType: app.installType,
+      timestamp: DateTime.now(),
+      success: true,
+    ));
+    return updated;
+  }
+
+  /// Keeps failed operations in the history too, so it shows when things
+  /// broke. A history that can't be written doesn't hide [error].
+  Future<void> _recordFailure(
+    TrackedApp app,
+    String action,
+    Object error, {
+    String? version,
+    InstallType? type,
+  }) async {
+    if (app.id == null) return;
+    try {
+      await db.addHistory(HistoryEntry(
+        appId: app.id!,
+        action: action,
+        version: version,
+        installType: type,
+        timestamp: DateTime.now(),
+        success: false,
+        details: error.toString(),
+      ));
+    } catch (e) {
+      print('Could not record failed $action of ${app.displayName}: $e');
+    }
+  }
+
+  /// Compares [file] with the hash the release's checksum file lists for
+  /// [assetName], deleting it on a mismatch. Releases without a checksum
+  /// file, or whose file doesn't mention the asset, are let through.
+  Future<void> _verifyChecksum(
+    File file,
+    String assetName,
+    Release release,
+    ReleaseSource source,
+    void Function(String status)? onStatus,
+  ) async {
+    final checksums = InstallerService.checksumAssetFor(release.assets, assetName);
+    if (checksums == null) return;
+
+    onStatus?.call('Verifying checksum...');
+    final request = source.downloadRequestFor(checksums);
+    final listing = await installer.downloadFile(request.url, checksums.name, headers: request.headers);
+    final String content;
+    try {
+      content = await listing.readAsString();
+    } finally {
+      await listing.delete();
+    }
+
+    final expected = InstallerService.expectedSha256(content, assetName);
+    if (expected == null) return;
+    final actual = await installer.sha256Of(file);
+    if (actual != expected) {
+      await file.delete();
+      throw Exception('Checksum mismatch for $assetName: ${checksums.name} lists $expected, '
+          'the download is $actual. The file was deleted.');
+    }
+  }
+
+  /// Checks [file] against the release's detached signature when [app]
+  /// has a signing key, deleting it when the check fails or the release
+  /// has no signature the key can check.
+  Future<void> _verifySignature(
+    TrackedApp app,
+    File file,
+    String assetName,
+    Release release,
+    ReleaseSource source,
+    void Function(String status)? onStatus,
+  ) async {
+    final key = app.signingKey;
+    if (key == null) return;
+
+    final signatureAsset = InstallerService.signatureAssetFor(release.assets, assetName, key);
+    if (signatureAsset == null) {
+      await file.delete();
+      throw AutonomixException(ErrorKind.installFailed,
+          '${release.tagName} has no signature of $assetName to check. The file was deleted.');
+    }
+
+    onStatus?.call('Verifying signature...');
+    final request = source.downloadRequestFor(signatureAsset);
+    final signature = await installer.downloadFile(request.url, signatureAsset.name, headers: request.headers);
+    try {
+      await installer.verifySignature(file, signature, key);
+    } catch (_) {
+      await file.delete();
+      rethrow;
+    } finally {
+      await signature.delete();
+    }
+  }
+
+  /// Name for [app]'s binary in `~/.local/bin`. Updates keep the name the
+  /// first install used.
+  Future<String> _binaryNameFor(
+    TrackedApp app,
+    Future<bool> Function(String name, TrackedApp owner, String alternative)? onNameCollision,
+    void Function(String warning)? onWarning,
+  ) async {
+    final installed = app.launchCommand;
+    if (app.installType == InstallType.binary && installed != null) {
+      return installed.split('/').last;
+    }
+
+    final name = app.repoName.toLowerCase();
+    final owner = InstallerService.binaryNameOwner(await db.getAllApps(), app, name);
+    if (owner == null) return name;
+
+    final alternative = InstallerService.disambiguatedBinaryName(app);
+    if (onNameCollision == null) {
+      onWarning?.call('"$name" already belongs to ${owner.displayName}, installed as "$alternative" instead.');
+    } else if (!await onNameCollision(name, owner, alternative)) {
+      throw Exception('Install cancelled: "$name" already belongs to ${owner.displayName}');
+    }
+    return alternative;
+  }
+
+  /// Installs every app in [apps] through the queue, picking assets
+  /// automatically unless a choice was already made in [resolved] (keyed by
+  /// app id). Apps already being installed elsewhere are left alone.
+  ///
+  /// Hitting the API rate limit pauses the run until the limit resets
+  /// (reported through [onPaused]) and then retries the same app, unless
+  /// the wait would exceed [maxRateLimitWait] or the app was already
+  /// retried [maxRateLimitRetries] times; then it fails and the run moves
+  /// on.
+  Future<BulkInstallSummary> installAll(
+    List<TrackedApp> apps, {
+    Map<int, InstallChoice> resolved = const {},
+    void Function(DateTime resumeAt)? onPaused,
+  }) {
+    return enqueue(() async {
+      final summary = BulkInstallSummary();
+      for (final app in apps) {
+        if (!tracker.tryStart(app.id!)) {
+          summary.failed[app.displayName] = 'already being installed';
+          continue;
+        }
+        try {
+          var retries = 0;
+          while (true) {
+            try {
+              final choice = resolved[app.id] ?? await resolve(app);
+              if (choice == null) {
+                summary.skipped.add(app.displayName);
+              } else {
+                await install(app, choice.release, choice.asset, choice.type, reason: choice.reason);
+                summary.installed.add(app.displayName);
+              }
+              break;
+            } on RateLimitException catch (e) {
+              final wait = rateLimitWait(e.resetAt, DateTime.now());
+              if (wait > maxRateLimitWait || retries++ >= maxRateLimitRetries) rethrow;
+              onPaused?.call(DateTime.now().add(wait));
+              await Future.delayed(wait);
+            }
+          }
+        } catch (e) {
+          summary.failed[app.displayName] = e.toString();
+        } finally {
+          tracker.finish(app.id!);
+        }
+      }
+      return summary;
+    });
+  }
+
+  /// Re-probes the system for every app in [apps] and corrects recorded
+  /// install types, package names, launch paths and reported versions that
+  /// don't match what is really installed, e.g. after an import. Apps that
+  /// can't be found are reported but left untouched.
+  Future<RecheckSummary> recheckInstalls(List<TrackedApp> apps) async {
+    final summary = RecheckSummary();
+    for (final app in apps) {
+      try {
+        final found = await installer.probeInstallation(app);
+        if (found == null) {
+          summary.notFound.add(app.displayName);
+          continue;
+        }
+
+        final sameType = found.type == app.installType;
+        var corrected = TrackedApp.fromMap({
+          ...app.toMap(),
+          'install_type': found.type.name,
+          'package_name': found.packageName,
+          // Keep a hand-set command for package installs of the same type
+          'launch_command': found.launchCommand ?? (sameType ? app.launchCommand : null),
+        });
+        corrected = TrackedApp.fromMap({
+          ...corrected.toMap(),
+          'reported_version':
+              await installer.detectInstalledVersion(corrected) ?? found.version ?? app.reportedVersion,
+        });
+
+        final changes = describeCorrections(app, corrected);
+        if (changes.isEmpty) {
+          summary.confirmed.add(app.displayName);
+        } else {
+          await db.updateApp(corrected);
+          await db.addHistory(HistoryEntry(
+            appId: app.id!,
+            action: 'correct',
+            version: corrected.installedVersion,
+            installType: corrected.installType,
+            timestamp: DateTime.now(),
+            success: true,
+            details: changes.join('\n'),
+          ));
+          summary.corrected[app.displayName] = changes;
+        }
+      } catch (e) {
+        summary.failed[app.displayName] = e.toString();
+      }
+    }
+    return summary;
+  }
+
+  /// What [recheckInstalls] changed between [before] and [after], one entry
+  /// per field.
+  static List<String> describeCorrections(TrackedApp before, TrackedApp after) {
+    String show(Object? value) => value?.toString() ?? 'none';
+    return [
+      if (before.installType != after.installType)
+        'install type ${show(before.installType?.displayName)} -> ${show(after.installType?.displayName)}',
+      if (before.packageName != after.packageName)
+        'package ${show(before.packageName)} -> ${show(after.packageName)}',
+      if (before.launchCommand != after.launchCommand)
+        'launch command ${show(before.launchCommand)} -> ${show(after.launchCommand)}',
+      if (before.reportedVersion != after.reportedVersion)
+        'version ${show(before.reportedVersion)} -> ${show(after.reportedVersion)}',
+    ];
+  }
+
+  /// How long to wait for a rate limit resetting at [resetAt], with a small
+  /// margin for clock skew. Unknown reset times wait a minute.
+  static Duration rateLimitWait(DateTime? resetAt, DateTime now) {
+    if (resetAt == null) return const Duration(minutes: 1);
+    final wait = resetAt.difference(now) + const Duration(seconds: 5);
+    return wait.isNegative ? const Duration(seconds: 5) : wait;
+  }
+}
+
+class BulkInstallSummary {
+  final List<String> installed = [];
+
+  /// Apps whose latest release has no asset this host can install.
+  final List<String> skipped = [];
+  final Map<String, String> failed = {};
+
+  @override
+  String toString() {
+    final lines = ['Installed: ${installed.length}'];
+    if (skipped.isNotEmpty) lines.add('Skipped (no compatible package): ${skipped.join(', ')}');
+    failed.forEach((name, error) => lines.add('Failed $name: $error'));
+    return lines.join('\n');
+  }
+}
+
+class RefreshSummary {
+  /// The saved apps, in the order their checks finished.
+  final List<TrackedApp> refreshed = [];
+  final Map<TrackedApp, Object> failed = {};
+
+  /// Set when GitHub's rate limit cut the run short.
+  RateLimitException? rateLimit;
+
+  /// E.g. `Update check complete: 2 updates available, 1 failed`.
+  String completionMessage(DateTime now) {
+    final updates = refreshed.where((app) => app.showsUpdate(now)).length;
+    final parts = [
+      switch (updates) {
+        0 => 'everything is up to date',
+        1 => '1 update available',
+        _ => '$updates updates available',
+      },
+      if (failed.isNotEmpty) '${failed.length} failed',
+    ];
+    return 'Update check complete: ${parts.join(', ')}';
+  }
+}
+
+class RecheckSummary {
+  final List<String> confirmed = [];
+
+  /// Corrections made, keyed by app name.
+  final Map<String, List<String>> corrected = {};
+
+  /// Apps recorded as installed that couldn't be found on the system.
+  final List<String> notFound = [];
+  final Map<String, String> failed = {};
+
+  @override
+  String toString() {
+    final lines = ['Confirmed: ${confirmed.length}'];
+    corrected.forEach((name, changes) => lines.add('Corrected $name: ${changes.join(', ')}'));
+    if (notFound.isNotEmpty) lines.add('Not found on this system: ${notFound.join(', ')}');
+    failed.forEach((name, error) => lines.add('Failed $name: $error'));
+    return lines.join('\n');
+  }
+}
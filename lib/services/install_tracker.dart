@@ -0,0 +1,63 @@
+import 'change_notifier.dart';
+import 'installer_service.dart';
+
+/// Tracks which apps have an install/uninstall in flight, so a second
+/// request for the same app can't race the first on the download path and
+/// database row.
+class InstallTracker extends ChangeNotifier {
+  final Set<int> _busy = {};
+  final Map<int, DownloadControl> _downloads = {};
+  final Map<int, double> _progress = {};
+
+  bool isBusy(int? appId) => appId != null && _busy.contains(appId);
+
+  Set<int> get busyIds => Set.unmodifiable(_busy);
+
+  /// Marks [appId] busy. Returns false, without changing anything, when an
+  /// operation for that app is already running.
+  bool tryStart(int appId) {
+    if (!_busy.add(appId)) return false;
+    _downloads[appId] = DownloadControl();
+    notifyListeners();
+    return true;
+  }
+
+  void finish(int appId) {
+    _downloads.remove(appId)?.resume();
+    _progress.remove(appId);
+    if (_busy.remove(appId)) notifyListeners();
+  }
+
+  /// Pause switch for the download of [appId]'s running operation, if any.
+  DownloadControl? downloadFor(int? appId) => appId == null ? null : _downloads[appId];
+
+  bool isPaused(int? appId) => downloadFor(appId)?.isPaused ?? false;
+
+  /// How much of [appId]'s current download has arrived, from 0 to 1. Null
+  /// before the download starts and when its size is unknown.
+  double? progressOf(int? appId) => appId == null ? null : _progress[appId];
+
+  /// Records download progress of a running operation. Listeners only hear
+  /// about each whole percent, so rows aren't rebuilt for every chunk.
+  void reportProgress(int appId, int received, int? total) {
+    if (!_busy.contains(appId) || total == null || total <= 0) return;
+    final fraction = (received / total).clamp(0.0, 1.0);
+    final previous = _progress[appId];
+    _progress[appId] = fraction;
+    if (previous == null || (fraction * 100).floor() != (previous * 100).floor()) notifyListeners();
+  }
+
+  void pauseDownload(int appId) {
+    final download = _downloads[appId];
+    if (download == null || download.isPaused) return;
+    download.pause();
+    notifyListeners();
+  }
+
+  void resumeDownload(int appId) {
+    final download = _downloads[appId];
+    if (download == null || !download.isPaused) return;
+    download.resume();
+    notifyListeners();
+  }
+}
@@ -1,22 +1,211 @@
+import 'dart:async';
+import 'dart:convert';
+import 'dart:ffi';
 import 'dart:io';
 import 'package:path/path.dart' as p;
-import 'package:path_provider/path_provider.dart';
 import 'package:http/http.dart' as http;
 import '../models/install_type.dart';
+import '../models/release.dart';
 import '../models/tracked_app.dart';
+import 'app_paths.dart';
+import 'autonomix_exception.dart';
+import 'proxy_config.dart';
+
+/// What [InstallerService.installPackage] put on the system. [failures]
+/// lists files of a multi-file install that were skipped, with the reason.
+typedef InstallResult = ({
+  String? launchCommand,
+  String? packageName,
+  List<String>? installedFiles,
+  Map<String, String> failures,
+});
+
+/// Pause switch for a running [InstallerService.downloadFile]. Pausing
+/// stops reading from the network; resuming continues from the bytes
+/// already saved.
+class DownloadControl {
+  Completer<void>? _resumed;
+  bool _active = false;
+
+  bool get isPaused => _resumed != null;
+
+  /// Whether a download using this control is currently running.
+  bool get isActive => _active;
+
+  void pause() => _resumed ??= Completer<void>();
+
+  void resume() {
+    _resumed?.complete();
+    _resumed = null;
+  }
+
+  Future<void> _waitWhilePaused() async {
+    while (_resumed != null) {
+      await _resumed!.future;
+    }
+  }
+}
+
+/// Build systems [InstallerService.installSource] can drive, with the
+/// tools each needs on the PATH.
+enum BuildSystem {
+  autotools(['make']),
+  meson(['meson', 'ninja']),
+  cargo(['cargo']),
+  make(['make']);
+
+  final List<String> tools;
+
+  const BuildSystem(this.tools);
+
+  /// The build system of a source tree whose top level holds [fileNames].
+  /// A `configure` script wins over the Makefile it generates.
+  static BuildSystem? detect(Iterable<String> fileNames) {
+    final names = fileNames.toSet();
+    if (names.contains('configure')) return BuildSystem.autotools;
+    if (names.contains('meson.build')) return BuildSystem.meson;
+    if (names.contains('Cargo.toml')) return BuildSystem.cargo;
+    if (names.any({'Makefile', 'makefile', 'GNUmakefile'}.contains)) return BuildSystem.make;
+    return null;
+  }
+
+  /// Commands that build the tree and install it under [prefix].
+  List<List<String>> commands(String prefix) {
+    switch (this) {
+      case BuildSystem.autotools:
+        return [
+          ['./configure', '--prefix=$prefix'],
+          ['make'],
+          ['make', 'install'],
+        ];
+      case BuildSystem.meson:
+        return [
+          ['meson', 'setup', '_build', '--prefix=$prefix'],
+          ['ninja', '-C', '_build'],
+          ['ninja', '-C', '_build', 'install'],
+        ];
+      case BuildSystem.cargo:
+        return [
+          ['cargo', 'install', '--path', '.', '--root', prefix],
+        ];
+      case BuildSystem.make:
+        return [
+          ['make'],
+          ['make', 'PREFIX=$prefix', 'install'],
+        ];
+    }
+  }
+}
+
+/// How an app was found installed on the system by
+/// [InstallerService.probeInstallation].
+typedef InstallProbe = ({InstallType type, String? packageName, String? launchCommand, String? version});
 
 class InstallerService {
+  /// Compressed tarballs we know how to unpack (tar picks the codec).
+  static const List<String> archiveExtensions = [
+    '.tar.gz',
+    '.tgz',
+    '.tar.xz',
+    '.txz',
+    '.tar.bz2',
+    '.tbz2',
+    '.tar.zst',
+    '.tzst',
+  ];
+
+  /// Files published next to an installable that must never be installed
+  /// themselves, e.g. AppImageUpdate's `.zsync` delta metadata.
+  static const List<String> companionExtensions = ['.zsync', '.torrent'];
+
+  /// Order in which package types are tried when picking an asset without
+  /// asking the user.
+  static const List<InstallType> typePriority = [
+    // Only Arch has pacman, where dpkg/rpm are at most foreign extras
+    InstallType.pacman,
+    InstallType.deb,
+    InstallType.rpm,
+    InstallType.appImage,
+    InstallType.flatpak,
+    InstallType.snap,
+    InstallType.binary,
+    InstallType.source,
+  ];
+
+  /// Settings key for [useGuiInstaller].
+  static const String guiInstallerSettingKey = 'use_gui_installer';
+
+  /// Graphical installers that take a local package file, in preference
+  /// order, with the arguments to pass before the file.
+  static const Map<InstallType, List<(String, List<String>)>> guiInstallers = {
+    InstallType.deb: [
+      ('gdebi-gtk', []),
+      ('gnome-software', ['--local-filename']),
+      ('plasma-discover', []),
+    ],
+    InstallType.rpm: [
+      ('gnome-software', ['--local-filename']),
+      ('plasma-discover', []),
+    ],
+  };
+
+  /// Hand .deb/.rpm files to the distro's graphical installer, when one is
+  /// present, instead of installing them through pkexec or sudo.
+  bool useGuiInstaller = false;
+
+  /// Settings key for [verifyChecksums].
+  static const String checksumSettingKey = 'verify_checksums';
+
+  /// Check downloads against the release's checksum file, when it has one.
+  bool verifyChecksums = true;
+
+  /// Settings key for [preferMusl]. When unset, it follows [hostLibc].
+  static const String preferMuslSettingKey = 'prefer_musl';
+
+  /// Among binaries of the same type, take musl or static builds, which
+  /// don't break on a glibc older than the one they were built against.
+  bool preferMusl = false;
+
+  ProxyConfig? _proxy;
+
+  /// Shared by all downloads so connections are reused; replaced when the
+  /// proxy changes.
+  http.Client _client;
+
+  /// Where downloads go; `downloads` in the data directory unless given,
+  /// e.g. in tests.
+  final Directory? downloadsDirectory;
+
+  InstallerService({http.Client? client, this.downloadsDirectory}) : _client = client ?? http.Client();
+
+  /// Proxy for downloads; null follows the environment.
+  ProxyConfig? get proxy => _proxy;
+
+  set proxy(ProxyConfig? value) {
+    _proxy = value;
+    // Downloads still using the old client fail over to the new one
+    _client.close();
+    _client = value?.createClient() ?? http.Client();
+  }
+
+  Future<Set<InstallType>>? _availableInstallers;
+  Future<List<String>?>? _escalator;
+
   Future<Directory> get _downloadsDir async {
-    final dataDir = await getApplicationSupportDirectory();
-    final dir = Directory(p.join(dataDir.path, 'downloads'));
+    final dir = downloadsDirectory ?? Directory(p.join((await AppPaths.supportDirectory()).path, 'downloads'));
     if (!await dir.exists()) {
       await dir.create(recursive: true);
     }
     return dir;
   }
 
+  Future<Directory> get _buildDir async {
+    final dataDir = await AppPaths.supportDirectory();
+    return Directory(p.join(dataDir.path, 'build'));
+  }
+
   Future<Directory> get _appImageDir async {
-    final dataDir = await getApplicationSupportDirectory();
+    final dataDir = await AppPaths.supportDirectory();
     final dir = Directory(p.join(dataDir.path, 'appimages'));
     if (!await dir.exists()) {
       await dir.create(recursive: true);
@@ -24,6 +213,44 @@ class InstallerService {
     return dir;
   }
 
+  /// Install types this host can handle. Probed once and cached, since the
+  /// set of package managers doesn't change while we're running.
+  Future<Set<InstallType>> detectAvailableInstallers() {
+    return _availableInstallers ??= _probeInstallers();
+  }
+
+  Future<Set<InstallType>> _probeInstallers() async {
+    final available = {InstallType.appImage, InstallType.binary};
+    if (await isOnPath('dpkg')) available.add(InstallType.deb);
+    if (await isOnPath('rpm')) available.add(InstallType.rpm);
+    if (await isOnPath('pacman')) available.add(InstallType.pacman);
+    if (await isOnPath('flatpak')) available.add(InstallType.flatpak);
+    if (await isOnPath('snap')) available.add(InstallType.snap);
+    for (final system in BuildSystem.values) {
+      if (await _hasTools(system)) {
+        available.add(InstallType.source);
+        break;
+      }
+    }
+    return available;
+  }
+
+  Future<bool> _hasTools(BuildSystem system) async {
+    for (final tool in system.tools) {
+      if (!await isOnPath(tool)) return false;
+    }
+    return true;
+  }
+
+  Future<bool> isOnPath(String command) async {
+    try {
+      final result = await Process.run('which', [command]);
+      return result.exitCode == 0;
+    } catch (_) {
+      return false;
+    }
+  }
+
   Future<InstallType?> detectSelfInstallType() async {
     // Check if installed via dpkg
     try {
@@ -37,6 +264,12 @@ class InstallerService {
       if (result.exitCode == 0) return InstallType.rpm;
     } catch (_) {}
 
+    // Check if installed via pacman
+    try {
+      final result = await Process.run('pacman', ['-Q', 'autonomix']);
+      if (result.exitCode == 0) return InstallType.pacman;
+    } catch (_) {}
+
     // Check if installed via flatpak
     try {
       final result = await Process.run('flatpak', ['info', 'io.github.plebone.autonomix']);
@@ -66,20 +299,281 @@ class InstallerService {
     return null;
   }
 
-  Future<File> downloadFile(String url, String filename) async {
+  /// Streams [url] into the downloads directory. [onProgress] gets a null
+  /// total when the server sends no Content-Length (e.g. some mirrors and
+  /// proxies), in which case only [expectedSize] can be checked.
+  ///
+  /// Data is written to a `.part` file that is renamed once complete. While
+  /// [control] is paused the transfer stops, keeping the `.part` file, and
+  /// resuming asks the server for the rest with a Range request. Servers
+  /// that ignore the range send the whole file again.
+  ///
+  /// A connection that drops is retried [retries] times the same way, and
+  /// the `.part` file is kept if it still fails, so the next attempt picks
+  /// up where this one stopped.
+  ///
+  /// Next to it, a `.part.source` file records the URL and the server's
+  /// [resumeValidator] for the data. A `.part` file from another URL, e.g.
+  /// the same asset name in an older release, is started over, and the
+  /// validator goes in an If-Range header so a file that changed on the
+  /// server is sent whole instead of having its tail appended.
+  Future<File> downloadFile(
+    String url,
+    String filename, {
+    int? expectedSize,
+    void Function(int received, int? total)? onProgress,
+    DownloadControl? control,
+    Map<String, String> headers = const {},
+    int retries = 2,
+  }) async {
     final dir = await _downloadsDir;
     final file = File(p.join(dir.path, filename));
-    
-    final response = await http.get(Uri.parse(url));
-    if (response.statusCode == 200) {
-      await file.writeAsBytes(response.bodyBytes);
-      return file;
-    } else {
-      throw Exception('Failed to download file: ${response.statusCode}');
+    final partial = File('${file.path}.part');
+    final source = File('${partial.path}.source');
+
+    control?._active = true;
+    try {
+      int? total;
+      var received = await partial.exists() ? await partial.length() : 0;
+      final resumable = received > 0 ? await _partialSource(source) : null;
+      var validator = resumable?.validator;
+      if (resumable?.url != url || (expectedSize != null && expectedSize > 0 && received >= expectedSize)) {
+        // Left over from a different file of the same name
+        received = 0;
+      }
+      var failures = 0;
+      while (true) {
+        final request = http.Request('GET', Uri.parse(url))..headers.addAll(headers);
+        if (received > 0) {
+          request.headers['Range'] = 'bytes=$received-';
+          if (validator != null) request.headers['If-Range'] = validator;
+        }
+
+        var paused = false;
+        try {
+          final response = await _client.send(request);
+          final resumed = received > 0 && response.statusCode == 206;
+          if (response.statusCode != 200 && !resumed) {
+            throw AutonomixException(ErrorKind.network, 'Failed to download file: ${response.statusCode}');
+          }
+          if (resumed) {
+            total ??= rangeTotal(response.headers['content-range']);
+          } else {
+            received = 0;
+            total = response.contentLength;
+            validator = resumeValidator(response.headers);
+            await source.writeAsString(jsonEncode({'url': url, 'validator': validator}));
+          }
+
+          final sink = partial.openWrite(mode: resumed ? FileMode.append : FileMode.write);
+          try {
+            await for (final chunk in response.stream) {
+              sink.add(chunk);
+              received += chunk.length;
+              onProgress?.call(received, total);
+              if (control?.isPaused ?? false) {
+                paused = true;
+                break;
+              }
+            }
+          } finally {
+            await sink.close();
+          }
+        } on IOException {
+          if (++failures > retries) rethrow;
+          continue;
+        } on http.ClientException {
+          if (++failures > retries) rethrow;
+          continue;
+        }
+        if (!paused) break;
+        await control!._waitWhilePaused();
+      }
+
+      final problem = checkDownloadLength(
+        received: received,
+        contentLength: total,
+        expectedSize: expectedSize,
+      );
+      if (problem != null) {
+        throw AutonomixException(ErrorKind.network, 'Download of $filename was incomplete: $problem');
+      }
+      if (await source.exists()) await source.delete();
+      return await partial.rename(file.path);
+    } on IOException {
+      // Keep what arrived so a retry can resume it
+      rethrow;
+    } on http.ClientException {
+      rethrow;
+    } catch (_) {
+      // Anything else means the partial data can't be trusted
+      if (await partial.exists()) await partial.delete();
+      if (await source.exists()) await source.delete();
+      rethrow;
+    } finally {
+      control?._active = false;
+    }
+  }
+
+  /// Downloads every piece of a multi-part asset (see [partsOf]) and joins
+  /// them, in order, into [joinedName]. The pieces are removed afterwards.
+  /// Like [downloadFile], the joined file only gets its name once complete.
+  Future<File> downloadParts(
+    List<ReleaseAsset> parts,
+    String joinedName, {
+    void Function(ReleaseAsset part, int index)? onPart,
+    void Function(int received, int? total)? onProgress,
+    DownloadControl? control,
+    ({String url, Map<String, String> headers}) Function(ReleaseAsset part)? requestFor,
+  }) async {
+    final dir = await _downloadsDir;
+    final joined = File(p.join(dir.path, joinedName));
+    // Not `.part`, which downloadFile would take for a resumable download
+    final joining = File('${joined.path}.joining');
+    final sink = joining.openWrite();
+    try {
+      for (var i = 0; i < parts.length; i++) {
+        onPart?.call(parts[i], i);
+        final request = requestFor?.call(parts[i]);
+        final piece = await downloadFile(
+          request?.url ?? parts[i].browserDownloadUrl,
+          parts[i].name,
+          expectedSize: parts[i].size,
+          onProgress: onProgress,
+          control: control,
+          headers: request?.headers ?? const {},
+        );
+        await sink.addStream(piece.openRead());
+        await piece.delete();
+      }
+      await sink.close();
+    } catch (_) {
+      await sink.close();
+      if (await joining.exists()) await joining.delete();
+      rethrow;
+    }
+
+    final expected = parts.fold<int>(0, (sum, part) => sum + part.size);
+    final problem = checkDownloadLength(received: await joining.length(), expectedSize: expected);
+    if (problem != null) {
+      await joining.delete();
+      throw Exception('Joining the parts of $joinedName failed: $problem');
+    }
+    return await joining.rename(joined.path);
+  }
+
+  /// What a response's data can be resumed against in an If-Range header:
+  /// its ETag, unless weak (which If-Range doesn't allow), else its
+  /// Last-Modified date. Null when it has neither.
+  static String? resumeValidator(Map<String, String> headers) {
+    final etag = headers['etag'];
+    if (etag != null && !etag.startsWith('W/')) return etag;
+    return headers['last-modified'];
+  }
+
+  /// The URL and validator [downloadFile] recorded in [source] for its
+  /// `.part` file, or null when there is no readable record.
+  static Future<({String url, String? validator})?> _partialSource(File source) async {
+    try {
+      final json = jsonDecode(await source.readAsString()) as Map<String, dynamic>;
+      return (url: json['url'] as String, validator: json['validator'] as String?);
+    } catch (_) {
+      return null;
+    }
+  }
+
+  /// Full size of the file from a `Content-Range: bytes 100-199/200` header,
+  /// or null when the header is missing or the size is `*`.
+  static int? rangeTotal(String? contentRange) {
+    if (contentRange == null) return null;
+    final match = RegExp(r'^bytes\s+\d+-\d+/(\d+)$').firstMatch(contentRange.trim());
+    return match == null ? null : int.parse(match.group(1)!);
+  }
+
+  /// Room left free on top of a download, since installing needs some too.
+  static const int diskSpaceMargin = 100 * 1024 * 1024;
+
+  /// Fails early when the downloads folder's volume can't hold [bytes]
+  /// plus [diskSpaceMargin], rather than partway through writing the file.
+  /// Does nothing when the free space can't be read.
+  Future<void> ensureFreeSpace(int bytes) async {
+    if (bytes <= 0) return;
+    final dir = await _downloadsDir;
+    final int? available;
+    try {
+      final result = await Process.run('df', ['-Pk', dir.path]);
+      available = result.exitCode == 0 ? parseDfAvailable(result.stdout.toString()) : null;
+    } on ProcessException {
+      return;
+    }
+    if (available == null) return;
+    final problem = spaceShortage(needed: bytes + diskSpaceMargin, available: available);
+    if (problem != null) throw AutonomixException(ErrorKind.installFailed, problem);
+  }
+
+  /// Free bytes from the output of `df -Pk <path>`, or null if it can't be
+  /// read.
+  static int? parseDfAvailable(String output) {
+    final lines = output.trim().split('\n');
+    if (lines.length < 2) return null;
+    // Filesystem, 1024-blocks, Used, Available, Capacity, Mounted on
+    final fields = lines.last.trim().split(RegExp(r'\s+'));
+    if (fields.length < 4) return null;
+    final kilobytes = int.tryParse(fields[3]);
+    return kilobytes == null ? null : kilobytes * 1024;
+  }
+
+  /// E.g. `Not enough disk space (need 1.2 GB, have 300.0 MB)`, or null
+  /// when there is room.
+  static String? spaceShortage({required int needed, required int available}) {
+    if (available >= needed) return null;
+    return 'Not enough disk space (need ${formatSize(needed)}, have ${formatSize(available)})';
+  }
+
+  static String formatSize(int bytes) {
+    if (bytes < 1024) return '$bytes B';
+    const units = ['KB', 'MB', 'GB'];
+    var size = bytes / 1024;
+    var unit = 0;
+    while (size >= 1024 && unit < units.length - 1) {
+      size /= 1024;
+      unit++;
+    }
+    return '${size.toStringAsFixed(1)} ${units[unit]}';
+  }
+
+  /// Why a download of [received] bytes looks truncated, or null if it
+  /// matches both the Content-Length and the asset size that are known.
+  /// An asset size of 0 is treated as unknown.
+  static String? checkDownloadLength({
+    required int received,
+    int? contentLength,
+    int? expectedSize,
+  }) {
+    if (contentLength != null && received != contentLength) {
+      return 'got $received of $contentLength bytes';
     }
+    if (expectedSize != null && expectedSize > 0 && received != expectedSize) {
+      return 'got $received bytes, release lists $expectedSize';
+    }
+    return null;
   }
 
-  Future<({String? launchCommand, String? packageName})> installPackage(File file, InstallType type) async {
+  /// [binaryName] is the file name for [InstallType.binary] installs in
+  /// `~/.local/bin`; it defaults to the downloaded file's name. Archives
+  /// are installed for [app], keeping clear of the binaries of [tracked]
+  /// (see [installArchiveBinaries]). [onOutput] receives the build log of
+  /// [InstallType.source] installs line by line.
+  Future<InstallResult> installPackage(
+    File file,
+    InstallType type, {
+    String? displayName,
+    String? binaryName,
+    TrackedApp? app,
+    List<TrackedApp> tracked = const [],
+    void Function(String warning)? onWarning,
+    void Function(String line)? onOutput,
+  }) async {
     switch (type) {
       case InstallType.deb:
         String? pkgName;
@@ -88,8 +582,10 @@ class InstallerService {
           if (res.exitCode == 0) pkgName = res.stdout.toString().trim();
         } catch (_) {}
 
-        await _runPrivileged('dpkg', ['-i', file.path]);
-        return (launchCommand: null, packageName: pkgName);
+        if (!await _installWithGui(file, type, pkgName)) {
+          await _runPrivileged('dpkg', ['-i', file.path]);
+        }
+        return (launchCommand: null, packageName: pkgName, installedFiles: null, failures: const {});
 
       case InstallType.rpm:
         String? pkgName;
@@ -98,22 +594,378 @@ class InstallerService {
           if (res.exitCode == 0) pkgName = res.stdout.toString().trim();
         } catch (_) {}
 
-        await _runPrivileged('rpm', ['-i', file.path]);
-        return (launchCommand: null, packageName: pkgName);
+        if (!await _installWithGui(file, type, pkgName)) {
+          await _runPrivileged('rpm', ['-i', file.path]);
+        }
+        return (launchCommand: null, packageName: pkgName, installedFiles: null, failures: const {});
+
+      case InstallType.pacman:
+        String? pkgName;
+        try {
+          // Prints "name version"
+          final res = await Process.run('pacman', ['-Qp', file.path]);
+          if (res.exitCode == 0) pkgName = res.stdout.toString().trim().split(' ').first;
+        } catch (_) {}
+
+        await _runPrivileged('pacman', ['-U', '--noconfirm', file.path]);
+        return (launchCommand: null, packageName: pkgName, installedFiles: null, failures: const {});
 
       case InstallType.flatpak:
-        await Process.run('flatpak', ['install', '-y', file.path]);
-        return (launchCommand: null, packageName: null);
+        if (file.path.toLowerCase().endsWith('.flatpakref')) {
+          await _addFlatpakRefRemotes(file);
+        }
+        final res = await Process.run('flatpak', ['install', '--user', '-y', file.path]);
+        if (res.exitCode != 0) {
+          throw Exception('flatpak install failed: ${res.stderr}');
+        }
+        return (launchCommand: null, packageName: null, installedFiles: null, failures: const {});
 
       case InstallType.appImage:
+        await _checkAppImageArchitecture(file);
         final appImageDir = await _appImageDir;
         final target = File(p.join(appImageDir.path, p.basename(file.path)));
         await file.copy(target.path);
         await Process.run('chmod', ['+x', target.path]);
-        return (launchCommand: target.path, packageName: null);
+        await _createAppImageDesktopEntry(target, displayName);
+        return (launchCommand: target.path, packageName: null, installedFiles: null, failures: const {});
+
+      case InstallType.binary:
+        if (isArchive(file.path) || isZip(file.path)) {
+          return installArchiveBinaries(file, app: app, tracked: tracked, onWarning: onWarning);
+        }
+        final result = await installBinaries({binaryName ?? p.basename(file.path): file});
+        return (
+          launchCommand: result.installed.first,
+          packageName: null,
+          installedFiles: result.installed,
+          failures: result.failed,
+        );
+
+      case InstallType.source:
+        return installSource(file, onOutput: onOutput);
 
       default:
-        throw Exception('Installation not supported for ${type.name}');
+        throw AutonomixException(ErrorKind.unsupported, 'Installation not supported for ${type.name}');
+    }
+  }
+
+  /// Unpacks a source tarball, builds it with the build system it uses and
+  /// installs it under `~/.local`. The build tree is removed afterwards,
+  /// unless the build fails, so the log can be followed up on. Programs
+  /// that appeared in (or changed in) `~/.local/bin` are reported as
+  /// installed files.
+  Future<InstallResult> installSource(File archive, {void Function(String line)? onOutput}) async {
+    final home = Platform.environment['HOME'];
+    if (home == null) throw Exception('HOME is not set, cannot install to ~/.local');
+    final prefix = p.join(home, '.local');
+
+    final buildDir = Directory(p.join((await _buildDir).path, p.basename(archive.path)));
+    if (await buildDir.exists()) await buildDir.delete(recursive: true);
+    await buildDir.create(recursive: true);
+    final untar = await Process.run('tar', ['-xf', archive.path, '-C', buildDir.path]);
+    if (untar.exitCode != 0) {
+      throw Exception('Could not unpack ${p.basename(archive.path)}: ${untar.stderr.toString().trim()}');
+    }
+
+    // Tarballs usually hold a single project-1.2.3/ directory
+    final entries = await buildDir.list().toList();
+    final root = entries.length == 1 && entries.single is Directory ? entries.single as Directory : buildDir;
+    final names = [for (final entity in await root.list().toList()) p.basename(entity.path)];
+    final system = BuildSystem.detect(names);
+    if (system == null) {
+      throw Exception('No supported build system found '
+          '(looked for configure, meson.build, Cargo.toml and a Makefile)');
+    }
+    for (final tool in system.tools) {
+      if (!await isOnPath(tool)) {
+        throw Exception('Building this project with ${system.name} needs $tool, which is not installed');
+      }
+    }
+
+    final binDir = Directory(p.join(prefix, 'bin'));
+    final before = await _modificationTimes(binDir);
+    for (final command in system.commands(prefix)) {
+      onOutput?.call('\$ ${command.join(' ')}');
+      final exitCode = await _runStreaming(command, root.path, onOutput);
+      if (exitCode != 0) {
+        throw Exception('`${command.join(' ')}` failed with exit code $exitCode '
+            '(build tree kept in ${root.path})');
+      }
+    }
+    final after = await _modificationTimes(binDir);
+    final installed = [
+      for (final MapEntry(key: path, value: modified) in after.entries)
+        if (before[path] != modified) path,
+    ]..sort();
+
+    await buildDir.delete(recursive: true);
+    return (
+      launchCommand: installed.isEmpty ? null : installed.first,
+      packageName: null,
+      installedFiles: installed.isEmpty ? null : installed,
+      failures: const {},
+    );
+  }
+
+  Future<Map<String, DateTime>> _modificationTimes(Directory dir) async {
+    final times = <String, DateTime>{};
+    if (!await dir.exists()) return times;
+    await for (final entity in dir.list()) {
+      if (entity is File) times[entity.path] = (await entity.stat()).modified;
+    }
+    return times;
+  }
+
+  /// Runs [command] in [workingDirectory], passing stdout and stderr to
+  /// [onOutput] as lines arrive. Returns the exit code.
+  Future<int> _runStreaming(
+    List<String> command,
+    String workingDirectory,
+    void Function(String line)? onOutput,
+  ) async {
+    final process = await Process.start(command.first, command.sublist(1), workingDirectory: workingDirectory);
+    Future<void> forward(Stream<List<int>> stream) => stream
+        .transform(const Utf8Decoder(allowMalformed: true))
+        .transform(const LineSplitter())
+        .forEach((line) => onOutput?.call(line));
+    await Future.wait([forward(process.stdout), forward(process.stderr)]);
+    return process.exitCode;
+  }
+
+  /// Unpacks a prebuilt tarball or zip and installs every program in it (see
+  /// [findExecutables]) under its own name. The first program named like
+  /// the archive becomes the launch command, otherwise the first one found.
+  ///
+  /// A name that belongs to another of the [tracked] apps, or to a file in
+  /// [binDir] that [app] didn't install, gets [app]'s owner as a prefix
+  /// instead, which [onWarning] hears about; when that is taken too, the
+  /// program is skipped. So is every program after the first with a given
+  /// name. Skipped programs are listed in `failures`.
+  Future<InstallResult> installArchiveBinaries(
+    File archive, {
+    Directory? binDir,
+    TrackedApp? app,
+    List<TrackedApp> tracked = const [],
+    void Function(String warning)? onWarning,
+  }) async {
+    binDir ??= defaultBinDir();
+    final extractDir = await Directory.systemTemp.createTemp('autonomix-extract-');
+    try {
+      await _unpack(archive, extractDir);
+      final executables = await findExecutables(extractDir);
+      if (executables.isEmpty) {
+        throw Exception('${p.basename(archive.path)} contains no programs');
+      }
+
+      final binaries = <String, File>{};
+      // Installed name to the program's own name, and that to its path
+      final originals = <String, String>{};
+      final seen = <String, String>{};
+      final skipped = <String, String>{};
+      for (final file in executables) {
+        final name = p.basename(file.path);
+        final path = p.relative(file.path, from: extractDir.path);
+        final first = seen[name];
+        if (first != null) {
+          skipped[path] = 'same name as $first';
+          continue;
+        }
+        seen[name] = path;
+
+        final conflict = await binaryNameConflict(name, binDir, app, tracked);
+        if (conflict == null) {
+          binaries[name] = file;
+          originals[name] = name;
+          continue;
+        }
+        final alternative = app == null ? null : '${app.repoOwner.toLowerCase()}-$name';
+        if (alternative != null &&
+            !binaries.containsKey(alternative) &&
+            await binaryNameConflict(alternative, binDir, app, tracked) == null) {
+          onWarning?.call('"$name" $conflict, so it was installed as "$alternative" instead.');
+          binaries[alternative] = file;
+          originals[alternative] = name;
+        } else {
+          skipped[name] = '"$name" $conflict';
+        }
+      }
+      if (binaries.isEmpty) {
+        throw Exception('No program from ${p.basename(archive.path)} could be installed: '
+            '${skipped.entries.map((e) => '${e.key} (${e.value})').join(', ')}');
+      }
+
+      final result = await installBinaries(binaries, binDir: binDir);
+      final archiveName = p.basename(archive.path).toLowerCase();
+      final launch = result.installed.firstWhere(
+        (path) => archiveName.startsWith(originals[p.basename(path)]!.toLowerCase()),
+        orElse: () => result.installed.first,
+      );
+      return (
+        launchCommand: launch,
+        packageName: null,
+        installedFiles: result.installed,
+        failures: {...skipped, ...result.failed},
+      );
+    } finally {
+      await extractDir.delete(recursive: true);
+    }
+  }
+
+  Future<void> _unpack(File archive, Directory into) async {
+    final ProcessResult result;
+    if (isZip(archive.path)) {
+      try {
+        result = await Process.run('unzip', ['-q', '-o', archive.path, '-d', into.path]);
+      } on ProcessException {
+        throw Exception('unzip is needed to unpack ${p.basename(archive.path)}, but it is not installed');
+      }
+    } else {
+      result = await Process.run('tar', ['-xf', archive.path, '-C', into.path]);
+    }
+    if (result.exitCode != 0) {
+      throw Exception('Could not unpack ${p.basename(archive.path)}: ${result.stderr.toString().trim()}');
+    }
+  }
+
+  /// The programs under [dir]: files with an execute bit, or when no file
+  /// has one (archives made on other systems), ELF binaries. Shared
+  /// libraries are left out. Sorted by path.
+  static Future<List<File>> findExecutables(Directory dir) async {
+    final files = <File>[];
+    await for (final entity in dir.list(recursive: true, followLinks: false)) {
+      if (entity is File && !RegExp(r'\.so(\.\d+)*$').hasMatch(entity.path)) files.add(entity);
+    }
+    files.sort((a, b) => a.path.compareTo(b.path));
+
+    final marked = <File>[];
+    for (final file in files) {
+      if ((await file.stat()).mode & 0x49 != 0) marked.add(file);
+    }
+    if (marked.isNotEmpty) return marked;
+
+    final elf = <File>[];
+    for (final file in files) {
+      final header = <int>[];
+      await for (final chunk in file.openRead(0, 4)) {
+        header.addAll(chunk);
+      }
+      if (_isElf(header)) elf.add(file);
+    }
+    return elf;
+  }
+
+  /// `~/.local/bin`, where binaries are installed.
+  static Directory defaultBinDir() {
+    final home = Platform.environment['HOME'];
+    if (home == null) throw Exception('HOME is not set, cannot install to ~/.local/bin');
+    return Directory(p.join(home, '.local', 'bin'));
+  }
+
+  /// Copies each of [binaries] (keyed by the name to install it under) into
+  /// [binDir], `~/.local/bin` by default, and makes it executable. A file
+  /// that can't be installed is skipped and reported in `failed` rather than
+  /// aborting the rest; only when nothing could be installed does this throw.
+  Future<({List<String> installed, Map<String, String> failed})> installBinaries(
+    Map<String, File> binaries, {
+    Directory? binDir,
+  }) async {
+    binDir ??= defaultBinDir();
+    await binDir.create(recursive: true);
+
+    final installed = <String>[];
+    final failed = <String, String>{};
+    for (final MapEntry(key: name, value: source) in binaries.entries) {
+      final target = File(p.join(binDir.path, name));
+      try {
+        await source.copy(target.path);
+        final chmod = await Process.run('chmod', ['755', target.path]);
+        if (chmod.exitCode != 0) {
+          // Not executable means not usable; don't leave it behind
+          await target.delete();
+          throw Exception('chmod failed: ${chmod.stderr.toString().trim()}');
+        }
+        installed.add(target.path);
+      } catch (e) {
+        failed[name] = e.toString();
+      }
+    }
+
+    if (installed.isEmpty) {
+      throw Exception('No binary could be installed: '
+          '${failed.entries.map((e) => '${e.key} (${e.value})').join(', ')}');
+    }
+    return (installed: installed, failed: failed);
+  }
+
+  /// Refuses AppImages built for another CPU, which would otherwise get a
+  /// desktop entry that can never start. The file name can lie; the ELF
+  /// header can't.
+  Future<void> _checkAppImageArchitecture(File file) async {
+    final header = <int>[];
+    await for (final chunk in file.openRead(0, 20)) {
+      header.addAll(chunk);
+    }
+    final arch = elfArchitecture(header);
+    final host = hostArchitecture();
+    if (arch != null && host != 'unknown' && arch != host) {
+      throw Exception('${p.basename(file.path)} is built for $arch, but this system is $host');
+    }
+  }
+
+  /// ELF `e_machine` values, named like [hostArchitecture].
+  static const Map<int, String> _elfMachines = {
+    0x03: 'x86',
+    0x28: 'armv7',
+    0x3E: 'x86_64',
+    0xB7: 'aarch64',
+    0xF3: 'riscv64',
+    0x16: 's390x',
+  };
+
+  /// Whether [header] starts with the ELF magic `\x7FELF`.
+  static bool _isElf(List<int> header) =>
+      header.length >= 4 && header[0] == 0x7F && header[1] == 0x45 && header[2] == 0x4C && header[3] == 0x46;
+
+  /// Architecture an ELF [header] (at least its first 20 bytes) was built
+  /// for. Returns null for non-ELF data and unfamiliar machine types.
+  static String? elfArchitecture(List<int> header) {
+    if (header.length < 20 || !_isElf(header)) return null;
+    // EI_DATA: 1 is little-endian, 2 big-endian
+    final machine = header[5] == 2 ? (header[18] << 8) | header[19] : header[18] | (header[19] << 8);
+    // EM_PPC64 covers both byte orders, which are different architectures
+    if (machine == 0x15) return header[5] == 1 ? 'ppc64le' : 'ppc64';
+    return _elfMachines[machine];
+  }
+
+  /// The system package [uninstallPackage] removes for [app]. Entries
+  /// from before the package name was recorded fall back to the repo name.
+  static String? packageNameOf(TrackedApp app) {
+    if (app.packageName != null) return app.packageName;
+    const packaged = {InstallType.deb, InstallType.rpm, InstallType.pacman};
+    return packaged.contains(app.installType) ? app.repoName : null;
+  }
+
+  /// What [uninstallPackage] will do to [app], for the confirmation.
+  static String describeUninstall(TrackedApp app) {
+    final package = packageNameOf(app);
+    final assumed = app.packageName == null ? ' (assumed from the repo name)' : '';
+    final files = app.installedFiles ?? [if (app.launchCommand != null) app.launchCommand!];
+    const unsupported = 'Autonomix has no record of how to remove this install; '
+        'use your package manager instead.';
+    switch (app.installType) {
+      case InstallType.appImage:
+        return app.launchCommand == null ? unsupported : 'Deletes ${app.launchCommand} and its menu entry.';
+      case InstallType.binary:
+      case InstallType.source:
+        return files.isEmpty ? unsupported : 'Deletes ${files.join(', ')}.';
+      case InstallType.deb:
+        return package == null ? unsupported : 'Removes the package $package$assumed with dpkg, as administrator.';
+      case InstallType.rpm:
+        return package == null ? unsupported : 'Removes the package $package$assumed with rpm, as administrator.';
+      case InstallType.pacman:
+        return package == null ? unsupported : 'Removes the package $package$assumed with pacman, as administrator.';
+      default:
+        return unsupported;
     }
   }
 
@@ -121,24 +973,46 @@ class InstallerService {
     if (app.installType == InstallType.appImage && app.launchCommand != null) {
        final file = File(app.launchCommand!);
        if (await file.exists()) await file.delete();
-    } else if (app.installType == InstallType.deb && app.packageName != null) {
-       await _runPrivileged('dpkg', ['-r', app.packageName!]);
-    } else if (app.installType == InstallType.rpm && app.packageName != null) {
-       await _runPrivileged('rpm', ['-e', app.packageName!]);
+       final entry = _appImageDesktopEntryFile(app.launchCommand!);
+       if (entry != null && await entry.exists()) await entry.delete();
+    } else if (app.installType == InstallType.binary && app.launchCommand != null) {
+       // The recorded paths, which may use a disambiguated name (see
+       // [binaryNameOwner])
+       for (final path in app.installedFiles ?? [app.launchCommand!]) {
+         final file = File(path);
+         if (await file.exists()) await file.delete();
+       }
+    } else if (app.installType == InstallType.source && app.installedFiles != null) {
+       // Only the programs are known; files installed elsewhere under
+       // ~/.local by the build stay behind
+       for (final path in app.installedFiles!) {
+         final file = File(path);
+         if (await file.exists()) await file.delete();
+       }
+    } else if (packageNameOf(app) case final package?) {
+       // A name guessed from the repo must at least be an installed package
+       if (app.packageName == null && !await isPackageInstalled(app.installType!, package)) {
+         throw AutonomixException(ErrorKind.notFound,
+             'No package named $package is installed; remove ${app.displayName} with your package manager');
+       }
+       switch (app.installType) {
+         case InstallType.deb:
+           await _runPrivileged('dpkg', ['-r', package]);
+         case InstallType.rpm:
+           await _runPrivileged('rpm', ['-e', package]);
+         default:
+           await _runPrivileged('pacman', ['-R', '--noconfirm', package]);
+       }
     } else {
-      throw Exception('Uninstall not supported for this app (missing package info)');
+      throw AutonomixException(ErrorKind.unsupported, 'Uninstall not supported for this app (missing package info)');
     }
   }
 
   Future<void> launchApp(TrackedApp app) async {
     if (app.launchCommand != null) {
-      // If we have a stored command/path, use it
-      if (app.installType == InstallType.appImage) {
-        await Process.start(app.launchCommand!, []);
-      } else {
-        // For others, it might be a command in PATH
-        await Process.start(app.launchCommand!, []);
-      }
+      // If we have a stored command/path, use it. For non-AppImages it
+      // might be a command in PATH.
+      await _startDetached(app.launchCommand!, const [], app.env);
       return;
     }
 
@@ -147,20 +1021,27 @@ class InstallerService {
        final appImageDir = await _appImageDir;
        await for (final entity in appImageDir.list()) {
          if (entity is File && entity.path.toLowerCase().contains(app.repoName.toLowerCase())) {
-           await Process.start(entity.path, []);
+           await _startDetached(entity.path, const [], app.env);
            return;
          }
        }
        throw Exception('Could not find AppImage to launch');
     } else {
+      // Package-manager installs usually ship a desktop entry
+      final desktopId = await _findDesktopEntryId(app);
+      if (desktopId != null) {
+        await _startDetached('gtk-launch', [desktopId], app.env);
+        return;
+      }
+
       // For system installs, try running the repo name as command
       try {
-        await Process.start(app.repoName, []);
+        await _startDetached(app.repoName, const [], app.env);
       } catch (e) {
         // Try lowercase as fallback (common for Linux binaries)
         if (app.repoName != app.repoName.toLowerCase()) {
           try {
-            await Process.start(app.repoName.toLowerCase(), []);
+            await _startDetached(app.repoName.toLowerCase(), const [], app.env);
             return;
           } catch (_) {
             // Ignore and throw original error
@@ -171,25 +1052,999 @@ class InstallerService {
     }
   }
 
+  File? _appImageDesktopEntryFile(String appImagePath) {
+    final home = Platform.environment['HOME'];
+    if (home == null) return null;
+    final id = p.basenameWithoutExtension(appImagePath).toLowerCase().replaceAll(RegExp(r'[^a-z0-9._-]'), '-');
+    return File(p.join(home, '.local', 'share', 'applications', 'autonomix-$id.desktop'));
+  }
+
+  Future<void> _createAppImageDesktopEntry(File appImage, String? displayName) async {
+    final entry = _appImageDesktopEntryFile(appImage.path);
+    if (entry == null) return;
+    await entry.parent.create(recursive: true);
+    await entry.writeAsString('''[Desktop Entry]
+Type=Application
+Name=${displayName ?? p.basenameWithoutExtension(appImage.path)}
+Exec=${escapeDesktopExecArg(appImage.path)}
+Terminal=false
+Categories=Utility;
+X-Autonomix-Managed=true
+''');
+  }
+
+  /// Quotes one argument of a desktop entry `Exec` key per the Desktop Entry
+  /// Specification: reserved characters force double quoting, `"`, `` ` ``,
+  /// `$` and `\` are backslash-escaped inside the quotes, backslashes are
+  /// escaped once more because Exec is itself a string value, and `%` is
+  /// doubled so it isn't read as a field code.
+  static String escapeDesktopExecArg(String arg) {
+    const reserved = ' \t\n"\'\\><~|&;\$*?#()`';
+    var quoted = arg;
+    if (arg.split('').any(reserved.contains)) {
+      final inner = arg.replaceAllMapped(RegExp(r'["`$\\]'), (m) => '\\${m[0]}');
+      quoted = '"$inner"';
+    }
+    return quoted.replaceAll('\\', '\\\\').replaceAll('%', '%%');
+  }
+
+  static final RegExp _versionPattern = RegExp(r'\d+(\.\d+)+([-+][0-9A-Za-z.-]+)?');
+
+  /// Pulls the first version-looking token out of `--version` style output.
+  static String? parseVersionOutput(String output) => _versionPattern.firstMatch(output)?.group(0);
+
+  /// Runs the app's version command and returns the version it reports, or
+  /// null when there's no command or it didn't print a version. `{path}` in
+  /// the command is replaced with the installed file.
+  Future<String?> detectInstalledVersion(TrackedApp app) async {
+    final command = app.versionCommand;
+    if (command == null || command.isEmpty) return null;
+
+    final path = app.launchCommand ?? app.repoName;
+    final quoted = "'${path.replaceAll("'", "'\\''")}'";
+    try {
+      final result = await Process.run(
+        'sh',
+        ['-c', command.replaceAll('{path}', quoted)],
+        environment: app.env,
+      ).timeout(const Duration(seconds: 10));
+      // Plenty of tools print their version on stderr
+      return parseVersionOutput('${result.stdout}\n${result.stderr}');
+    } catch (e) {
+      print('Version command for ${app.displayName} failed: $e');
+      return null;
+    }
+  }
+
+  /// Runs [app]'s post-update command through `sh`, with the installed
+  /// [version] in `AUTONOMIX_VERSION`. Returns the exit code and combined
+  /// output; a command that can't start or times out counts as exit code -1.
+  Future<({int exitCode, String output})> runPostUpdateCommand(TrackedApp app, String version) async {
+    try {
+      final result = await Process.run(
+        'sh',
+        ['-c', app.postUpdateCommand!],
+        environment: {
+          ...?app.env,
+          'AUTONOMIX_APP': app.displayName,
+          'AUTONOMIX_VERSION': version,
+        },
+      ).timeout(const Duration(minutes: 2));
+      final output = '${result.stdout}${result.stderr}'.trim();
+      return (exitCode: result.exitCode, output: output);
+    } catch (e) {
+      return (exitCode: -1, output: e.toString());
+    }
+  }
+
+  /// Looks on the system for how [app] is really installed, trying the
+  /// recorded install type first. Returns null when nothing is found.
+  Future<InstallProbe?> probeInstallation(TrackedApp app) async {
+    final names = {
+      if (app.packageName != null) app.packageName!,
+      app.repoName,
+      app.repoName.toLowerCase(),
+    };
+    final probes = <InstallType, Future<InstallProbe?> Function()>{
+      InstallType.appImage: () => _probeAppImage(app),
+      InstallType.deb: () =>
+          _probePackage(InstallType.deb, names, 'dpkg-query', (n) => ['-W', '-f', r'${Version}', n]),
+      InstallType.rpm: () =>
+          _probePackage(InstallType.rpm, names, 'rpm', (n) => ['-q', '--queryformat', '%{VERSION}', n]),
+      // Prints "name version"
+      InstallType.pacman: () => _probePackage(InstallType.pacman, names, 'pacman', (n) => ['-Q', n],
+          parseVersion: (output) => output.split(' ').last),
+      InstallType.flatpak: () => _probeFlatpak(names),
+      InstallType.snap: () => _probeSnap(names),
+      InstallType.binary: () => _probeLocalBin(names),
+    };
+    final order = [
+      if (app.installType != null && probes.containsKey(app.installType)) app.installType!,
+      ...probes.keys.where((t) => t != app.installType),
+    ];
+    for (final type in order) {
+      try {
+        final found = await probes[type]!();
+        if (found != null) return found;
+      } catch (_) {
+        // Tool missing on this distro
+      }
+    }
+    return null;
+  }
+
+  Future<InstallProbe?> _probeAppImage(TrackedApp app) async {
+    final recorded = app.launchCommand;
+    if (recorded != null && recorded.toLowerCase().endsWith('.appimage') && await File(recorded).exists()) {
+      return (type: InstallType.appImage, packageName: null, launchCommand: recorded, version: null);
+    }
+    final dir = await _appImageDir;
+    await for (final entity in dir.list()) {
+      final name = p.basename(entity.path).toLowerCase();
+      if (entity is File && name.endsWith('.appimage') && name.contains(app.repoName.toLowerCase())) {
+        return (type: InstallType.appImage, packageName: null, launchCommand: entity.path, version: null);
+      }
+    }
+    return null;
+  }
+
+  Future<InstallProbe?> _probePackage(
+    InstallType type,
+    Set<String> names,
+    String tool,
+    List<String> Function(String name) args, {
+    String Function(String output)? parseVersion,
+  }) async {
+    for (final name in names) {
+      final result = await Process.run(tool, args(name));
+      final output = result.stdout.toString().trim();
+      final version = parseVersion == null || output.isEmpty ? output : parseVersion(output);
+      if (result.exitCode == 0 && version.isNotEmpty) {
+        return (type: type, packageName: name, launchCommand: null, version: version);
+      }
+    }
+    return null;
+  }
+
+  Future<InstallProbe?> _probeFlatpak(Set<String> names) async {
+    final result = await Process.run('flatpak', ['list', '--app', '--columns=application,version']);
+    if (result.exitCode != 0) return null;
+    final lowerNames = names.map((n) => n.toLowerCase()).toSet();
+    for (final line in const LineSplitter().convert(result.stdout.toString())) {
+      final columns = line.split('\t');
+      final id = columns.first.trim();
+      final lastSegment = id.split('.').last.toLowerCase();
+      if (lowerNames.contains(id.toLowerCase()) || lowerNames.contains(lastSegment)) {
+        final version = columns.length > 1 ? columns[1].trim() : '';
+        return (
+          type: InstallType.flatpak,
+          packageName: id,
+          launchCommand: null,
+          version: version.isEmpty ? null : version,
+        );
+      }
+    }
+    return null;
+  }
+
+  Future<InstallProbe?> _probeSnap(Set<String> names) async {
+    for (final name in names) {
+      final result = await Process.run('snap', ['list', name]);
+      if (result.exitCode != 0) continue;
+      // Header line, then "name  version  rev  tracking  publisher  notes"
+      final lines = const LineSplitter().convert(result.stdout.toString());
+      final columns = lines.length > 1 ? lines[1].split(RegExp(r'\s+')) : const <String>[];
+      return (
+        type: InstallType.snap,
+        packageName: name,
+        launchCommand: null,
+        version: columns.length > 1 ? columns[1] : null,
+      );
+    }
+    return null;
+  }
+
+  Future<InstallProbe?> _probeLocalBin(Set<String> names) async {
+    final home = Platform.environment['HOME'];
+    if (home == null) return null;
+    for (final name in names) {
+      final path = p.join(home, '.local', 'bin', name);
+      if (await File(path).exists()) {
+        return (type: InstallType.binary, packageName: null, launchCommand: path, version: null);
+      }
+    }
+    return null;
+  }
+
+  /// The other tracked app in [apps] whose installed binary (or one of
+  /// the programs installed from its archive) is already called [name], if
+  /// any. Installing [app] under that name would overwrite it, and
+  /// uninstalling either would remove both.
+  static TrackedApp? binaryNameOwner(List<TrackedApp> apps, TrackedApp app, String name) {
+    for (final other in apps) {
+      if (other.id == app.id || other.installType != InstallType.binary) continue;
+      final files = [if (other.launchCommand != null) other.launchCommand!, ...?other.installedFiles];
+      if (files.any((file) => p.basename(file) == name)) return other;
+    }
+    return null;
+  }
+
+  /// Why [app] can't install a program as [name] in [binDir]: it belongs
+  /// to another of the [tracked] apps, or a file [app] didn't install is
+  /// already there. Null when the name is free or already [app]'s.
+  static Future<String?> binaryNameConflict(
+    String name,
+    Directory binDir,
+    TrackedApp? app,
+    List<TrackedApp> tracked,
+  ) async {
+    final path = p.join(binDir.path, name);
+    if (app != null) {
+      if (app.launchCommand == path || (app.installedFiles?.contains(path) ?? false)) return null;
+      final owner = binaryNameOwner(tracked, app, name);
+      if (owner != null) return 'already belongs to ${owner.displayName}';
+    }
+    if (await File(path).exists()) return 'would replace a file Autonomix did not install';
+    return null;
+  }
+
+  /// Fallback binary name when the plain one is taken, e.g. `acme-server`
+  /// for acme/server.
+  static String disambiguatedBinaryName(TrackedApp app) =>
+      '${app.repoOwner}-${app.repoName}'.toLowerCase();
+
+  /// Starts [executable] without tying its lifetime or output to Autonomix.
+  Future<void> _startDetached(
+    String executable, [
+    List<String> args = const [],
+    Map<String, String>? environment,
+  ]) async {
+    await Process.start(executable, args, environment: environment, mode: ProcessStartMode.detached);
+  }
+
+  /// Looks for a `.desktop` file named after the package (or repo) in the
+  /// usual XDG application directories and returns its desktop id.
+  Future<String?> _findDesktopEntryId(TrackedApp app) async {
+    final home = Platform.environment['HOME'];
+    final dirs = [
+      if (home != null) p.join(home, '.local', 'share', 'applications'),
+      '/usr/local/share/applications',
+      '/usr/share/applications',
+    ];
+    final names = {
+      if (app.packageName != null) app.packageName!,
+      app.repoName,
+      app.repoName.toLowerCase(),
+    };
+
+    for (final dir in dirs) {
+      for (final name in names) {
+        if (await File(p.join(dir, '$name.desktop')).exists()) return name;
+      }
+    }
+    return null;
+  }
+
   InstallType? identifyAssetType(String filename) {
     final lower = filename.toLowerCase();
+    if (companionExtensions.any(lower.endsWith)) return null;
+    // A split asset is installed through its first piece; the rest are
+    // fetched alongside it.
+    final split = splitPart(filename);
+    if (split != null) return split.part == 1 ? identifyAssetType(split.joinedName) : null;
     if (lower.endsWith('.deb')) return InstallType.deb;
     if (lower.endsWith('.rpm')) return InstallType.rpm;
+    // Checked before other tarballs, which these also are
+    if (lower.endsWith('.pkg.tar.zst') || lower.endsWith('.pkg.tar.xz')) return InstallType.pacman;
     if (lower.endsWith('.appimage')) return InstallType.appImage;
-    if (lower.endsWith('.flatpak')) return InstallType.flatpak;
+    if (lower.endsWith('.flatpak') || lower.endsWith('.flatpakref')) return InstallType.flatpak;
     if (lower.endsWith('.snap')) return InstallType.snap;
+    if (isArchive(lower)) return isPrebuiltArchive(lower) ? InstallType.binary : InstallType.source;
+    // Releases ship programs, not source, as zips (GitHub adds source ones
+    // itself); other platforms' zips are weeded out by [isLinuxAsset]
+    if (isZip(lower)) return InstallType.binary;
     return null;
   }
 
-  Future<void> _runPrivileged(String command, List<String> args) async {
-    // Try pkexec first
+  static String hostArchitecture() {
+    final abi = Abi.current();
+    if (abi == Abi.linuxX64) return 'x86_64';
+    if (abi == Abi.linuxArm64) return 'aarch64';
+    if (abi == Abi.linuxIA32) return 'x86';
+    if (abi == Abi.linuxArm) return 'armv7';
+    if (abi == Abi.linuxRiscv64) return 'riscv64';
+    return 'unknown';
+  }
+
+  /// Rejects assets clearly meant for another OS.
+  static bool isLinuxAsset(String filename) {
+    final lower = filename.toLowerCase();
+    const foreign = ['windows', 'win32', 'win64', 'darwin', 'macos', 'osx', 'apple', 'freebsd'];
+    const foreignExtensions = ['.exe', '.msi', '.dmg', '.pkg', '.apk'];
+    if (foreignExtensions.any(lower.endsWith)) return false;
+    // Short "win" only counts as a whole token, e.g. tool-win-x64.zip
+    if (RegExp(r'(^|[-_.])win([-_.]|$)').hasMatch(lower)) return false;
+    return !foreign.any(lower.contains);
+  }
+
+  /// Assets without an architecture in their name are assumed universal.
+  static bool matchesArchitecture(String filename, String hostArch) {
+    final arch = ReleaseAsset.architectureOf(filename);
+    return arch == null || arch == hostArch;
+  }
+
+  /// Per-app hard filter: the name must contain at least one [include]
+  /// keyword (when any are set) and none of the [exclude] keywords.
+  /// Matching is case-insensitive.
+  static bool matchesKeywords(String filename, {List<String>? include, List<String>? exclude}) {
+    final lower = filename.toLowerCase();
+    bool hit(String keyword) => keyword.isNotEmpty && lower.contains(keyword.toLowerCase());
+    if (include != null && include.any((k) => k.isNotEmpty) && !include.any(hit)) return false;
+    return exclude == null || !exclude.any(hit);
+  }
+
+  /// Patterns [matchesAssetPattern] already warned about.
+  static final Set<String> _brokenPatterns = {};
+
+  /// Per-app hard filter: whether [filename] matches [pattern] (see
+  /// [ReleaseAsset.patternRegExp]). Always true without a pattern, and for
+  /// one that doesn't compile, which is logged and ignored rather than
+  /// failing every pick for the app.
+  static bool matchesAssetPattern(String filename, String? pattern) {
+    if (pattern == null || pattern.trim().isEmpty) return true;
+    try {
+      return ReleaseAsset.patternRegExp(pattern).hasMatch(filename);
+    } on FormatException catch (e) {
+      if (_brokenPatterns.add(pattern)) print('Warning: ignoring invalid asset pattern "$pattern": ${e.message}');
+      return true;
+    }
+  }
+
+  /// [assetName] with the release version replaced by `{version}`, so the
+  /// asset a user picked can be recognised again in later releases, e.g.
+  /// `tool-qt_1.2.0_amd64.deb` becomes `tool-qt_{version}_amd64.deb`.
+  static String assetSignature(String assetName, String tagName) {
+    final version = tagName.replaceFirst(RegExp(r'^[vV]'), '');
+    var signature = assetName;
+    for (final token in {tagName, version}) {
+      if (token.isNotEmpty) signature = signature.replaceAll(token, '{version}');
+    }
+    // Tags like "release-42" don't show up in names; fall back to any
+    // dotted version number.
+    if (signature == assetName) {
+      signature = signature.replaceAll(RegExp(r'\d+(\.\d+)+'), '{version}');
+    }
+    return signature;
+  }
+
+  /// The asset in [assets] with the same [signature] for [tagName].
+  static ReleaseAsset? findBySignature(List<ReleaseAsset> assets, String signature, String tagName) {
+    for (final asset in assets) {
+      if (assetSignature(asset.name, tagName) == signature) return asset;
+    }
+    return null;
+  }
+
+  /// Points an asset gets in [scoreAsset] for each step it is ahead in
+  /// [typePriority].
+  static const int typeStepWeight = 20;
+
+  /// For naming the host's architecture rather than none at all.
+  static const int exactArchWeight = 30;
+
+  /// For a musl or static build, with [preferMusl]. Less than a type step,
+  /// so it only decides between builds of the same type.
+  static const int portableBuildWeight = 15;
+
+  /// Added for a build named after the host's distribution, and taken off
+  /// for one named after another distribution. A distribution the host's
+  /// is only based on (`ID_LIKE`) gets half.
+  static const int distroWeight = 25;
+
+  /// Distribution names seen in asset names, with the `/etc/os-release` id
+  /// they stand for.
+  static const Map<String, String> distroTokens = {
+    'ubuntu': 'ubuntu',
+    'debian': 'debian',
+    'fedora': 'fedora',
+    'centos': 'centos',
+    'rhel': 'rhel',
+    'el7': 'rhel',
+    'el8': 'rhel',
+    'el9': 'rhel',
+    'opensuse': 'opensuse',
+    'suse': 'suse',
+    'sles': 'suse',
+    'alpine': 'alpine',
+  };
+
+  /// This host's `ID` and `ID_LIKE` from `/etc/os-release`, e.g. `ubuntu`
+  /// and `debian`.
+  static final List<String> hostDistros = _readHostDistros();
+
+  static List<String> _readHostDistros() {
+    try {
+      return parseOsRelease(File('/etc/os-release').readAsStringSync());
+    } on FileSystemException {
+      return const [];
+    }
+  }
+
+  /// The `ID` and `ID_LIKE` entries of an os-release file. Ids like
+  /// `opensuse-leap` also count as their family, `opensuse`.
+  static List<String> parseOsRelease(String content) {
+    final ids = <String>[];
+    for (final line in const LineSplitter().convert(content)) {
+      final match = RegExp(r'^(ID|ID_LIKE)=(.*)$').firstMatch(line.trim());
+      if (match == null) continue;
+      for (final id in match.group(2)!.replaceAll(RegExp('["\']'), '').toLowerCase().split(RegExp(r'\s+'))) {
+        if (id.isEmpty) continue;
+        ids.add(id);
+        if (id.contains('-')) ids.add(id.split('-').first);
+      }
+    }
+    return ids.toSet().toList();
+  }
+
+  /// The distribution [filename] says it was built for, as an os-release
+  /// id, if any.
+  static String? distroOf(String filename) {
+    final lower = filename.toLowerCase();
+    for (final entry in distroTokens.entries) {
+      if (RegExp('(?<![a-z0-9])${entry.key}(?![a-z])').hasMatch(lower)) return entry.value;
+    }
+    return null;
+  }
+
+  /// How well an asset of [type] named [name] suits this host; higher is
+  /// better. The notes say what the score is made of, for the install
+  /// history.
+  static ({int score, List<String> notes}) scoreAsset(
+    String name,
+    InstallType type, {
+    required String hostArch,
+    bool preferMusl = false,
+    List<String> distros = const [],
+  }) {
+    final typeScore = (typePriority.length - typePriority.indexOf(type)) * typeStepWeight;
+    var score = typeScore;
+    final notes = ['${type.displayName} +$typeScore'];
+    if (ReleaseAsset.architectureOf(name) == hostArch) {
+      score += exactArchWeight;
+      notes.add('built for $hostArch +$exactArchWeight');
+    }
+    if (preferMusl && isPortableBuild(name)) {
+      score += portableBuildWeight;
+      notes.add('musl or static build +$portableBuildWeight');
+    }
+    final distro = distroOf(name);
+    if (distro != null && distros.isNotEmpty) {
+      final weight = distro == distros.first
+          ? distroWeight
+          : distros.contains(distro)
+              ? distroWeight ~/ 2
+              : -distroWeight;
+      score += weight;
+      notes.add('built for $distro ${weight > 0 ? '+' : ''}$weight');
+    }
+    return (score: score, notes: notes);
+  }
+
+  /// The Linux assets for this host's architecture among the [available]
+  /// types, best [scoreAsset] first; equal scores keep release order.
+  /// [include]/[exclude] keywords are applied after the Linux and
+  /// architecture filters. [preferMusl] defaults to this service's setting
+  /// and [distros] to [hostDistros].
+  List<({ReleaseAsset asset, InstallType type, int score, List<String> notes})> rankAssets(
+    List<ReleaseAsset> assets,
+    Set<InstallType> available, {
+    String? hostArch,
+    List<String>? include,
+    List<String>? exclude,
+    String? pattern,
+    bool? preferMusl,
+    List<String>? distros,
+  }) {
+    final arch = hostArch ?? hostArchitecture();
+    final ranked = <({ReleaseAsset asset, InstallType type, int score, List<String> notes})>[];
+    for (final asset in assets) {
+      final type = identifyAssetType(asset.name);
+      if (type == null || !available.contains(type)) continue;
+      if (!isLinuxAsset(asset.name) || !matchesArchitecture(asset.name, arch)) continue;
+      if (!matchesKeywords(asset.name, include: include, exclude: exclude)) continue;
+      if (!matchesAssetPattern(asset.name, pattern)) continue;
+      final scored = scoreAsset(asset.name, type,
+          hostArch: arch, preferMusl: preferMusl ?? this.preferMusl, distros: distros ?? hostDistros);
+      ranked.add((asset: asset, type: type, score: scored.score, notes: scored.notes));
+    }
+    // List.sort isn't stable, so break ties by position explicitly
+    final order = {for (var i = 0; i < ranked.length; i++) ranked[i].asset: i};
+    ranked.sort((a, b) => a.score != b.score ? b.score - a.score : order[a.asset]! - order[b.asset]!);
+    return ranked;
+  }
+
+  /// Picks an asset without asking: the best ranked by [rankAssets].
+  ({ReleaseAsset asset, InstallType type, String reason})? findBestAsset(
+    List<ReleaseAsset> assets,
+    Set<InstallType> available, {
+    String? hostArch,
+    List<String>? include,
+    List<String>? exclude,
+    String? pattern,
+    bool? preferMusl,
+    List<String>? distros,
+  }) {
+    final arch = hostArch ?? hostArchitecture();
+    final ranked = rankAssets(assets, available,
+        hostArch: arch,
+        include: include,
+        exclude: exclude,
+        pattern: pattern,
+        preferMusl: preferMusl,
+        distros: distros);
+    if (ranked.isEmpty) return null;
+    final best = ranked.first;
+    final filtered = (include?.isNotEmpty ?? false) || (exclude?.isNotEmpty ?? false);
+    final patterned = pattern?.trim() ?? '';
+    return (
+      asset: best.asset,
+      type: best.type,
+      reason: 'best match for $arch, scoring ${best.score} (${best.notes.join(', ')})'
+          '${filtered ? ' after the app\'s asset keyword filters' : ''}'
+          '${patterned.isEmpty ? '' : ' among assets matching $patterned'}',
+    );
+  }
+
+  /// Whether [filename] names a musl or fully static build.
+  static bool isPortableBuild(String filename) {
+    final lower = filename.toLowerCase();
+    return ReleaseAsset.libcOf(lower) == 'musl' || RegExp(r'(^|[-_.])static([-_.]|$)').hasMatch(lower);
+  }
+
+  /// The host's C library, `musl` or `glibc`, going by `ldd --version`
+  /// (which musl's ldd prints to stderr).
+  static Future<String> hostLibc() async {
+    try {
+      final result = await Process.run('ldd', ['--version']);
+      return '${result.stdout}${result.stderr}'.toLowerCase().contains('musl') ? 'musl' : 'glibc';
+    } on ProcessException {
+      return 'glibc';
+    }
+  }
+
+  /// Human-readable record of which assets were considered, how each
+  /// compatible one scored and why [chosen] won, kept in the install
+  /// history so odd picks can be explained later. Pass the same filters as
+  /// the pick so the ranking matches it; assets left out say why.
+  String describeAssetSelection({
+    required List<ReleaseAsset> assets,
+    required ReleaseAsset chosen,
+    required String reason,
+    required Set<InstallType> available,
+    String? hostArch,
+    List<String>? include,
+    List<String>? exclude,
+    String? pattern,
+  }) {
+    final arch = hostArch ?? hostArchitecture();
+    final lines = ['Chose ${chosen.name}: $reason', 'Candidates:'];
+    final ranked = rankAssets(assets, available, hostArch: arch, include: include, exclude: exclude, pattern: pattern);
+    for (final (index, entry) in ranked.indexed) {
+      final mark = entry.asset.name == chosen.name ? ' (chosen)' : '';
+      lines.add('  ${index + 1}. ${entry.asset.name} [${entry.type.name}]$mark'
+          ' score ${entry.score}: ${entry.notes.join(', ')}');
+    }
+
+    final rankedNames = {for (final entry in ranked) entry.asset.name};
+    final left = <String>[];
+    final ignored = <String>[];
+    for (final asset in assets) {
+      if (rankedNames.contains(asset.name)) continue;
+      final type = identifyAssetType(asset.name);
+      if (type == null) {
+        ignored.add(asset.name);
+        continue;
+      }
+      final notes = [
+        if (asset.name == chosen.name) 'chosen',
+        if (!available.contains(type))
+          'not installable here'
+        else if (!isLinuxAsset(asset.name))
+          'not a Linux build'
+        else if (!matchesArchitecture(asset.name, arch))
+          'built for ${ReleaseAsset.architectureOf(asset.name)}'
+        else if (!matchesKeywords(asset.name, include: include, exclude: exclude))
+          'filtered out by the asset keywords'
+        else if (!matchesAssetPattern(asset.name, pattern))
+          'does not match the asset pattern'
+        else
+          'not for this system',
+      ];
+      left.add('  - ${asset.name} [${type.name}] (${notes.join(', ')})');
+    }
+    if (left.isNotEmpty) lines.addAll(['Left out:', ...left]);
+    if (ignored.isNotEmpty) lines.add('Ignored: ${ignored.join(', ')}');
+    return lines.join('\n');
+  }
+
+  /// The AppImageUpdate `.zsync` file published for [appImage], if any,
+  /// for a future delta-update path.
+  static ReleaseAsset? zsyncFor(List<ReleaseAsset> assets, ReleaseAsset appImage) {
+    final name = '${appImage.name}.zsync'.toLowerCase();
+    for (final asset in assets) {
+      if (asset.name.toLowerCase() == name) return asset;
+    }
+    return null;
+  }
+
+  /// Piece names of split assets: `name.part1`, `name.part02`, `name.001`.
+  static final RegExp _partPattern = RegExp(r'^(.+)\.(?:part(\d+)|(\d{3}))$', caseSensitive: false);
+
+  /// For one piece of a multi-part asset, the name of the joined file and
+  /// the piece's 1-based number, e.g. `tool.tar.gz.002` gives
+  /// `tool.tar.gz` and 2.
+  static ({String joinedName, int part})? splitPart(String filename) {
+    final match = _partPattern.firstMatch(filename);
+    if (match == null) return null;
+    final part = int.parse(match.group(2) ?? match.group(3)!);
+    if (part == 0) return null;
+    return (joinedName: match.group(1)!, part: part);
+  }
+
+  /// Every piece of the multi-part asset [first] belongs to, in order.
+  /// Throws when pieces are missing from [assets]. Releases don't state how
+  /// many pieces there are, so only gaps before the last one are caught;
+  /// a missing final piece shows up as a size mismatch or a broken file.
+  static List<ReleaseAsset> partsOf(List<ReleaseAsset> assets, ReleaseAsset first) {
+    final joinedName = splitPart(first.name)!.joinedName;
+    final byNumber = <int, ReleaseAsset>{};
+    for (final asset in assets) {
+      final split = splitPart(asset.name);
+      if (split != null && split.joinedName == joinedName) byNumber[split.part] = asset;
+    }
+    final last = byNumber.keys.fold<int>(0, (a, b) => a > b ? a : b);
+    final missing = [
+      for (var i = 1; i <= last; i++)
+        if (!byNumber.containsKey(i)) i,
+    ];
+    if (missing.isNotEmpty) {
+      throw Exception('$joinedName is split into $last parts but part ${missing.join(', ')} '
+          '${missing.length == 1 ? 'is' : 'are'} missing from the release');
+    }
+    return [for (var i = 1; i <= last; i++) byNumber[i]!];
+  }
+
+  /// Signatures of checksum lists (`checksums.txt.sig`, `SHA256SUMS.gpg`),
+  /// never checksum lists themselves.
+  static const List<String> signatureExtensions = ['.sig', '.asc', '.gpg', '.pem', '.minisig'];
+
+  /// The checksum file covering [assetName] in [assets]: a dedicated
+  /// `<asset>.sha256` or `<asset>.sha256sum` first, then a release-wide list
+  /// such as `SHA256SUMS` or `checksums.txt`. Files named after another
+  /// asset, and signatures, are never taken for the list.
+  static ReleaseAsset? checksumAssetFor(List<ReleaseAsset> assets, String assetName) {
+    final name = assetName.toLowerCase();
+    for (final candidate in assets) {
+      final lower = candidate.name.toLowerCase();
+      if (lower == '$name.sha256' || lower == '$name.sha256sum') return candidate;
+    }
+    final others = [
+      for (final other in assets)
+        if (other.name.toLowerCase() != name) '${other.name.toLowerCase()}.',
+    ];
+    for (final candidate in assets) {
+      final lower = candidate.name.toLowerCase();
+      if (signatureExtensions.any(lower.endsWith) || others.any(lower.startsWith)) continue;
+      if (lower.contains('sha256sum') || lower.startsWith('checksums')) return candidate;
+    }
+    return null;
+  }
+
+  /// The SHA-256 listed for [assetName] in a `sha256sum`-style [content]
+  /// (`<hash>  <name>`, or `<hash> *<name>` for binary mode). A file holding
+  /// nothing but a single hash is taken to be for [assetName].
+  static String? expectedSha256(String content, String assetName) {
+    final pattern = RegExp(r'^([0-9a-fA-F]{64})(?:\s+\*?(.+))?$');
+    final matches = [
+      for (final line in const LineSplitter().convert(content))
+        if (pattern.firstMatch(line.trim()) case final match?) match,
+    ];
+    for (final match in matches) {
+      final name = match.group(2)?.trim();
+      if (name != null && p.basename(name) == assetName) return match.group(1)!.toLowerCase();
+    }
+    if (matches.length == 1 && matches.single.group(2) == null) {
+      return matches.single.group(1)!.toLowerCase();
+    }
+    return null;
+  }
+
+  /// SHA-256 of [file] via `sha256sum`.
+  Future<String> sha256Of(File file) async {
+    final result = await Process.run('sha256sum', [file.path]);
+    if (result.exitCode != 0) {
+      throw Exception('sha256sum failed: ${result.stderr.toString().trim()}');
+    }
+    return result.stdout.toString().split(RegExp(r'\s+')).first.toLowerCase();
+  }
+
+  /// Whether [key] is a minisign public key rather than an OpenPGP one.
+  static bool isMinisignKey(String key) => !key.contains('BEGIN PGP PUBLIC KEY BLOCK');
+
+  /// The key line of a minisign public key, which may be pasted with the
+  /// `untrusted comment:` line of its `.pub` file.
+  static String minisignKeyOf(String key) => const LineSplitter()
+      .convert(key)
+      .map((line) => line.trim())
+      .lastWhere((line) => line.isNotEmpty && !line.startsWith('untrusted comment:'), orElse: () => '');
+
+  /// The detached signature of [assetName] in [assets] that [key] can
+  /// check: `<asset>.minisig` for a minisign key, `<asset>.asc` or
+  /// `<asset>.sig` for an OpenPGP key.
+  static ReleaseAsset? signatureAssetFor(List<ReleaseAsset> assets, String assetName, String key) {
+    final extensions = isMinisignKey(key) ? const ['.minisig'] : const ['.asc', '.sig'];
+    for (final extension in extensions) {
+      final wanted = '$assetName$extension'.toLowerCase();
+      for (final candidate in assets) {
+        if (candidate.name.toLowerCase() == wanted) return candidate;
+      }
+    }
+    return null;
+  }
+
+  /// Checks that [signature] is a valid signature of [file] by [key], with
+  /// minisign or, for OpenPGP keys, gpg in a throwaway keyring. Throws when
+  /// it isn't or the tool is missing.
+  Future<void> verifySignature(File file, File signature, String key) async {
+    final name = p.basename(file.path);
+    if (isMinisignKey(key)) {
+      final ProcessResult result;
+      try {
+        result = await Process.run(
+            'minisign', ['-V', '-P', minisignKeyOf(key), '-m', file.path, '-x', signature.path]);
+      } on ProcessException {
+        throw AutonomixException(
+            ErrorKind.unsupported, 'minisign is needed to check the signature of $name, but it is not installed');
+      }
+      if (result.exitCode != 0) {
+        throw AutonomixException(
+            ErrorKind.installFailed, 'Signature check of $name failed: ${result.stderr.toString().trim()}');
+      }
+      return;
+    }
+
+    final home = await Directory.systemTemp.createTemp('autonomix-gpg');
     try {
-      final result = await Process.run('pkexec', [command, ...args]);
+      final keyFile = File(p.join(home.path, 'key.asc'));
+      await keyFile.writeAsString(key);
+      final ProcessResult result;
+      try {
+        final imported = await Process.run('gpg', ['--homedir', home.path, '--batch', '--import', keyFile.path]);
+        if (imported.exitCode != 0) {
+          throw AutonomixException(
+              ErrorKind.installFailed, 'The signing key could not be read: ${imported.stderr.toString().trim()}');
+        }
+        result = await Process.run('gpg', ['--homedir', home.path, '--batch', '--verify', signature.path, file.path]);
+      } on ProcessException {
+        throw AutonomixException(
+            ErrorKind.unsupported, 'gpg is needed to check the signature of $name, but it is not installed');
+      }
       if (result.exitCode != 0) {
-        throw Exception('Command failed: ${result.stderr}');
+        throw AutonomixException(
+            ErrorKind.installFailed, 'Signature check of $name failed: ${result.stderr.toString().trim()}');
       }
-    } catch (e) {
-      throw Exception('Failed to run privileged command: $e');
+    } finally {
+      await home.delete(recursive: true);
+    }
+  }
+
+  static bool isArchive(String filename) {
+    final lower = filename.toLowerCase();
+    return archiveExtensions.any(lower.endsWith);
+  }
+
+  static bool isZip(String filename) => filename.toLowerCase().endsWith('.zip');
+
+  /// Whether an archive's name says it holds programs built for a platform,
+  /// like `tool-linux-x86_64.tar.gz`, rather than source like
+  /// `tool-1.2.0.tar.gz`.
+  static bool isPrebuiltArchive(String filename) {
+    final lower = filename.toLowerCase();
+    return ReleaseAsset.architectureOf(lower) != null ||
+        ReleaseAsset.libcOf(lower) != null ||
+        RegExp(r'(^|[-_.])(linux|static)([-_.]|$)').hasMatch(lower);
+  }
+
+  /// Parses the key/value pairs of a `.flatpakref` file's `[Flatpak Ref]` group.
+  static Map<String, String> parseFlatpakRef(String content) {
+    final fields = <String, String>{};
+    var inRefGroup = false;
+    for (final rawLine in content.split('\n')) {
+      final line = rawLine.trim();
+      if (line.isEmpty || line.startsWith('#')) continue;
+      if (line.startsWith('[')) {
+        inRefGroup = line == '[Flatpak Ref]';
+        continue;
+      }
+      if (!inRefGroup) continue;
+      final eq = line.indexOf('=');
+      if (eq <= 0) continue;
+      fields[line.substring(0, eq).trim()] = line.substring(eq + 1).trim();
+    }
+    return fields;
+  }
+
+  /// Makes sure the remotes a `.flatpakref` depends on are configured, so
+  /// installing from a remote the user has never added doesn't fail.
+  Future<void> _addFlatpakRefRemotes(File ref) async {
+    final fields = parseFlatpakRef(await ref.readAsString());
+
+    final runtimeRepo = fields['RuntimeRepo'];
+    if (runtimeRepo != null && runtimeRepo.isNotEmpty) {
+      final name = p.basenameWithoutExtension(Uri.parse(runtimeRepo).path);
+      await _addFlatpakRemote(['--from', name, runtimeRepo]);
+    }
+
+    // Without a key flatpak install sets up the app remote itself.
+    final url = fields['Url'];
+    final name = fields['SuggestRemoteName'];
+    final gpgKey = fields['GPGKey'];
+    if (url != null && name != null && gpgKey != null) {
+      final keyFile = File(p.join((await _downloadsDir).path, '$name.gpg'));
+      await keyFile.writeAsBytes(base64.decode(gpgKey));
+      try {
+        await _addFlatpakRemote(['--gpg-import=${keyFile.path}', name, url]);
+      } finally {
+        await keyFile.delete();
+      }
+    }
+  }
+
+  Future<void> _addFlatpakRemote(List<String> args) async {
+    final res = await Process.run(
+      'flatpak',
+      ['remote-add', '--if-not-exists', '--user', ...args],
+    );
+    if (res.exitCode != 0) {
+      throw Exception('Failed to add flatpak remote ${args.last}: ${res.stderr}');
+    }
+  }
+
+  /// Warns when [installedPath] is not what runs for its command name,
+  /// because another copy comes earlier on PATH (e.g. /usr/bin). Returns
+  /// null when the installed binary wins or the check isn't possible.
+  Future<String?> pathShadowWarning(String installedPath) async {
+    try {
+      final result = await Process.run('which', ['-a', p.basename(installedPath)]);
+      final shadow = shadowingBinary(result.stdout.toString(), installedPath);
+      if (shadow == null) return null;
+      return 'Running "${p.basename(installedPath)}" starts $shadow, not the copy installed at '
+          '$installedPath, because it comes first on PATH.';
+    } catch (_) {
+      return null;
+    }
+  }
+
+  /// The first match in `which -a` [output] when it isn't [installedPath].
+  static String? shadowingBinary(String output, String installedPath) {
+    final matches = output.split('\n').map((l) => l.trim()).where((l) => l.isNotEmpty).toList();
+    if (matches.isEmpty || !matches.contains(installedPath)) return null;
+    return matches.first == installedPath ? null : matches.first;
+  }
+
+  /// The first of [guiInstallers] for [type] that is on PATH.
+  Future<(String, List<String>)?> detectGuiInstaller(InstallType type) async {
+    for (final installer in guiInstallers[type] ?? const <(String, List<String>)>[]) {
+      if (await isOnPath(installer.$1)) return installer;
+    }
+    return null;
+  }
+
+  /// Installs [file] through a graphical installer if [useGuiInstaller] is
+  /// set and one is available. Returns false when the caller should fall
+  /// back to running the package manager as administrator.
+  Future<bool> _installWithGui(File file, InstallType type, String? packageName) async {
+    if (!useGuiInstaller) return false;
+    final installer = await detectGuiInstaller(type);
+    if (installer == null) return false;
+
+    final (command, args) = installer;
+    final result = await Process.run(command, [...args, file.path]);
+    if (result.exitCode != 0) {
+      throw Exception('$command failed: ${result.stderr}');
+    }
+
+    // The graphical installers exit normally when the user closes them
+    // without installing, so check the package actually landed.
+    if (packageName != null && !await isPackageInstalled(type, packageName)) {
+      throw Exception('$packageName was not installed by $command');
+    }
+    return true;
+  }
+
+  /// Whether the package manager for [type] has [packageName] installed.
+  Future<bool> isPackageInstalled(InstallType type, String packageName) async {
+    try {
+      switch (type) {
+        case InstallType.deb:
+          final check = await Process.run('dpkg-query', ['-W', '-f', r'${Status}', packageName]);
+          return check.exitCode == 0 && check.stdout.toString().contains('install ok installed');
+        case InstallType.rpm:
+          return (await Process.run('rpm', ['-q', packageName])).exitCode == 0;
+        case InstallType.pacman:
+          return (await Process.run('pacman', ['-Q', packageName])).exitCode == 0;
+        default:
+          return false;
+      }
+    } on ProcessException {
+      return false;
+    }
+  }
+
+  /// The command prefix that runs a command as administrator: pkexec, or
+  /// sudo where polkit is missing. Probed once and cached. Null when
+  /// neither is available.
+  Future<List<String>?> detectEscalator() {
+    return _escalator ??= _probeEscalator();
+  }
+
+  Future<List<String>?> _probeEscalator() async => chooseEscalator(
+        hasPkexec: await isOnPath('pkexec'),
+        hasSudo: await isOnPath('sudo'),
+        environment: Platform.environment,
+      );
+
+  /// Like [detectEscalator], but throws when there is no way to become
+  /// administrator, before anything is attempted.
+  Future<List<String>> requireEscalator() async {
+    final escalator = await detectEscalator();
+    if (escalator == null) {
+      throw AutonomixException(
+          ErrorKind.unsupported, 'No privilege escalation tool found; install polkit (pkexec) or sudo');
+    }
+    return escalator;
+  }
+
+  /// pkexec shows a graphical password prompt. Without it, sudo can ask
+  /// through the `SUDO_ASKPASS` helper if one is set; otherwise it only
+  /// works without a password (`-n`), since there is no terminal to ask on.
+  static List<String>? chooseEscalator({
+    required bool hasPkexec,
+    required bool hasSudo,
+    required Map<String, String> environment,
+  }) {
+    if (hasPkexec) return const ['pkexec'];
+    if (!hasSudo) return null;
+    final askPass = environment['SUDO_ASKPASS'];
+    return askPass != null && askPass.isNotEmpty ? const ['sudo', '-A'] : const ['sudo', '-n'];
+  }
+
+  Future<void> _runPrivileged(String command, List<String> args) async {
+    final escalator = await requireEscalator();
+    final ProcessResult result;
+    try {
+      result = await Process.run(escalator.first, [...escalator.skip(1), command, ...args]);
+    } on ProcessException catch (e) {
+      throw AutonomixException(ErrorKind.installFailed, 'Failed to run ${escalator.first}: ${e.message}', cause: e);
+    }
+    if (result.exitCode != 0) {
+      throw privilegedFailure(escalator.first, command, result.exitCode, result.stderr.toString());
+    }
+  }
+
+  /// The error for a run of [command] through [escalator] that exited with
+  /// [exitCode]. pkexec itself exits 126 when the password prompt is
+  /// dismissed and 127 when authorization fails, which are told apart from
+  /// [command] failing.
+  static AutonomixException privilegedFailure(String escalator, String command, int exitCode, String stderr) {
+    switch (escalator == 'pkexec' ? exitCode : null) {
+      case 126:
+        return AutonomixException(ErrorKind.authCancelled, 'The administrator password prompt was dismissed');
+      case 127:
+        return AutonomixException(ErrorKind.authCancelled, 'Not authorized to run $command as administrator');
+      default:
+        return AutonomixException(
+            ErrorKind.installFailed, '$command failed with exit code $exitCode: ${stderr.trim()}');
     }
   }
 }
@@ -0,0 +1,79 @@
+import 'dart:io';
+import '../models/tracked_app.dart';
+import 'database_service.dart';
+
+/// Desktop notifications through `notify-send`, so no plugin is needed.
+class NotificationService {
+  /// Settings key; notifications are on unless this is explicitly false.
+  static const String settingKey = 'notifications_enabled';
+
+  static bool enabledIn(Map<String, dynamic> settings) =>
+      DatabaseService.settingOr(settings, settingKey, true);
+
+  /// Shows a notification. Failures (e.g. no notification daemon) are
+  /// reported as false rather than thrown.
+  Future<bool> notify(String summary, String body) async {
+    try {
+      final result = await Process.run(
+        'notify-send',
+        ['--app-name=Autonomix', summary, body],
+        environment: await _sessionBusEnvironment(),
+      );
+      return result.exitCode == 0;
+    } catch (_) {
+      return false;
+    }
+  }
+
+  /// Shows a notification with a button labelled [action] and waits until
+  /// it is dismissed. Returns whether the button (or the notification
+  /// itself) was clicked. Falls back to a plain notification where
+  /// `notify-send` is too old for actions.
+  Future<bool> notifyWithAction(String summary, String body, String action) async {
+    try {
+      final result = await Process.run(
+        'notify-send',
+        ['--app-name=Autonomix', '--wait', '--action=default=$action', summary, body],
+        environment: await _sessionBusEnvironment(),
+      );
+      if (result.exitCode == 0) return result.stdout.toString().trim() == 'default';
+    } catch (_) {
+      return false;
+    }
+    await notify(summary, body);
+    return false;
+  }
+
+  /// Jobs started by cron lack the session bus address `notify-send`
+  /// needs. Points it at the user's bus when the socket is there.
+  static Future<Map<String, String>?> _sessionBusEnvironment() async {
+    final address = sessionBusAddress(Platform.environment, await _uid());
+    if (address == null || !await File(address.substring('unix:path='.length)).exists()) return null;
+    return {'DBUS_SESSION_BUS_ADDRESS': address};
+  }
+
+  /// The standard bus address of the user [uid] when [environment] has
+  /// none, or null when it already has one.
+  static String? sessionBusAddress(Map<String, String> environment, String? uid) {
+    if (environment['DBUS_SESSION_BUS_ADDRESS']?.isNotEmpty ?? false) return null;
+    final runtimeDir = environment['XDG_RUNTIME_DIR'] ?? (uid == null ? null : '/run/user/$uid');
+    return runtimeDir == null ? null : 'unix:path=$runtimeDir/bus';
+  }
+
+  static Future<String?> _uid() async {
+    try {
+      final status = await File('/proc/self/status').readAsString();
+      return RegExp(r'^Uid:\s+(\d+)', multiLine: true).firstMatch(status)?.group(1);
+    } on FileSystemException {
+      return null;
+    }
+  }
+
+  /// Title and body announcing the updates in [apps], or null when empty.
+  static ({String summary, String body})? describeUpdates(List<TrackedApp> apps) {
+    if (apps.isEmpty) return null;
+    final summary = apps.length == 1 ? 'Update available' : '${apps.length} updates available';
+    final body = apps.map((a) => '${a.displayName} ${a.latestVersion}').join('\n');
+    return (summary: summary, body: body);
+  }
+}
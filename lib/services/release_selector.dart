@@ -0,0 +1,68 @@
+import 'dart:io';
+import '../models/release.dart';
+import '../models/release_channel.dart';
+import '../models/tracked_app.dart';
+import 'database_service.dart';
+
+/// Picks which release counts as "latest" for a tracked app. GitHub returns
+/// releases newest first, so the first acceptable entry wins.
+class ReleaseSelector {
+  /// Drafts are never offered for install unless explicitly allowed, e.g.
+  /// when testing unpublished builds with `AUTONOMIX_ALLOW_DRAFTS=1`.
+  static bool allowDrafts = Platform.environment['AUTONOMIX_ALLOW_DRAFTS'] == '1';
+
+  /// Channel for apps without an override of their own, loaded from the
+  /// [ReleaseChannel.settingKey] setting.
+  static ReleaseChannel defaultChannel = ReleaseChannel.stable;
+
+  static ReleaseChannel channelIn(Map<String, dynamic> settings) =>
+      ReleaseChannel.fromString(DatabaseService.settingOr<String?>(settings, ReleaseChannel.settingKey, null)) ??
+      ReleaseChannel.stable;
+
+  /// The app's own channel wins over [defaultChannel].
+  static ReleaseChannel effectiveChannel(TrackedApp app) => app.channel ?? defaultChannel;
+
+  static bool isSelectable(Release release) => allowDrafts || !release.draft;
+
+  /// [releases] without the ones that must never be installed.
+  static List<Release> selectable(List<Release> releases) =>
+      releases.where(isSelectable).toList();
+
+  static bool matchesTagPrefix(Release release, String? tagPrefix) {
+    if (tagPrefix == null || tagPrefix.isEmpty) return true;
+    return release.tagName.startsWith(tagPrefix);
+  }
+
+  /// Whether [release] may be offered to [app] at all, given its channel
+  /// and tag prefix.
+  static bool accepts(Release release, TrackedApp app) {
+    if (!isSelectable(release)) return false;
+    if (release.prerelease && effectiveChannel(app) != ReleaseChannel.prerelease) return false;
+    return matchesTagPrefix(release, app.tagPrefix);
+  }
+
+  static Release? selectLatest(List<Release> releases, TrackedApp app) {
+    for (final release in releases) {
+      if (accepts(release, app)) return release;
+    }
+    return null;
+  }
+
+  /// The releases in [releases] published before [latest] that [app]
+  /// accepts, newest first. Fallbacks when [latest] has nothing to install.
+  static List<Release> olderThan(List<Release> releases, Release latest, TrackedApp app) {
+    final index = releases.indexWhere((release) => release.tagName == latest.tagName);
+    return [
+      for (final release in releases.skip(index + 1))
+        if (accepts(release, app)) release,
+    ];
+  }
+
+  /// Whether the plain `/releases/latest` endpoint is good enough, or the
+  /// release list has to be filtered locally. The former never returns
+  /// pre-releases.
+  static bool needsReleaseList(TrackedApp app) {
+    if (effectiveChannel(app) == ReleaseChannel.prerelease) return true;
+    return app.tagPrefix != null && app.tagPrefix!.isNotEmpty;
+  }
+}
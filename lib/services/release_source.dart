@@ -0,0 +1,23 @@
+import '../models/release.dart';
+
+/// The release API of one forge. [GitHubService] implements it for GitHub
+/// and hands out the others through `GitHubService.sourceFor`, so the rest
+/// of the app only deals with one service.
+abstract class ReleaseSource {
+  /// Newest release that is neither a draft nor a pre-release. Throws
+  /// `ReleaseNotFoundException` when there is none.
+  Future<Release> getLatestRelease(String owner, String repo);
+
+  /// Throws `ReleaseNotFoundException` when no release has [tag].
+  Future<Release> getReleaseByTag(String owner, String repo, String tag);
+
+  /// One page of releases, newest first.
+  Future<List<Release>> getReleases(String owner, String repo, {int perPage = 10, int page = 1});
+
+  /// Repository details in the shape of GitHub's `/repos/{owner}/{repo}`
+  /// response, as far as the forge has them.
+  Future<Map<String, dynamic>> getRepository(String owner, String repo);
+
+  /// Where and how to download [asset].
+  ({String url, Map<String, String> headers}) downloadRequestFor(ReleaseAsset asset);
+}
@@ -0,0 +1,160 @@
+import 'dart:convert';
+import 'dart:io';
+import 'package:path/path.dart' as p;
+import '../models/install_type.dart';
+import 'app_paths.dart';
+import 'autonomix_exception.dart';
+
+class PendingSelfUpdate {
+  final String packagePath;
+  final InstallType installType;
+  final String version;
+
+  PendingSelfUpdate({
+    required this.packagePath,
+    required this.installType,
+    required this.version,
+  });
+
+  Map<String, dynamic> toMap() {
+    return {
+      'package_path': packagePath,
+      'install_type': installType.name,
+      'version': version,
+    };
+  }
+
+  factory PendingSelfUpdate.fromMap(Map<String, dynamic> map) {
+    return PendingSelfUpdate(
+      packagePath: map['package_path'] as String,
+      installType: InstallType.fromString(map['install_type'] as String?)!,
+      version: map['version'] as String,
+    );
+  }
+}
+
+/// Updating Autonomix while it runs is fragile (a deb/rpm replaces the very
+/// binary we're executing), so self-updates happen in two phases: the new
+/// package is staged with a marker file, then a detached helper installs it
+/// once this process has exited and relaunches the app. If the helper never
+/// gets to run, the marker survives the restart and the update can be
+/// resumed.
+class SelfUpdateService {
+  static const Set<InstallType> supportedTypes = {
+    InstallType.deb,
+    InstallType.rpm,
+    InstallType.appImage,
+  };
+
+  Future<Directory> get _stagingDir async {
+    final dataDir = await AppPaths.supportDirectory();
+    final dir = Directory(p.join(dataDir.path, 'self-update'));
+    if (!await dir.exists()) {
+      await dir.create(recursive: true);
+    }
+    return dir;
+  }
+
+  Future<File> get _markerFile async => File(p.join((await _stagingDir).path, 'pending.json'));
+
+  Future<PendingSelfUpdate> stage(File download, InstallType type, String version) async {
+    if (!supportedTypes.contains(type)) {
+      throw AutonomixException(ErrorKind.unsupported, 'Self-update is not supported for ${type.displayName} installs');
+    }
+
+    final staged = await download.rename(p.join((await _stagingDir).path, p.basename(download.path)));
+    final update = PendingSelfUpdate(
+      packagePath: staged.path,
+      installType: type,
+      version: version,
+    );
+    await (await _markerFile).writeAsString(jsonEncode(update.toMap()));
+    return update;
+  }
+
+  Future<PendingSelfUpdate?> pending() async {
+    final marker = await _markerFile;
+    if (!await marker.exists()) return null;
+
+    try {
+      final update = PendingSelfUpdate.fromMap(jsonDecode(await marker.readAsString()));
+      if (!await File(update.packagePath).exists()) {
+        await clear();
+        return null;
+      }
+      return update;
+    } catch (e) {
+      print('Discarding unreadable self-update marker: $e');
+      await clear();
+      return null;
+    }
+  }
+
+  Future<void> clear() async {
+    final marker = await _markerFile;
+    if (!await marker.exists()) return;
+    try {
+      final update = PendingSelfUpdate.fromMap(jsonDecode(await marker.readAsString()));
+      final staged = File(update.packagePath);
+      if (await staged.exists()) await staged.delete();
+    } catch (_) {}
+    await marker.delete();
+  }
+
+  /// Spawns the helper that finishes [update] after we exit, then exits.
+  /// Package installs run through [escalator], as picked by
+  /// `InstallerService.detectEscalator`, and fail without one.
+  Future<void> applyAfterExit(
+    PendingSelfUpdate update, {
+    bool relaunch = true,
+    List<String>? escalator,
+  }) async {
+    final marker = await _markerFile;
+    final appImagePath = Platform.environment['APPIMAGE'];
+    final relaunchPath = update.installType == InstallType.appImage && appImagePath != null
+        ? appImagePath
+        : Platform.resolvedExecutable;
+
+    final List<String> installCommand;
+    if ((update.installType == InstallType.deb || update.installType == InstallType.rpm) && escalator == null) {
+      throw AutonomixException(
+          ErrorKind.unsupported, 'No privilege escalation tool found; install polkit (pkexec) or sudo');
+    }
+    switch (update.installType) {
+      case InstallType.deb:
+        installCommand = [...escalator!, 'dpkg', '-i', update.packagePath];
+      case InstallType.rpm:
+        installCommand = [...escalator!, 'rpm', '-U', update.packagePath];
+      case InstallType.appImage:
+        if (appImagePath == null) {
+          throw Exception('Not running from an AppImage, nothing to replace');
+        }
+        installCommand = ['install', '-m', '755', update.packagePath, appImagePath];
+      default:
+        throw AutonomixException(
+            ErrorKind.unsupported, 'Self-update is not supported for ${update.installType.displayName} installs');
+    }
+
+    const script = r'''
+pid="$1"; pkg="$2"; marker="$3"; relaunch="$4"; shift 4
+while kill -0 "$pid" 2>/dev/null; do sleep 1; done
+"$@" && rm -f "$marker" "$pkg" && [ -n "$relaunch" ] && exec "$relaunch"
+''';
+
+    await Process.start(
+      'sh',
+      [
+        '-c',
+        script,
+        'autonomix-self-update',
+        '$pid',
+        update.packagePath,
+        marker.path,
+        relaunch ? relaunchPath : '',
+        ...installCommand,
+      ],
+      mode: ProcessStartMode.detached,
+    );
+    exit(0);
+  }
+}
@@ -1,28 +1,207 @@
+import 'dart:async';
+import 'dart:io';
 import 'package:flutter/material.dart';
+import 'package:flutter/services.dart';
 import 'package:provider/provider.dart';
+import 'package:url_launcher/url_launcher.dart';
+import '../app_info.dart';
+import '../models/app_metadata.dart';
+import '../models/app_sort.dart';
+import '../models/release.dart';
+import '../models/release_channel.dart';
+import '../models/release_provider.dart';
 import '../models/tracked_app.dart';
 import '../models/install_type.dart';
+import '../services/autonomix_exception.dart';
 import '../services/database_service.dart';
+import '../services/error_log_service.dart';
 import '../services/github_service.dart';
+import '../services/install_manager.dart';
+import '../services/install_tracker.dart';
 import '../services/installer_service.dart';
+import '../services/notification_service.dart';
+import '../services/proxy_config.dart';
+import '../services/release_selector.dart';
+import '../services/self_update_service.dart';
 import 'widgets/app_list_item.dart';
 import 'widgets/add_app_dialog.dart';
+import 'widgets/app_settings_dialog.dart';
+import 'widgets/connection_test_dialog.dart';
+import 'widgets/error_log_dialog.dart';
+import 'widgets/github_token_dialog.dart';
+import 'widgets/history_dialog.dart';
+import 'widgets/import_snippet_dialog.dart';
+import 'widgets/install_version_dialog.dart';
+import 'widgets/provider_headers_dialog.dart';
+import 'widgets/proxy_dialog.dart';
+import 'widgets/raw_entry_dialog.dart';
+import 'widgets/update_all_dialog.dart';
 
 class HomeScreen extends StatefulWidget {
   const HomeScreen({super.key});
 
+  /// "Autonomix (3 updates)" while updates are pending, so they are
+  /// visible even when the window is in the background.
+  static String windowTitle(int updates) => switch (updates) {
+        0 => 'Autonomix',
+        1 => 'Autonomix (1 update)',
+        _ => 'Autonomix ($updates updates)',
+      };
+
+  /// Apps in [after] that show an update for a release they didn't have
+  /// in [before], so each release is announced once.
+  static List<TrackedApp> newUpdates(List<TrackedApp> before, List<TrackedApp> after, DateTime now) {
+    final previous = {for (final app in before) app.id: app.latestVersion};
+    return after.where((app) => app.showsUpdate(now) && previous[app.id] != app.latestVersion).toList();
+  }
+
+  /// Whether [app] matches a search: a case-insensitive substring of its
+  /// display name or `owner/repo`. An empty query matches everything.
+  static bool matchesSearch(TrackedApp app, String query) {
+    final q = query.trim().toLowerCase();
+    if (q.isEmpty) return true;
+    return app.displayName.toLowerCase().contains(q) ||
+        '${app.repoOwner}/${app.repoName}'.toLowerCase().contains(q);
+  }
+
   @override
   State<HomeScreen> createState() => _HomeScreenState();
 }
 
 class _HomeScreenState extends State<HomeScreen> {
+  /// Implemented by the Linux runner to set the native window title.
+  static const _windowChannel = MethodChannel('autonomix/window');
+
   List<TrackedApp> _apps = [];
   bool _isLoading = true;
+  late final InstallTracker _installTracker;
+  bool _advancedMode = false;
+  String? _windowTitle;
+  Set<int> _busyIds = {};
+
+  /// Null while the search field is closed.
+  String? _search;
+  final _searchController = TextEditingController();
+
+  AppSort _sort = AppSort.name;
+
+  /// Hours between automatic checks of each app; 0 turns them off.
+  int _autoCheckHours = 24;
+  Timer? _autoCheckTimer;
+  bool _checking = false;
+  bool _notificationsEnabled = true;
+
+  /// Closing the window hides it to the tray, where checks keep running.
+  bool _runInBackground = false;
+
+  List<TrackedApp> get _visibleApps => _sort.sort(
+        _search == null ? _apps : _apps.where((a) => HomeScreen.matchesSearch(a, _search!)).toList(),
+        DateTime.now(),
+      );
 
   @override
   void initState() {
     super.initState();
-    _loadApps();
+    _installTracker = context.read<InstallTracker>();
+    _installTracker.addListener(_onInstallTrackerChanged);
+    _windowChannel.setMethodCallHandler(_onWindowCall);
+    _loadSettings()
+        .then((_) => _loadApps())
+        .then((_) => _checkForUpdates(onlyDue: true));
+    _registerSelf();
+  }
+
+  @override
+  void dispose() {
+    _installTracker.removeListener(_onInstallTrackerChanged);
+    _searchController.dispose();
+    _autoCheckTimer?.cancel();
+    _windowChannel.setMethodCallHandler(null);
+    super.dispose();
+  }
+
+  /// Installs outlive the details sheet, so rows are refreshed when their
+  /// operation finishes rather than when the sheet closes.
+  void _onInstallTrackerChanged() {
+    final busy = _installTracker.busyIds;
+    final finished = _busyIds.difference(busy);
+    setState(() => _busyIds = busy);
+    for (final id in finished) {
+      _reloadApp(id);
+    }
+  }
+
+  Future<void> _registerSelf() async {
+    final db = context.read<DatabaseService>();
+    final installer = context.read<InstallerService>();
+    final errorLog = context.read<ErrorLogService>();
+    try {
+      // Rows still soft-deleted here outlived their undo window, e.g. because
+      // Autonomix quit before the snackbar closed.
+      await db.purgeDeletedApps();
+      final installType = await installer.detectSelfInstallType();
+      final changed = await db.registerSelf(appVersion, installType);
+      if (changed && mounted) await _loadApps();
+    } catch (e) {
+      errorLog.record('Register Autonomix', e);
+    }
+    if (mounted) await _resumeSelfUpdate();
+  }
+
+  /// Offers to finish a self-update that was staged but never applied,
+  /// e.g. because Autonomix was killed before the helper could run.
+  Future<void> _resumeSelfUpdate() async {
+    final selfUpdate = context.read<SelfUpdateService>();
+    final installer = context.read<InstallerService>();
+    final update = await selfUpdate.pending();
+    if (update == null || !mounted) return;
+
+    if (update.version.replaceFirst(RegExp(r'^[vV]'), '') == appVersion) {
+      await selfUpdate.clear();
+      return;
+    }
+
+    final apply = await showDialog<bool>(
+      context: context,
+      builder: (context) => AlertDialog(
+        title: const Text('Finish Updating Autonomix'),
+        content: Text('Autonomix ${update.version} was downloaded but not installed yet. '
+            'Restart now to finish the update?'),
+        actions: [
+          TextButton(
+            onPressed: () async {
+              await selfUpdate.clear();
+              if (context.mounted) Navigator.pop(context, false);
+            },
+            child: const Text('Discard'),
+          ),
+          TextButton(
+            onPressed: () => Navigator.pop(context, false),
+            child: const Text('Later'),
+          ),
+          FilledButton(
+            onPressed: () => Navigator.pop(context, true),
+            child: const Text('Restart Now'),
+          ),
+        ],
+      ),
+    );
+    if (apply == true) await selfUpdate.applyAfterExit(update, escalator: await installer.detectEscalator());
+  }
+
+  Future<void> _syncWindowTitle() async {
+    final now = DateTime.now();
+    final updates = _apps.where((a) => a.showsUpdate(now)).length;
+    final title = HomeScreen.windowTitle(updates);
+    if (title == _windowTitle) return;
+    _windowTitle = title;
+    try {
+      await _windowChannel.invokeMethod('setTitle', title);
+    } on MissingPluginException {
+      // Not running under the Linux runner, e.g. in tests
+    } on PlatformException catch (e) {
+      print('Could not set the window title: $e');
+    }
   }
 
   Future<void> _loadApps() async {
@@ -34,9 +213,11 @@ class _HomeScreenState extends State<HomeScreen> {
         _apps = apps;
         _isLoading = false;
       });
+      _syncWindowTitle();
     } catch (e) {
       setState(() => _isLoading = false);
       if (mounted) {
+        context.read<ErrorLogService>().record('Load apps', e);
         ScaffoldMessenger.of(context).showSnackBar(
           SnackBar(content: Text('Error loading apps: $e')),
         );
@@ -44,6 +225,31 @@ class _HomeScreenState extends State<HomeScreen> {
     }
   }
 
+  /// Refreshes a single row in place after an operation on one app, so the
+  /// list keeps its scroll position. Full reloads are for add/remove.
+  Future<void> _reloadApp(int? id) async {
+    if (id == null) return _loadApps();
+
+    final updated = await context.read<DatabaseService>().getApp(id);
+    if (!mounted) return;
+
+    final index = _apps.indexWhere((a) => a.id == id);
+    if (updated == null || index == -1) {
+      await _loadApps();
+      return;
+    }
+    setState(() => _apps[index] = updated);
+    _syncWindowTitle();
+  }
+
+  /// Stops flagging [app]'s latest release until a newer one comes out.
+  Future<void> _skipVersion(TrackedApp app) async {
+    final version = app.latestVersion;
+    if (app.id == null || version == null) return;
+    await context.read<DatabaseService>().ignoreVersion(app.id!, version);
+    await _reloadApp(app.id);
+  }
+
   Future<void> _addApp() async {
     final result = await showDialog<Map<String, String>>(
       context: context,
@@ -56,10 +262,15 @@ class _HomeScreenState extends State<HomeScreen> {
           result['owner']!,
           result['repo']!,
           result['name']!,
+          tagPrefix: result['tag_prefix'],
+          channel: ReleaseChannel.fromString(result['channel']),
+          provider: ReleaseProvider.fromString(result['provider']),
+          host: result['host'],
         );
         _loadApps();
       } catch (e) {
         if (mounted) {
+          context.read<ErrorLogService>().record('Add ${result['owner']}/${result['repo']}', e);
           ScaffoldMessenger.of(context).showSnackBar(
             SnackBar(content: Text('Error adding app: $e')),
           );
@@ -68,51 +279,461 @@ class _HomeScreenState extends State<HomeScreen> {
     }
   }
 
-  Future<void> _checkForUpdates() async {
+  Future<void> _loadSettings() async {
     final gh = context.read<GitHubService>();
+    final installer = context.read<InstallerService>();
+    final manager = context.read<InstallManager>();
+    final errorLog = context.read<ErrorLogService>();
+    try {
+      final db = context.read<DatabaseService>();
+      await db.migrate();
+      final settings = await db.getSettings();
+      ReleaseSelector.defaultChannel = ReleaseSelector.channelIn(settings);
+      gh.customHeaders = GitHubService.headersFromSetting(settings[GitHubService.headersSettingKey]);
+      gh.token = GitHubService.tokenFrom(settings, Platform.environment);
+      gh.etags = GitHubService.etagsFromJson(await db.getEtagCache());
+      installer.useGuiInstaller =
+          DatabaseService.settingOr(settings, InstallerService.guiInstallerSettingKey, false);
+      installer.verifyChecksums =
+          DatabaseService.settingOr(settings, InstallerService.checksumSettingKey, true);
+      installer.preferMusl = DatabaseService.settingOr(
+          settings, InstallerService.preferMuslSettingKey, await InstallerService.hostLibc() == 'musl');
+      manager.refreshConcurrency =
+          DatabaseService.settingOr(settings, InstallManager.refreshConcurrencySettingKey, 5);
+      final advanced = DatabaseService.settingOr(settings, _advancedModeKey, false);
+      final sort = AppSort.fromString(DatabaseService.settingOr<String?>(settings, AppSort.settingKey, null));
+      final autoCheckHours = DatabaseService.settingOr(settings, _autoCheckKey, 24);
+      final notifications = NotificationService.enabledIn(settings);
+      final background = DatabaseService.settingOr(settings, _backgroundKey, false);
+      if (mounted) {
+        setState(() {
+          _advancedMode = advanced;
+          _sort = sort ?? AppSort.name;
+          _notificationsEnabled = notifications;
+        });
+        if (background) await _setRunInBackground(true);
+        _applyAutoCheck(autoCheckHours);
+      }
+      final proxy = ProxyConfig.fromSettings(settings);
+      if (proxy != null) {
+        gh.useProxy(proxy);
+        installer.proxy = proxy;
+      }
+    } catch (e) {
+      errorLog.record('Load settings', e);
+    }
+  }
+
+  static const _advancedModeKey = 'advanced_mode';
+  static const _autoCheckKey = 'auto_check_hours';
+
+  /// How often the timer looks for apps that are due; each app is only
+  /// checked once its interval has passed.
+  static const _autoCheckTick = Duration(minutes: 15);
+
+  void _applyAutoCheck(int hours) {
+    _autoCheckHours = hours < 0 ? 0 : hours;
+    if (_autoCheckHours > 0) TrackedApp.defaultCheckInterval = Duration(hours: _autoCheckHours);
+    _autoCheckTimer?.cancel();
+    _autoCheckTimer =
+        _autoCheckHours > 0 ? Timer.periodic(_autoCheckTick, (_) => _checkForUpdates(onlyDue: true)) : null;
+  }
+
+  static const _backgroundKey = 'run_in_background';
+
+  /// Calls from the Linux runner's tray menu.
+  Future<void> _onWindowCall(MethodCall call) async {
+    if (call.method == 'checkNow' && mounted) await _checkForUpdates();
+  }
+
+  /// Tells the runner whether closing the window should hide it to the
+  /// tray. Returns false when this build has no tray, leaving it off.
+  Future<bool> _setRunInBackground(bool enabled) async {
+    bool supported;
+    try {
+      supported = await _windowChannel.invokeMethod<bool>('setRunInBackground', enabled) ?? false;
+    } on MissingPluginException {
+      supported = false;
+    } on PlatformException catch (e) {
+      print('Could not change background mode: $e');
+      supported = false;
+    }
+    if (mounted) setState(() => _runInBackground = enabled && supported);
+    return supported;
+  }
+
+  Future<void> _toggleRunInBackground() async {
     final db = context.read<DatabaseService>();
-    
-    for (var app in _apps) {
-      try {
-        final release = await gh.getLatestRelease(app.repoOwner, app.repoName);
-        final updatedApp = app.copyWith(
-          latestVersion: release.tagName,
-          lastChecked: DateTime.now(),
+    final enabled = !_runInBackground;
+    final supported = await _setRunInBackground(enabled);
+    if (enabled && !supported) {
+      if (mounted) {
+        ScaffoldMessenger.of(context).showSnackBar(
+          const SnackBar(content: Text('This build of Autonomix has no tray icon support')),
         );
-        await db.updateApp(updatedApp);
-      } catch (e) {
+      }
+      return;
+    }
+    await db.setSetting(_backgroundKey, enabled);
+  }
+
+  Future<void> _toggleNotifications() async {
+    setState(() => _notificationsEnabled = !_notificationsEnabled);
+    await context.read<DatabaseService>().setSetting(NotificationService.settingKey, _notificationsEnabled);
+  }
+
+  /// Announces updates found in the background on the desktop, since the
+  /// window may be hidden. Clicking the notification brings it forward.
+  Future<void> _notifyUpdates(NotificationService notifications, List<TrackedApp> updates) async {
+    final message = NotificationService.describeUpdates(updates);
+    if (message == null || !_notificationsEnabled) return;
+    final clicked = await notifications.notifyWithAction(message.summary, message.body, 'Show');
+    if (!clicked) return;
+    try {
+      await _windowChannel.invokeMethod('present');
+    } on MissingPluginException {
+      // Not running under the Linux runner
+    } on PlatformException catch (e) {
+      print('Could not show the window: $e');
+    }
+  }
+
+  Future<void> _editAutoCheck() async {
+    final db = context.read<DatabaseService>();
+    const choices = {0: 'Off', 6: 'Every 6 hours', 12: 'Every 12 hours', 24: 'Daily', 168: 'Weekly'};
+    final hours = await showDialog<int>(
+      context: context,
+      builder: (context) => SimpleDialog(
+        title: const Text('Check for updates automatically'),
+        children: [
+          for (final choice in choices.entries)
+            RadioListTile<int>(
+              value: choice.key,
+              groupValue: _autoCheckHours,
+              title: Text(choice.value),
+              onChanged: (value) => Navigator.pop(context, value),
+            ),
+        ],
+      ),
+    );
+    if (hours == null) return;
+    _applyAutoCheck(hours);
+    await db.setSetting(_autoCheckKey, hours);
+  }
+
+  Future<void> _toggleAdvancedMode() async {
+    setState(() => _advancedMode = !_advancedMode);
+    await context.read<DatabaseService>().setSetting(_advancedModeKey, _advancedMode);
+  }
+
+  Future<void> _toggleGuiInstaller() async {
+    final installer = context.read<InstallerService>();
+    installer.useGuiInstaller = !installer.useGuiInstaller;
+    await context
+        .read<DatabaseService>()
+        .setSetting(InstallerService.guiInstallerSettingKey, installer.useGuiInstaller);
+  }
+
+  /// Switches the global channel and rechecks, since apps following it may
+  /// now have a different latest release.
+  Future<void> _togglePrereleases() async {
+    final channel = ReleaseSelector.defaultChannel == ReleaseChannel.stable
+        ? ReleaseChannel.prerelease
+        : ReleaseChannel.stable;
+    ReleaseSelector.defaultChannel = channel;
+    await context.read<DatabaseService>().setSetting(ReleaseChannel.settingKey, channel.name);
+    await _checkForUpdates();
+  }
+
+  Future<void> _toggleChecksums() async {
+    final installer = context.read<InstallerService>();
+    installer.verifyChecksums = !installer.verifyChecksums;
+    await context
+        .read<DatabaseService>()
+        .setSetting(InstallerService.checksumSettingKey, installer.verifyChecksums);
+  }
+
+  Future<void> _togglePreferMusl() async {
+    final installer = context.read<InstallerService>();
+    installer.preferMusl = !installer.preferMusl;
+    await context.read<DatabaseService>().setSetting(InstallerService.preferMuslSettingKey, installer.preferMusl);
+  }
+
+  Future<void> _editGitHubToken() async {
+    final db = context.read<DatabaseService>();
+    final gh = context.read<GitHubService>();
+    final stored = await db.getSettingOr<String?>(GitHubService.tokenSettingKey, null);
+    if (!mounted) return;
+    final token = await showDialog<String>(
+      context: context,
+      builder: (context) => GitHubTokenDialog(
+        token: stored,
+        overriddenByEnvironment: Platform.environment.containsKey('GITHUB_TOKEN'),
+      ),
+    );
+    if (token == null) return;
+
+    await db.setSetting(GitHubService.tokenSettingKey, token.isEmpty ? null : token);
+    gh.token = GitHubService.tokenFrom(await db.getSettings(), Platform.environment);
+    gh.invalidateReleaseCache();
+  }
+
+  Future<void> _editProviderHeaders() async {
+    final gh = context.read<GitHubService>();
+    final headers = await showDialog<Map<String, Map<String, String>>>(
+      context: context,
+      builder: (context) => ProviderHeadersDialog(headers: gh.customHeaders),
+    );
+    if (headers == null || !mounted) return;
+
+    gh.customHeaders = headers;
+    await context.read<DatabaseService>().setSetting(GitHubService.headersSettingKey, headers);
+  }
+
+  Future<void> _editProxy() async {
+    final db = context.read<DatabaseService>();
+    final gh = context.read<GitHubService>();
+    final installer = context.read<InstallerService>();
+    final settings = await db.getSettings();
+    if (!mounted) return;
+    final result = await showDialog<({String url, String noProxy})>(
+      context: context,
+      builder: (context) => ProxyDialog(
+        url: settings[ProxyConfig.urlSettingKey] as String?,
+        noProxy: settings[ProxyConfig.noProxySettingKey] as String?,
+      ),
+    );
+    if (result == null) return;
+
+    await db.setSetting(ProxyConfig.urlSettingKey, result.url.isEmpty ? null : result.url);
+    await db.setSetting(ProxyConfig.noProxySettingKey, result.noProxy.isEmpty ? null : result.noProxy);
+    final proxy = ProxyConfig.fromSettings(await db.getSettings());
+    gh.useProxy(proxy);
+    installer.proxy = proxy;
+  }
+
+  Future<void> _importSnippet() async {
+    final app = await showDialog<TrackedApp>(
+      context: context,
+      builder: (context) => const ImportSnippetDialog(),
+    );
+    if (app == null || !mounted) return;
+
+    try {
+      await context.read<DatabaseService>().importApp(app);
+      _loadApps();
+    } catch (e) {
+      if (mounted) {
+        context.read<ErrorLogService>().record('Import ${app.repoOwner}/${app.repoName}', e);
+        ScaffoldMessenger.of(context).showSnackBar(
+          SnackBar(content: Text('Error importing app: $e')),
+        );
+      }
+    }
+  }
+
+  /// Checks every app for updates. Automatic checks pass [onlyDue] so apps
+  /// checked more recently than their interval are skipped; they don't run
+  /// at all when turned off. Only one check runs at a time.
+  Future<void> _checkForUpdates({bool onlyDue = false}) async {
+    if (onlyDue && _autoCheckHours == 0) return;
+    if (_checking) {
+      if (!onlyDue) {
+        ScaffoldMessenger.of(context).showSnackBar(
+          const SnackBar(content: Text('Already checking for updates')),
+        );
+      }
+      return;
+    }
+    final db = context.read<DatabaseService>();
+    final gh = context.read<GitHubService>();
+    final manager = context.read<InstallManager>();
+    final errorLog = context.read<ErrorLogService>();
+    final notifications = context.read<NotificationService>();
+    final now = DateTime.now();
+    final apps = onlyDue ? _apps.where((a) => a.isDueForCheck(now)).toList() : _apps;
+    if (apps.isEmpty) return;
+    // A manual refresh should see releases published in the last minutes
+    if (!onlyDue) gh.invalidateReleaseCache();
+
+    _checking = true;
+    try {
+      final summary = await manager.refreshAll(apps, onError: (app, e) {
         print('Error checking updates for ${app.displayName}: $e');
+        errorLog.record('Check updates for ${app.displayName}', e);
+      });
+      try {
+        await db.saveEtagCache(GitHubService.etagsToJson(gh.etags));
+      } catch (e) {
+        errorLog.record('Save ETag cache', e);
+      }
+      final rateLimit = summary.rateLimit;
+      if (rateLimit != null) {
+        // The rest would fail the same way; they stay due for the next check
+        errorLog.record('Check updates', rateLimit);
+        _showRateLimited(rateLimit.resetAt);
+      }
+      await _loadApps();
+      if (!mounted) return;
+      if (!onlyDue && rateLimit == null) {
+        ScaffoldMessenger.of(context).showSnackBar(
+          SnackBar(content: Text(summary.completionMessage(DateTime.now()))),
+        );
+      } else if (onlyDue) {
+        final found = HomeScreen.newUpdates(apps, summary.refreshed, DateTime.now());
+        if (found.isNotEmpty) {
+          final count = found.length;
+          ScaffoldMessenger.of(context).showSnackBar(
+            SnackBar(content: Text(count == 1 ? '1 new update available' : '$count new updates available')),
+          );
+          // Stays open until the notification is dismissed
+          unawaited(_notifyUpdates(notifications, found));
+        }
       }
+    } finally {
+      _checking = false;
     }
-    _loadApps();
+  }
+
+  void _showRateLimited(DateTime? resetAt) {
+    if (!mounted) return;
+    final message = resetAt == null
+        ? 'Rate limited by GitHub, try again later'
+        : 'Rate limited by GitHub, try again at ${TimeOfDay.fromDateTime(resetAt.toLocal()).format(context)}';
+    ScaffoldMessenger.of(context).showSnackBar(SnackBar(content: Text(message)));
   }
 
   @override
   Widget build(BuildContext context) {
     return Scaffold(
       appBar: AppBar(
-        title: const Text('Autonomix'),
+        title: _search == null
+            ? const Text('Autonomix')
+            : TextField(
+                controller: _searchController,
+                autofocus: true,
+                decoration: const InputDecoration(hintText: 'Search apps', border: InputBorder.none),
+                onChanged: (value) => setState(() => _search = value),
+              ),
         actions: [
+          IconButton(
+            icon: Icon(_search == null ? Icons.search : Icons.close),
+            onPressed: _toggleSearch,
+            tooltip: _search == null ? 'Search' : 'Close search',
+          ),
+          PopupMenuButton<AppSort>(
+            icon: const Icon(Icons.sort),
+            tooltip: 'Sort',
+            onSelected: _setSort,
+            itemBuilder: (context) => [
+              for (final sort in AppSort.values)
+                CheckedPopupMenuItem(value: sort, checked: sort == _sort, child: Text(sort.displayName)),
+            ],
+          ),
+          IconButton(
+            icon: const Icon(Icons.error_outline),
+            onPressed: _showErrorLog,
+            tooltip: 'Recent errors',
+          ),
           IconButton(
             icon: const Icon(Icons.refresh),
             onPressed: _checkForUpdates,
             tooltip: 'Check for updates',
           ),
+          PopupMenuButton<String>(
+            onSelected: (value) {
+              switch (value) {
+                case 'import':
+                  _importSnippet();
+                case 'install_all':
+                  _installAllUninstalled();
+                case 'update_all':
+                  _updateAll();
+                case 'recheck':
+                  _recheckInstalls();
+                case 'auto_check':
+                  _editAutoCheck();
+                case 'notifications':
+                  _toggleNotifications();
+                case 'background':
+                  _toggleRunInBackground();
+                case 'headers':
+                  _editProviderHeaders();
+                case 'proxy':
+                  _editProxy();
+                case 'github_token':
+                  _editGitHubToken();
+                case 'gui_installer':
+                  _toggleGuiInstaller();
+                case 'checksums':
+                  _toggleChecksums();
+                case 'prefer_musl':
+                  _togglePreferMusl();
+                case 'prereleases':
+                  _togglePrereleases();
+                case 'advanced':
+                  _toggleAdvancedMode();
+                case 'connection_test':
+                  showDialog(
+                    context: context,
+                    builder: (_) => ConnectionTestDialog(gh: context.read<GitHubService>()),
+                  );
+              }
+            },
+            itemBuilder: (context) => [
+              const PopupMenuItem(value: 'import', child: Text('Import from snippet')),
+              const PopupMenuItem(value: 'install_all', child: Text('Install all not yet installed')),
+              const PopupMenuItem(value: 'update_all', child: Text('Update all')),
+              const PopupMenuItem(value: 'recheck', child: Text('Recheck install types')),
+              const PopupMenuItem(value: 'auto_check', child: Text('Automatic update checks')),
+              const PopupMenuItem(value: 'github_token', child: Text('GitHub token')),
+              const PopupMenuItem(value: 'headers', child: Text('Custom request headers')),
+              const PopupMenuItem(value: 'proxy', child: Text('Proxy')),
+              const PopupMenuItem(value: 'connection_test', child: Text('Test connection')),
+              CheckedPopupMenuItem(
+                value: 'gui_installer',
+                checked: context.read<InstallerService>().useGuiInstaller,
+                child: const Text('Use graphical package installer'),
+              ),
+              CheckedPopupMenuItem(
+                value: 'checksums',
+                checked: context.read<InstallerService>().verifyChecksums,
+                child: const Text('Verify checksums'),
+              ),
+              CheckedPopupMenuItem(
+                value: 'prefer_musl',
+                checked: context.read<InstallerService>().preferMusl,
+                child: const Text('Prefer musl and static binaries'),
+              ),
+              CheckedPopupMenuItem(
+                value: 'prereleases',
+                checked: ReleaseSelector.defaultChannel == ReleaseChannel.prerelease,
+                child: const Text('Include pre-releases by default'),
+              ),
+              CheckedPopupMenuItem(
+                value: 'notifications',
+                checked: _notificationsEnabled,
+                child: const Text('Desktop notifications'),
+              ),
+              CheckedPopupMenuItem(
+                value: 'background',
+                checked: _runInBackground,
+                child: const Text('Keep running in the tray'),
+              ),
+              CheckedPopupMenuItem(
+                value: 'advanced',
+                checked: _advancedMode,
+                child: const Text('Advanced mode'),
+              ),
+            ],
+          ),
         ],
       ),
       body: _isLoading
           ? const Center(child: CircularProgressIndicator())
           : _apps.isEmpty
               ? const Center(child: Text('No apps tracked. Add one!'))
-              : ListView.builder(
-                  itemCount: _apps.length,
-                  itemBuilder: (context, index) {
-                    return AppListItem(
-                      app: _apps[index],
-                      onTap: () => _showAppDetails(_apps[index]),
-                    );
-                  },
-                ),
+              : _buildList(_visibleApps),
       floatingActionButton: FloatingActionButton(
         onPressed: _addApp,
         child: const Icon(Icons.add),
@@ -120,29 +741,239 @@ class _HomeScreenState extends State<HomeScreen> {
     );
   }
 
+  Widget _buildList(List<TrackedApp> apps) {
+    if (apps.isEmpty) return Center(child: Text('No apps match "${_search!.trim()}"'));
+    return ListView.builder(
+      itemCount: apps.length,
+      itemBuilder: (context, index) {
+        final app = apps[index];
+        return AppListItem(
+          app: app,
+          busy: _busyIds.contains(app.id),
+          paused: _installTracker.isPaused(app.id),
+          progress: _installTracker.progressOf(app.id),
+          onTap: () => _showAppDetails(app),
+          onSkipVersion: () => _skipVersion(app),
+        );
+      },
+    );
+  }
+
+  Future<void> _setSort(AppSort sort) async {
+    setState(() => _sort = sort);
+    await context.read<DatabaseService>().setSetting(AppSort.settingKey, sort.name);
+  }
+
+  void _toggleSearch() {
+    setState(() {
+      _search = _search == null ? '' : null;
+      _searchController.clear();
+    });
+  }
+
+  Future<void> _installAllUninstalled() async {
+    final pending = _apps.where((a) => !a.isInstalled).toList();
+    if (pending.isEmpty) {
+      ScaffoldMessenger.of(context).showSnackBar(
+        const SnackBar(content: Text('Every tracked app is already installed')),
+      );
+      return;
+    }
+
+    final manager = context.read<InstallManager>();
+    final errorLog = context.read<ErrorLogService>();
+    final summary = await manager.installAll(pending, onPaused: _showRateLimitPause);
+    summary.failed.forEach((name, error) => errorLog.record('Install $name', error));
+    if (mounted) _showBulkSummary('Bulk Install Finished', summary);
+  }
+
+  /// Maintenance pass that corrects install records against what is really
+  /// on the system.
+  Future<void> _recheckInstalls() async {
+    final installed = _apps.where((a) => a.isInstalled && !a.isSelf).toList();
+    final summary = await context.read<InstallManager>().recheckInstalls(installed);
+    await _loadApps();
+    if (mounted) _showBulkSummary('Recheck Finished', summary);
+  }
+
+  /// Resolves every pending update, lets the user pick which to apply, then
+  /// runs the chosen ones through the install queue. Autonomix itself is
+  /// left out since it updates through the staged self-update instead.
+  Future<void> _updateAll() async {
+    final now = DateTime.now();
+    final pending = _apps.where((a) => a.showsUpdate(now) && !a.isSelf).toList();
+    if (pending.isEmpty) {
+      ScaffoldMessenger.of(context).showSnackBar(
+        const SnackBar(content: Text('Everything is up to date')),
+      );
+      return;
+    }
+
+    final manager = context.read<InstallManager>();
+    final errorLog = context.read<ErrorLogService>();
+    final updates = <({TrackedApp app, InstallChoice choice})>[];
+    final unresolved = <({TrackedApp app, Object error})>[];
+    RateLimitException? rateLimit;
+    for (final app in pending) {
+      // Past a rate limit, installAll resolves the rest once it has waited
+      if (rateLimit != null) {
+        unresolved.add((app: app, error: rateLimit));
+        continue;
+      }
+      try {
+        final choice = await manager.resolve(app);
+        if (choice != null) updates.add((app: app, choice: choice));
+      } on RateLimitException catch (e) {
+        rateLimit = e;
+        unresolved.add((app: app, error: e));
+      } catch (e) {
+        errorLog.record('Check ${app.displayName}', e);
+        unresolved.add((app: app, error: e));
+      }
+    }
+    if (!mounted) return;
+    if (updates.isEmpty && unresolved.isEmpty) {
+      ScaffoldMessenger.of(context).showSnackBar(
+        const SnackBar(content: Text('No compatible packages for the pending updates')),
+      );
+      return;
+    }
+
+    final selected = await showDialog<Set<int>>(
+      context: context,
+      builder: (context) => UpdateAllDialog(updates: updates, unresolved: unresolved),
+    );
+    if (selected == null || selected.isEmpty) return;
+
+    final chosen = updates.where((u) => selected.contains(u.app.id)).toList();
+    final summary = await manager.installAll(
+      [
+        ...chosen.map((u) => u.app),
+        for (final u in unresolved)
+          if (selected.contains(u.app.id)) u.app,
+      ],
+      resolved: {for (final u in chosen) u.app.id!: u.choice},
+      onPaused: _showRateLimitPause,
+    );
+    summary.failed.forEach((name, error) => errorLog.record('Update $name', error));
+    if (mounted) _showBulkSummary('Update All Finished', summary);
+  }
+
+  void _showRateLimitPause(DateTime resumeAt) {
+    if (!mounted) return;
+    final time = TimeOfDay.fromDateTime(resumeAt).format(context);
+    ScaffoldMessenger.of(context).showSnackBar(
+      SnackBar(
+        content: Text('Paused due to rate limit, resuming at $time'),
+        duration: resumeAt.difference(DateTime.now()),
+      ),
+    );
+  }
+
+  void _showBulkSummary(String title, Object summary) {
+    showDialog(
+      context: context,
+      builder: (context) => AlertDialog(
+        title: Text(title),
+        content: Text(summary.toString()),
+        actions: [
+          FilledButton(
+            onPressed: () => Navigator.pop(context),
+            child: const Text('OK'),
+          ),
+        ],
+      ),
+    );
+  }
+
+  void _showErrorLog() {
+    showDialog(
+      context: context,
+      builder: (context) => ErrorLogDialog(log: context.read<ErrorLogService>()),
+    );
+  }
+
   Future<void> _showAppDetails(TrackedApp app) async {
-    await showModalBottomSheet(
+    final removed = await showModalBottomSheet<bool>(
       context: context,
-      builder: (context) => AppDetailsSheet(app: app),
+      builder: (context) => AppDetailsSheet(app: app, advanced: _advancedMode),
     );
-    _loadApps();
+    if (removed == true) {
+      await _removeApp(app);
+    } else {
+      await _reloadApp(app.id);
+    }
+  }
+
+  /// Soft-deletes [app] and only removes it for good once the undo
+  /// snackbar has gone away without being used.
+  Future<void> _removeApp(TrackedApp app) async {
+    final id = app.id;
+    if (id == null) return;
+    final db = context.read<DatabaseService>();
+    await db.softDeleteApp(id);
+    if (!mounted) return;
+    setState(() => _apps.removeWhere((a) => a.id == id));
+    _syncWindowTitle();
+
+    final reason = await ScaffoldMessenger.of(context)
+        .showSnackBar(SnackBar(
+          content: Text('Stopped tracking ${app.displayName}'),
+          duration: const Duration(seconds: 5),
+          action: SnackBarAction(label: 'Undo', onPressed: () {}),
+        ))
+        .closed;
+
+    if (reason == SnackBarClosedReason.action) {
+      await db.restoreApp(id);
+      if (mounted) await _loadApps();
+    } else {
+      await db.deleteApp(id);
+    }
   }
 }
 
 class AppDetailsSheet extends StatefulWidget {
   final TrackedApp app;
 
-  const AppDetailsSheet({super.key, required this.app});
+  /// Shows power-user tools such as the raw database entry editor.
+  final bool advanced;
+
+  const AppDetailsSheet({super.key, required this.app, this.advanced = false});
 
   @override
   State<AppDetailsSheet> createState() => _AppDetailsSheetState();
 }
 
 class _AppDetailsSheetState extends State<AppDetailsSheet> {
+  late TrackedApp _app = widget.app;
   bool _isInstalling = false;
   String? _statusMessage;
 
-  Future<void> _install(BuildContext context) async {
+  /// Bytes so far while a download runs; null in other phases.
+  ({int received, int? total})? _download;
+
+  late final Future<AppMetadata> _metadata;
+
+  /// Null until known (or when the release could not be fetched), in which
+  /// case Install stays enabled and any problem surfaces on click.
+  bool? _hasCompatibleAsset;
+
+  @override
+  void initState() {
+    super.initState();
+    _metadata = context.read<GitHubService>().getAppMetadata(_app);
+    _checkCompatibility();
+  }
+
+  Future<void> _checkCompatibility() async {
+    if (_app.isInstalled && !_app.hasUpdate) return;
+    final compatible = await context.read<InstallManager>().hasCompatibleAsset(_app);
+    if (mounted) setState(() => _hasCompatibleAsset = compatible);
+  }
+
+  /// Installs the latest release, or the one tagged [tag].
+  Future<void> _install(BuildContext context, {String? tag}) async {
     setState(() {
       _isInstalling = true;
       _statusMessage = 'Fetching releases...';
@@ -151,104 +982,264 @@ class _AppDetailsSheetState extends State<AppDetailsSheet> {
     try {
       final gh = context.read<GitHubService>();
       final installer = context.read<InstallerService>();
-      final db = context.read<DatabaseService>();
+      final manager = context.read<InstallManager>();
+
+      var release = tag == null
+          ? await gh.getLatestReleaseFor(_app)
+          : await gh.sourceFor(_app).getReleaseByTag(_app.repoOwner, _app.repoName, tag);
 
-      final release = await gh.getLatestRelease(widget.app.repoOwner, widget.app.repoName);
-      
-      // Find candidates
-      final candidates = <InstallType, dynamic>{}; // dynamic to avoid importing ReleaseAsset
-      for (var asset in release.assets) {
-        final type = installer.identifyAssetType(asset.name);
-        if (type != null) {
-          candidates[type] = asset;
+      final available = await installer.detectAvailableInstallers();
+      var candidates = manager.candidatesFor(_app, release);
+
+      // Nothing for this system in the latest release: offer an older one
+      String? fellBackFrom;
+      if (tag == null && !candidates.keys.any(available.contains)) {
+        final fallback = await manager.fallback(_app, release, available);
+        if (fallback != null) {
+          fellBackFrom = release.tagName;
+          release = fallback.release;
+          candidates = manager.candidatesFor(_app, release);
+          if (mounted) {
+            setState(() => _statusMessage =
+                '$fellBackFrom has no compatible package, installing ${release.tagName} instead...');
+          }
         }
       }
 
       if (candidates.isEmpty) {
-        throw Exception('No supported assets found in release');
+        throw AutonomixException(ErrorKind.noCompatibleAsset, 'No supported assets found in release');
       }
-
       if (!mounted) return;
 
-      // Show selection dialog
-      final selectedType = await showDialog<InstallType>(
-        context: context,
-        builder: (context) => SimpleDialog(
-          title: const Text('Select Package Type'),
-          children: candidates.keys.map((type) {
-            return SimpleDialogOption(
-              onPressed: () => Navigator.pop(context, type),
-              child: Padding(
-                padding: const EdgeInsets.symmetric(vertical: 8),
+      // Reuse the asset picked last time when this release has its equivalent
+      final signature = _app.preferredAssetSignature;
+      final found = signature == null
+          ? null
+          : InstallerService.findBySignature(release.assets, signature, release.tagName);
+      final preferred =
+          found != null && InstallerService.matchesAssetPattern(found.name, _app.assetPattern) ? found : null;
+      final preferredType = preferred == null ? null : installer.identifyAssetType(preferred.name);
+
+      final InstallType selectedType;
+      final ReleaseAsset asset;
+      final String reason;
+      if (preferred != null && preferredType != null && available.contains(preferredType)) {
+        selectedType = preferredType;
+        asset = preferred;
+        reason = 'same asset as picked before ($signature)';
+      } else {
+        // Show selection dialog, greying out formats this system can't install
+        final picked = await showDialog<InstallType>(
+          context: context,
+          builder: (context) => SimpleDialog(
+            title: const Text('Select Package Type'),
+            children: candidates.keys.map((type) {
+              final supported = available.contains(type);
+              final candidate = candidates[type]!;
+              return SimpleDialogOption(
+                onPressed: supported ? () => Navigator.pop(context, type) : null,
                 child: Row(
                   children: [
-                    Icon(_getIconForType(type)),
-                    const SizedBox(width: 12),
-                    Text(type.displayName),
+                    Expanded(
+                      child: Opacity(
+                        opacity: supported ? 1 : 0.4,
+                        child: Padding(
+                          padding: const EdgeInsets.symmetric(vertical: 8),
+                          child: Row(
+                            children: [
+                              Icon(_getIconForType(type)),
+                              const SizedBox(width: 12),
+                              Text(type.displayName),
+                              if (!supported) ...[
+                                const SizedBox(width: 12),
+                                const Text('(not available on this system)'),
+                              ],
+                            ],
+                          ),
+                        ),
+                      ),
+                    ),
+                    // Manual escape hatch, also for formats we can't install
+                    IconButton(
+                      icon: const Icon(Icons.link),
+                      tooltip: 'Copy download link',
+                      onPressed: () => _copyAssetLink(candidate),
+                    ),
+                    IconButton(
+                      icon: const Icon(Icons.open_in_browser),
+                      tooltip: 'Download in browser',
+                      onPressed: () => _downloadInBrowser(candidate),
+                    ),
                   ],
                 ),
-              ),
-            );
-          }).toList(),
-        ),
-      );
+              );
+            }).toList(),
+          ),
+        );
+
+        if (picked == null) {
+          setState(() => _isInstalling = false);
+          return;
+        }
+
+        selectedType = picked;
+        asset = candidates[selectedType]!;
+        reason = candidates.length == 1
+            ? 'only installable package type in the release'
+            : 'picked ${selectedType.displayName} in the package chooser';
+        // Remembered so later updates pick the equivalent asset unasked
+        _app = _app.copyWith(
+          preferredAssetSignature: InstallerService.assetSignature(asset.name, release.tagName),
+        );
+      }
+
+      void onStatus(String status) {
+        if (mounted) {
+          setState(() {
+            _statusMessage = status;
+            _download = null;
+          });
+        }
+      }
 
-      if (selectedType == null) {
-        setState(() => _isInstalling = false);
+      if (_app.isSelf && SelfUpdateService.supportedTypes.contains(selectedType)) {
+        // Verified like any other download before it replaces Autonomix
+        final file = await manager.download(_app, release, asset,
+            onStatus: onStatus, onProgress: _onDownloadProgress);
+        await _stageSelfUpdate(file, selectedType, release.tagName);
         return;
       }
 
-      final asset = candidates[selectedType]!;
-      
-      setState(() => _statusMessage = 'Downloading ${asset.name}...');
-      final file = await installer.downloadFile(asset.browserDownloadUrl, asset.name);
-
-      setState(() => _statusMessage = 'Installing...');
-      final result = await installer.installPackage(file, selectedType);
-
-      // Update DB
-      final updatedApp = widget.app.copyWith(
-        installedVersion: release.tagName,
-        installType: selectedType,
-        launchCommand: result.launchCommand,
-        packageName: result.packageName,
-        lastChecked: DateTime.now(),
+      String? pathWarning;
+      final updatedApp = await manager.install(
+        _app,
+        release,
+        asset,
+        selectedType,
+        reason: fellBackFrom == null ? reason : InstallManager.fallbackReason(fellBackFrom, reason),
+        onStatus: onStatus,
+        onProgress: _onDownloadProgress,
+        onWarning: (warning) => pathWarning = pathWarning == null ? warning : '$pathWarning $warning',
+        onNameCollision: _confirmBinaryName,
       );
-      await db.updateApp(updatedApp);
 
       if (mounted) {
+        final canLaunch = selectedType == InstallType.appImage || selectedType == InstallType.binary;
         Navigator.pop(context); // Close sheet
         ScaffoldMessenger.of(context).showSnackBar(
-          const SnackBar(content: Text('Installation successful')),
+          SnackBar(
+            content: Text(pathWarning == null ? 'Installation successful' : 'Installed. $pathWarning'),
+            duration: Duration(seconds: pathWarning == null ? 4 : 10),
+            action: canLaunch
+                ? SnackBarAction(
+                    label: 'Launch',
+                    onPressed: () => installer.launchApp(updatedApp),
+                  )
+                : null,
+          ),
         );
       }
     } catch (e) {
       if (mounted) {
+        final error = AutonomixException.from(e);
         setState(() {
           _isInstalling = false;
-          _statusMessage = 'Error: $e';
+          _download = null;
+          _statusMessage = error.describe();
         });
+        if (error.kind == ErrorKind.authCancelled) {
+          ScaffoldMessenger.of(context).showSnackBar(
+            const SnackBar(content: Text('Authentication cancelled, nothing was installed')),
+          );
+          return;
+        }
+        context.read<ErrorLogService>().record('Install ${_app.displayName}', e);
       }
     }
   }
 
+  Future<void> _stageSelfUpdate(File file, InstallType type, String version) async {
+    final selfUpdate = context.read<SelfUpdateService>();
+    final installer = context.read<InstallerService>();
+    final update = await selfUpdate.stage(file, type, version);
+    if (!mounted) return;
+
+    final restart = await showDialog<bool>(
+      context: context,
+      builder: (context) => AlertDialog(
+        title: const Text('Restart to Update'),
+        content: Text('Autonomix $version is ready. It will be installed after Autonomix '
+            'closes and then started again.'),
+        actions: [
+          TextButton(
+            onPressed: () => Navigator.pop(context, false),
+            child: const Text('Later'),
+          ),
+          FilledButton(
+            onPressed: () => Navigator.pop(context, true),
+            child: const Text('Restart Now'),
+          ),
+        ],
+      ),
+    );
+
+    if (restart == true) {
+      await selfUpdate.applyAfterExit(update, escalator: await installer.detectEscalator());
+      return;
+    }
+    if (mounted) {
+      Navigator.pop(context); // Close sheet
+      ScaffoldMessenger.of(context).showSnackBar(
+        const SnackBar(content: Text('Update staged, it will be offered on next start')),
+      );
+    }
+  }
+
   IconData _getIconForType(InstallType type) {
     switch (type) {
       case InstallType.deb: return Icons.grid_view;
       case InstallType.rpm: return Icons.settings;
+      case InstallType.pacman: return Icons.inventory_2;
       case InstallType.appImage: return Icons.extension;
       case InstallType.flatpak: return Icons.layers;
       case InstallType.snap: return Icons.shopping_bag;
+      case InstallType.source: return Icons.build;
       default: return Icons.download;
     }
   }
 
+  /// Closes the sheet asking for the app to be removed from the list, once
+  /// confirmed.
+  Future<void> _confirmStopTracking() async {
+    final installed = _app.installedVersion;
+    final confirm = await showDialog<bool>(
+      context: context,
+      builder: (context) => AlertDialog(
+        title: Text('Stop tracking ${_app.displayName}?'),
+        content: Text('Removes the app from this list. Nothing is uninstalled'
+            '${installed != null ? '; version $installed stays on this system' : ''}.'),
+        actions: [
+          TextButton(
+            onPressed: () => Navigator.pop(context, false),
+            child: const Text('Cancel'),
+          ),
+          FilledButton(
+            onPressed: () => Navigator.pop(context, true),
+            child: const Text('Stop tracking'),
+          ),
+        ],
+      ),
+    );
+    if (confirm == true && mounted) Navigator.pop(context, true);
+  }
+
   Future<void> _uninstall(BuildContext context) async {
     final confirm = await showDialog<bool>(
       context: context,
       builder: (context) => AlertDialog(
-        title: const Text('Uninstall App'),
-        content: const Text('Are you sure you want to uninstall this app?'),
+        title: Text('Uninstall ${_app.displayName}?'),
+        content: Text('${InstallerService.describeUninstall(_app)}\n\n'
+            'Autonomix keeps tracking the app, so it can be installed again later.'),
         actions: [
           TextButton(
             onPressed: () => Navigator.pop(context, false),
@@ -270,25 +1261,7 @@ class _AppDetailsSheetState extends State<AppDetailsSheet> {
     });
 
     try {
-      await context.read<InstallerService>().uninstallPackage(widget.app);
-
-      // Update DB - Clear installed fields
-      final updatedApp = TrackedApp(
-        id: widget.app.id,
-        repoOwner: widget.app.repoOwner,
-        repoName: widget.app.repoName,
-        displayName: widget.app.displayName,
-        createdAt: widget.app.createdAt,
-        latestVersion: widget.app.latestVersion,
-        lastChecked: widget.app.lastChecked,
-        // Cleared:
-        installedVersion: null,
-        installType: null,
-        launchCommand: null,
-        packageName: null,
-      );
-      
-      await context.read<DatabaseService>().updateApp(updatedApp);
+      await context.read<InstallManager>().uninstall(_app);
 
       if (mounted) {
         Navigator.pop(context); // Close sheet
@@ -301,11 +1274,19 @@ class _AppDetailsSheetState extends State<AppDetailsSheet> {
         setState(() {
           _isInstalling = false;
         });
+        final error = AutonomixException.from(e);
+        if (error.kind == ErrorKind.authCancelled) {
+          ScaffoldMessenger.of(context).showSnackBar(
+            const SnackBar(content: Text('Authentication cancelled, nothing was uninstalled')),
+          );
+          return;
+        }
+        context.read<ErrorLogService>().record('Uninstall ${_app.displayName}', e);
         showDialog(
           context: context,
           builder: (context) => AlertDialog(
-            title: const Text('Uninstall Error'),
-            content: Text(e.toString()),
+            title: Text(error.kind == ErrorKind.unknown ? 'Uninstall Error' : error.kind.title),
+            content: Text(error.message),
             actions: [
               TextButton(
                 onPressed: () => Navigator.pop(context),
@@ -320,10 +1301,11 @@ class _AppDetailsSheetState extends State<AppDetailsSheet> {
 
   Future<void> _launch(BuildContext context) async {
     try {
-      await context.read<InstallerService>().launchApp(widget.app);
+      await context.read<InstallerService>().launchApp(_app);
       if (mounted) Navigator.pop(context);
     } catch (e) {
       if (mounted) {
+        context.read<ErrorLogService>().record('Launch ${_app.displayName}', e);
         showDialog(
           context: context,
           builder: (context) => AlertDialog(
@@ -341,31 +1323,407 @@ class _AppDetailsSheetState extends State<AppDetailsSheet> {
     }
   }
 
+  String _assetHints(TrackedApp app) {
+    final arch = app.installedArchitecture ?? 'any arch';
+    final libc = app.installedLibc;
+    return libc == null ? arch : '$arch • $libc';
+  }
+
+  /// Runs [operation] unless another install/uninstall of this app is
+  /// already in flight, e.g. from a previously opened sheet.
+  Future<void> _exclusive(Future<void> Function() operation) async {
+    final tracker = context.read<InstallTracker>();
+    final id = _app.id!;
+    if (!tracker.tryStart(id)) {
+      ScaffoldMessenger.of(context).showSnackBar(
+        SnackBar(content: Text('${_app.displayName} is already being installed or removed')),
+      );
+      return;
+    }
+    try {
+      await operation();
+    } finally {
+      tracker.finish(id);
+    }
+  }
+
+  /// Asks for a tag from the recent releases and installs it in place of
+  /// whatever is installed now.
+  Future<void> _installVersion(BuildContext context) async {
+    final gh = context.read<GitHubService>();
+    final List<Release> releases;
+    try {
+      releases = await gh.sourceFor(_app).getReleases(_app.repoOwner, _app.repoName, perPage: 30);
+    } catch (e) {
+      if (mounted) {
+        ScaffoldMessenger.of(context).showSnackBar(
+          SnackBar(content: Text('Could not list releases: $e')),
+        );
+      }
+      return;
+    }
+    if (!mounted) return;
+
+    final tag = await showDialog<String>(
+      context: context,
+      builder: (context) => InstallVersionDialog(releases: releases, installedVersion: _app.installedVersion),
+    );
+    if (tag == null || !mounted) return;
+    await _exclusive(() => _install(this.context, tag: tag));
+  }
+
+  Future<void> _openReleaseNotes() async {
+    final url = Uri.parse(_app.releaseNotesUrl);
+    if (!await launchUrl(url) && mounted) {
+      ScaffoldMessenger.of(context).showSnackBar(
+        SnackBar(content: Text('Could not open $url')),
+      );
+    }
+  }
+
+  Future<void> _showChangelog() async {
+    final gh = context.read<GitHubService>();
+    try {
+      final release = await gh.getLatestReleaseFor(_app);
+      final changelog = await gh.changelogFor(_app, release);
+      if (!mounted) return;
+      await showDialog(
+        context: context,
+        builder: (context) => AlertDialog(
+          title: Text("What's new in ${release.tagName}"),
+          content: SizedBox(
+            width: 500,
+            child: SingleChildScrollView(
+              child: Column(
+                crossAxisAlignment: CrossAxisAlignment.start,
+                mainAxisSize: MainAxisSize.min,
+                children: [
+                  if (changelog?.generated == true)
+                    Padding(
+                      padding: const EdgeInsets.only(bottom: 8),
+                      child: Text(
+                        'No release notes were published. Commits since ${_app.installedVersion}:',
+                        style: Theme.of(context).textTheme.bodySmall,
+                      ),
+                    ),
+                  SelectableText(changelog?.text ?? 'No release notes available.'),
+                ],
+              ),
+            ),
+          ),
+          actions: [
+            TextButton(
+              onPressed: () => Navigator.pop(context),
+              child: const Text('Close'),
+            ),
+          ],
+        ),
+      );
+    } catch (e) {
+      if (!mounted) return;
+      context.read<ErrorLogService>().record('Changelog for ${_app.displayName}', e);
+      ScaffoldMessenger.of(context).showSnackBar(
+        SnackBar(content: Text('Could not load the changelog: $e')),
+      );
+    }
+  }
+
+  void _onDownloadProgress(int received, int? total) {
+    // Repaint once per percent, or per MB when the size is unknown
+    final step = total == null || total < 100 ? 1 << 20 : total ~/ 100;
+    final previous = _download;
+    if (previous != null && previous.received ~/ step == received ~/ step) return;
+    if (mounted) setState(() => _download = (received: received, total: total));
+  }
+
+  Future<bool> _confirmBinaryName(String name, TrackedApp owner, String alternative) async {
+    if (!mounted) return false;
+    final confirmed = await showDialog<bool>(
+      context: context,
+      builder: (context) => AlertDialog(
+        title: const Text('Binary name in use'),
+        content: Text('${owner.displayName} is already installed as "$name". Installing '
+            '${_app.displayName} under the same name would replace it.'),
+        actions: [
+          TextButton(
+            onPressed: () => Navigator.pop(context, false),
+            child: const Text('Cancel'),
+          ),
+          FilledButton(
+            onPressed: () => Navigator.pop(context, true),
+            child: Text('Install as "$alternative"'),
+          ),
+        ],
+      ),
+    );
+    return confirmed ?? false;
+  }
+
+  Future<void> _copyAssetLink(ReleaseAsset asset) async {
+    await Clipboard.setData(ClipboardData(text: asset.browserDownloadUrl));
+    if (mounted) {
+      ScaffoldMessenger.of(context).showSnackBar(
+        SnackBar(content: Text('Download link for ${asset.name} copied')),
+      );
+    }
+  }
+
+  Future<void> _downloadInBrowser(ReleaseAsset asset) async {
+    if (!await launchUrl(Uri.parse(asset.browserDownloadUrl)) && mounted) {
+      ScaffoldMessenger.of(context).showSnackBar(
+        SnackBar(content: Text('Could not open ${asset.browserDownloadUrl}')),
+      );
+    }
+  }
+
+  Future<void> _copySnippet() async {
+    await Clipboard.setData(ClipboardData(text: _app.toSnippet()));
+    if (mounted) {
+      ScaffoldMessenger.of(context).showSnackBar(
+        const SnackBar(content: Text('Snippet copied to clipboard')),
+      );
+    }
+  }
+
+  Future<void> _editRawEntry(BuildContext context) async {
+    final updated = await showDialog<TrackedApp>(
+      context: context,
+      builder: (context) => RawEntryDialog(app: _app),
+    );
+    if (updated == null || !mounted) return;
+
+    await context.read<DatabaseService>().updateApp(updated);
+    if (mounted) setState(() => _app = updated);
+  }
+
+  /// Hides the update badge for [days] against the current latest version;
+  /// null lifts the snooze.
+  Future<void> _snooze(int? days) async {
+    final updated = TrackedApp.fromMap({
+      ..._app.toMap(),
+      'snooze_until': days == null ? null : DateTime.now().add(Duration(days: days)).toIso8601String(),
+      'snoozed_version': days == null ? null : _app.latestVersion,
+    });
+    await context.read<DatabaseService>().updateApp(updated);
+    if (mounted) setState(() => _app = updated);
+  }
+
+  Future<void> _showHistory() async {
+    final entries = await context.read<DatabaseService>().getHistory(_app.id!);
+    if (!mounted) return;
+    await showDialog(
+      context: context,
+      builder: (context) => HistoryDialog(appName: _app.displayName, entries: entries),
+    );
+  }
+
+  Future<void> _clearIgnoredVersions() async {
+    await context.read<DatabaseService>().clearIgnoredVersions(_app.id!);
+    if (mounted) setState(() => _app = TrackedApp.fromMap({..._app.toMap(), 'ignored_versions': null}));
+  }
+
+  Future<void> _setPinned(bool pinned) async {
+    await context.read<DatabaseService>().setPinned(_app.id!, pinned);
+    if (mounted) setState(() => _app = _app.copyWith(pinned: pinned));
+  }
+
+  Future<void> _editSettings(BuildContext context) async {
+    final updated = await showDialog<TrackedApp>(
+      context: context,
+      builder: (context) => AppSettingsDialog(app: _app),
+    );
+    if (updated == null || !mounted) return;
+
+    await context.read<DatabaseService>().updateApp(updated);
+    if (!mounted) return;
+    context.read<InstallManager>().forgetCompatibility(updated.id!);
+    setState(() {
+      _app = updated;
+      _hasCompatibleAsset = null;
+    });
+    await _checkCompatibility();
+  }
+
+  double? get _downloadFraction {
+    final download = _download;
+    final total = download?.total;
+    if (download == null || total == null || total == 0) return null;
+    return download.received / total;
+  }
+
+  String get _statusWithProgress {
+    final download = _download;
+    if (download == null) return _statusMessage ?? '';
+    final total = download.total;
+    final size = total == null
+        ? InstallerService.formatSize(download.received)
+        : '${InstallerService.formatSize(download.received)} of ${InstallerService.formatSize(total)}';
+    return '${_statusMessage ?? ''} $size';
+  }
+
   @override
   Widget build(BuildContext context) {
+    final tracker = context.watch<InstallTracker>();
+    final busyElsewhere = !_isInstalling && tracker.isBusy(_app.id);
+    final download = tracker.downloadFor(_app.id);
+    final paused = download?.isPaused ?? false;
+    final noPackage = _hasCompatibleAsset == false;
     return Container(
       padding: const EdgeInsets.all(16),
       child: Column(
         mainAxisSize: MainAxisSize.min,
         crossAxisAlignment: CrossAxisAlignment.start,
         children: [
-          Text(widget.app.displayName, style: Theme.of(context).textTheme.headlineSmall),
+          Row(
+            children: [
+              Expanded(
+                child: Text(_app.displayName, style: Theme.of(context).textTheme.headlineSmall),
+              ),
+              IconButton(
+                icon: const Icon(Icons.share),
+                tooltip: 'Copy shareable snippet',
+                onPressed: _copySnippet,
+              ),
+              IconButton(
+                icon: const Icon(Icons.tune),
+                tooltip: 'App settings',
+                onPressed: () => _editSettings(context),
+              ),
+              if (widget.advanced)
+                IconButton(
+                  icon: const Icon(Icons.data_object),
+                  tooltip: 'Edit database entry',
+                  onPressed: busyElsewhere || _isInstalling ? null : () => _editRawEntry(context),
+                ),
+              IconButton(
+                icon: const Icon(Icons.remove_circle_outline),
+                tooltip: 'Stop tracking',
+                onPressed: _isInstalling || busyElsewhere ? null : _confirmStopTracking,
+              ),
+            ],
+          ),
           const SizedBox(height: 8),
-          Text('Repo: ${widget.app.repoOwner}/${widget.app.repoName}'),
-          Text('Installed: ${widget.app.installedVersion ?? "Not installed"}'),
-          Text('Latest: ${widget.app.latestVersion ?? "Unknown"}'),
+          Text('Repo: ${_app.repoOwner}/${_app.repoName}'),
+          FutureBuilder<AppMetadata>(
+            future: _metadata,
+            builder: (context, snapshot) {
+              final metadata = snapshot.data;
+              if (metadata == null) return const SizedBox.shrink();
+              final published = metadata.latestPublishedAt;
+              return Column(
+                crossAxisAlignment: CrossAxisAlignment.start,
+                children: [
+                  if (metadata.description != null)
+                    Text(metadata.description!, style: Theme.of(context).textTheme.bodySmall),
+                  if (metadata.languageAndLicense != null) Text(metadata.languageAndLicense!),
+                  Text([
+                    if (metadata.stars != null) '★ ${metadata.stars}',
+                    if (published != null)
+                      'Released ${MaterialLocalizations.of(context).formatMediumDate(published.toLocal())}',
+                  ].join(' • ')),
+                ],
+              );
+            },
+          ),
+          Text('Installed: ${_app.installedVersion ?? "Not installed"}'
+              '${_app.reportedVersion != null ? ' (reports ${_app.reportedVersion})' : ''}'),
+          if (_app.installedUnavailable)
+            Text(
+              'The installed release is no longer available upstream, so it can\'t be '
+              'reinstalled. Consider updating.',
+              style: TextStyle(color: Theme.of(context).colorScheme.error),
+            ),
+          Text('Latest: ${_app.latestVersion ?? "Unknown"}'),
+          if (_app.hasUpdate && _app.isSnoozed(DateTime.now()))
+            Row(
+              children: [
+                Text('Snoozed until '
+                    '${MaterialLocalizations.of(context).formatMediumDate(_app.snoozeUntil!)}'),
+                TextButton(
+                  onPressed: () => _snooze(null),
+                  child: const Text('Unsnooze'),
+                ),
+              ],
+            ),
+          if (_app.hasUpdate && _app.latestIgnored)
+            Row(
+              children: [
+                Text('Skipped ${_app.latestVersion}'),
+                TextButton(
+                  onPressed: _clearIgnoredVersions,
+                  child: const Text('Show again'),
+                ),
+              ],
+            ),
+          if (_app.hasUpdate && _app.latestReleaseNotes != null)
+            ExpansionTile(
+              title: Text("What's new in ${_app.latestVersion}"),
+              tilePadding: EdgeInsets.zero,
+              childrenPadding: const EdgeInsets.only(bottom: 8),
+              expandedCrossAxisAlignment: CrossAxisAlignment.start,
+              children: [
+                ConstrainedBox(
+                  constraints: const BoxConstraints(maxHeight: 240),
+                  child: SingleChildScrollView(child: SelectableText(_app.latestReleaseNotes!)),
+                ),
+              ],
+            ),
+          if (_app.installedAsset != null)
+            Text('Asset: ${_app.installedAsset} (${_assetHints(_app)})'),
+          Wrap(
+            children: [
+              TextButton.icon(
+                onPressed: _openReleaseNotes,
+                icon: const Icon(Icons.open_in_new),
+                label: const Text('Release notes'),
+              ),
+              // Stored notes are shown inline; without them, fetch or list commits
+              if (_app.hasUpdate && _app.latestReleaseNotes == null)
+                TextButton.icon(
+                  onPressed: _showChangelog,
+                  icon: const Icon(Icons.notes),
+                  label: const Text("What's new"),
+                ),
+              TextButton.icon(
+                onPressed: _showHistory,
+                icon: const Icon(Icons.timeline),
+                label: const Text('History'),
+              ),
+              if (_app.isInstalled)
+                TextButton.icon(
+                  onPressed: () => _setPinned(!_app.pinned),
+                  icon: Icon(_app.pinned ? Icons.push_pin : Icons.push_pin_outlined),
+                  label: Text(_app.pinned ? 'Unpin' : 'Pin this version'),
+                ),
+              TextButton.icon(
+                onPressed: _isInstalling || busyElsewhere ? null : () => _installVersion(context),
+                icon: const Icon(Icons.history),
+                label: const Text('Install specific version…'),
+              ),
+            ],
+          ),
           const SizedBox(height: 16),
           if (_isInstalling) ...[
-            const LinearProgressIndicator(),
+            LinearProgressIndicator(value: _downloadFraction ?? (paused ? 0 : null)),
             const SizedBox(height: 8),
-            Text(_statusMessage ?? ''),
+            Row(
+              children: [
+                Expanded(child: Text(paused ? 'Download paused' : _statusWithProgress)),
+                if (download != null && download.isActive)
+                  TextButton.icon(
+                    onPressed: () => paused ? tracker.resumeDownload(_app.id!) : tracker.pauseDownload(_app.id!),
+                    icon: Icon(paused ? Icons.play_arrow : Icons.pause),
+                    label: Text(paused ? 'Resume' : 'Pause'),
+                  ),
+              ],
+            ),
           ] else
             Row(
               mainAxisAlignment: MainAxisAlignment.end,
               children: [
-                if (widget.app.isInstalled) ...[
+                if (_app.isInstalled) ...[
                   OutlinedButton.icon(
-                    onPressed: () => _uninstall(context),
+                    onPressed: busyElsewhere ? null : () => _exclusive(() => _uninstall(context)),
                     icon: const Icon(Icons.delete),
                     label: const Text('Uninstall'),
                   ),
@@ -376,19 +1734,36 @@ class _AppDetailsSheetState extends State<AppDetailsSheet> {
                     label: const Text('Launch'),
                   ),
                 ],
+                if (_app.showsUpdate(DateTime.now()))
+                  PopupMenuButton<int>(
+                    icon: const Icon(Icons.snooze),
+                    tooltip: 'Snooze this update',
+                    onSelected: _snooze,
+                    itemBuilder: (context) => const [
+                      PopupMenuItem(value: 1, child: Text('Snooze for a day')),
+                      PopupMenuItem(value: 7, child: Text('Snooze for a week')),
+                      PopupMenuItem(value: 30, child: Text('Snooze for a month')),
+                    ],
+                  ),
                 const SizedBox(width: 8),
-                if (widget.app.hasUpdate)
-                  FilledButton.icon(
-                    onPressed: () => _install(context),
-                    icon: const Icon(Icons.system_update),
-                    label: const Text('Update'),
+                if (_app.hasUpdate)
+                  Tooltip(
+                    message: noPackage ? 'No Linux package available' : '',
+                    child: FilledButton.icon(
+                      onPressed: busyElsewhere || noPackage ? null : () => _exclusive(() => _install(context)),
+                      icon: const Icon(Icons.system_update),
+                      label: const Text('Update'),
+                    ),
                   ),
                 const SizedBox(width: 8),
-                if (!widget.app.isInstalled)
-                  FilledButton.icon(
-                    onPressed: () => _install(context),
-                    icon: const Icon(Icons.download),
-                    label: const Text('Install'),
+                if (!_app.isInstalled)
+                  Tooltip(
+                    message: noPackage ? 'No Linux package available' : '',
+                    child: FilledButton.icon(
+                      onPressed: busyElsewhere || noPackage ? null : () => _exclusive(() => _install(context)),
+                      icon: const Icon(Icons.download),
+                      label: const Text('Install'),
+                    ),
                   ),
               ],
             ),
@@ -1,10 +1,66 @@
 import 'package:flutter/material.dart';
 import 'package:provider/provider.dart';
+import '../../models/release.dart';
+import '../../models/release_channel.dart';
+import '../../models/release_provider.dart';
 import '../../services/github_service.dart';
 
 class AddAppDialog extends StatefulWidget {
   const AddAppDialog({super.key});
 
+  /// Repo names that say nothing about the app they ship.
+  static const Set<String> genericRepoNames = {
+    'release',
+    'releases',
+    'dist',
+    'bin',
+    'binaries',
+    'builds',
+    'download',
+    'downloads',
+    'packages',
+    'app',
+  };
+
+  static bool isGenericRepoName(String repo) => genericRepoNames.contains(repo.toLowerCase());
+
+  /// Guesses an app name from an asset file name by dropping the extension
+  /// and everything from the first version/platform token onwards, e.g.
+  /// `MyTool-1.2.0-x86_64.AppImage` becomes `MyTool`.
+  static String? nameFromAsset(String assetName) {
+    final stem = assetName.replaceFirst(
+      RegExp(r'\.(appimage|deb|rpm|flatpak|snap|zip|tar\.\w+|tgz)$', caseSensitive: false),
+      '',
+    );
+    final stop = RegExp(
+      r'^(v?\d.*|linux|x86_64|x86|amd64|aarch64|arm64|i386|i686|x64|musl|gnu|static|setup|installer)$',
+      caseSensitive: false,
+    );
+    final tokens = <String>[];
+    for (final token in stem.split(RegExp(r'[-_. ]+'))) {
+      if (token.isEmpty) continue;
+      if (stop.hasMatch(token)) break;
+      tokens.add(token);
+    }
+    return tokens.isEmpty ? null : tokens.join(' ');
+  }
+
+  /// Display name suggested when adding a repo. Generic repo names fall back
+  /// to the primary asset's name, then the owner.
+  static String suggestDisplayName({
+    required String owner,
+    required String repo,
+    String? description,
+    List<ReleaseAsset> assets = const [],
+  }) {
+    if (!isGenericRepoName(repo)) return description ?? repo;
+    for (final asset in assets) {
+      final name = nameFromAsset(asset.name);
+      if (name != null && !isGenericRepoName(name)) return name;
+    }
+    return owner;
+  }
+
   @override
   State<AddAppDialog> createState() => _AddAppDialogState();
 }
@@ -15,7 +71,11 @@ class _AddAppDialogState extends State<AddAppDialog> {
   final _ownerController = TextEditingController();
   final _repoController = TextEditingController();
   final _nameController = TextEditingController();
+  final _tagPrefixController = TextEditingController();
 
+  ReleaseProvider _provider = ReleaseProvider.github;
+  String? _host;
+  bool _trackPrereleases = false;
   bool _isFetching = false;
   bool _hasFetched = false;
   String? _error;
@@ -30,31 +90,45 @@ class _AddAppDialogState extends State<AddAppDialog> {
     });
 
     try {
-      // Parse URL
-      // Supports:
-      // https://github.com/owner/repo
-      // https://github.com/owner/repo/releases...
-      final uri = Uri.parse(url);
-      if (uri.host != 'github.com') {
-        throw Exception('Not a GitHub URL');
+      // Supports repository and release page URLs on GitHub, GitLab and
+      // Gitea/Forgejo, e.g. https://github.com/owner/repo/releases or
+      // https://gitlab.com/group/sub/repo
+      final parsed = ReleaseProvider.parseRepoUrl(url);
+      if (parsed == null) {
+        throw Exception('Not a repository URL');
       }
+      final owner = parsed.owner;
+      final repo = parsed.repo;
 
-      final segments = uri.pathSegments;
-      if (segments.length < 2) {
-        throw Exception('Invalid repository URL');
-      }
-
-      final owner = segments[0];
-      final repo = segments[1];
+      final gh = context.read<GitHubService>();
+      final provider = parsed.provider ?? await gh.detectProvider(parsed.host);
+      // Apps on a public instance don't store its host
+      final host = parsed.provider == null ? parsed.host : null;
 
       // Fetch details
-      final gh = context.read<GitHubService>();
-      final info = await gh.getRepository(owner, repo);
+      final source = gh.sourceOf(provider, host: host);
+      final info = await source.getRepository(owner, repo);
+
+      var assets = <ReleaseAsset>[];
+      if (AddAppDialog.isGenericRepoName(info['name'])) {
+        try {
+          assets = (await source.getLatestRelease(owner, repo)).assets;
+        } catch (_) {
+          // No release yet, fall back to the owner name
+        }
+      }
 
       setState(() {
+        _provider = provider;
+        _host = host;
         _ownerController.text = info['owner']['login'];
         _repoController.text = info['name'];
-        _nameController.text = info['description'] ?? info['name']; // Use description or name
+        _nameController.text = AddAppDialog.suggestDisplayName(
+          owner: info['owner']['login'],
+          repo: info['name'],
+          description: info['description'],
+          assets: assets,
+        );
         _hasFetched = true;
         _isFetching = false;
       });
@@ -72,6 +146,7 @@ class _AddAppDialogState extends State<AddAppDialog> {
     _ownerController.dispose();
     _repoController.dispose();
     _nameController.dispose();
+    _tagPrefixController.dispose();
     super.dispose();
   }
 
@@ -91,7 +166,7 @@ class _AddAppDialogState extends State<AddAppDialog> {
                 TextFormField(
                   controller: _urlController,
                   decoration: InputDecoration(
-                    labelText: 'GitHub URL',
+                    labelText: 'Repository URL',
                     hintText: 'https://github.com/owner/repo',
                     suffixIcon: IconButton(
                       icon: const Icon(Icons.paste),
@@ -127,6 +202,21 @@ class _AddAppDialogState extends State<AddAppDialog> {
                   decoration: const InputDecoration(labelText: 'Display Name'),
                   validator: (v) => v?.isEmpty == true ? 'Required' : null,
                 ),
+                TextFormField(
+                  controller: _tagPrefixController,
+                  decoration: const InputDecoration(
+                    labelText: 'Tag prefix (optional)',
+                    hintText: 'e.g. cli-v',
+                    helperText: 'Track one product of a monorepo',
+                  ),
+                ),
+                CheckboxListTile(
+                  contentPadding: EdgeInsets.zero,
+                  title: const Text('Track pre-releases'),
+                  subtitle: const Text('For projects that only publish nightlies or betas'),
+                  value: _trackPrereleases,
+                  onChanged: (value) => setState(() => _trackPrereleases = value ?? false),
+                ),
               ],
               if (_error != null) ...[
                 const SizedBox(height: 16),
@@ -149,9 +239,13 @@ class _AddAppDialogState extends State<AddAppDialog> {
             onPressed: () {
               if (_formKey.currentState!.validate()) {
                 Navigator.pop(context, {
+                  'provider': _provider.name,
+                  if (_host != null) 'host': _host!,
                   'owner': _ownerController.text,
                   'repo': _repoController.text,
                   'name': _nameController.text,
+                  'tag_prefix': _tagPrefixController.text.trim(),
+                  if (_trackPrereleases) 'channel': ReleaseChannel.prerelease.name,
                 });
               }
             },
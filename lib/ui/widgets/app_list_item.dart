@@ -1,38 +1,104 @@
 import 'package:flutter/material.dart';
+import '../../models/release_channel.dart';
 import '../../models/tracked_app.dart';
 
 class AppListItem extends StatelessWidget {
   final TrackedApp app;
   final VoidCallback onTap;
+  final bool busy;
+
+  /// The app's download is paused; only meaningful while [busy].
+  final bool paused;
+
+  /// Fraction of the running download; null while its size is unknown.
+  final double? progress;
+
+  /// Offered from the update badge to stop flagging the latest release.
+  final VoidCallback? onSkipVersion;
 
   const AppListItem({
     super.key,
     required this.app,
     required this.onTap,
+    this.busy = false,
+    this.paused = false,
+    this.progress,
+    this.onSkipVersion,
   });
 
   @override
   Widget build(BuildContext context) {
+    final now = DateTime.now();
     return ListTile(
       title: Text(app.displayName),
       subtitle: Text('${app.repoOwner}/${app.repoName}'),
       trailing: Row(
         mainAxisSize: MainAxisSize.min,
         children: [
-          if (app.hasUpdate)
-            Container(
-              padding: const EdgeInsets.symmetric(horizontal: 8, vertical: 4),
-              decoration: BoxDecoration(
-                color: Colors.orange.shade100,
-                borderRadius: BorderRadius.circular(12),
-              ),
-              child: Text(
-                'Update Available',
-                style: TextStyle(color: Colors.orange.shade900, fontSize: 12),
+          if (app.pinned)
+            Tooltip(
+              message: 'Pinned at ${app.installedVersion ?? 'the installed version'}',
+              child: const Icon(Icons.push_pin, color: Colors.grey),
+            )
+          else if (app.isSnoozed(now) && app.hasUpdate)
+            Tooltip(
+              message: 'Snoozed until ${MaterialLocalizations.of(context).formatMediumDate(app.snoozeUntil!)}',
+              child: const Icon(Icons.snooze, color: Colors.grey),
+            )
+          else if (app.hasUpdate && !app.latestIgnored)
+            PopupMenuButton<String>(
+              tooltip: 'Update options',
+              enabled: onSkipVersion != null,
+              onSelected: (_) => onSkipVersion?.call(),
+              itemBuilder: (context) => [
+                PopupMenuItem(value: 'skip', child: Text('Skip ${app.latestVersion}')),
+              ],
+              child: Container(
+                padding: const EdgeInsets.symmetric(horizontal: 8, vertical: 4),
+                decoration: BoxDecoration(
+                  color: Colors.orange.shade100,
+                  borderRadius: BorderRadius.circular(12),
+                ),
+                child: Text(
+                  'Update Available',
+                  style: TextStyle(color: Colors.orange.shade900, fontSize: 12),
+                ),
               ),
             ),
+          if (app.channel == ReleaseChannel.prerelease) ...[
+            const SizedBox(width: 8),
+            const Tooltip(
+              message: 'Tracks pre-releases',
+              child: Icon(Icons.science_outlined, color: Colors.grey),
+            ),
+          ],
           const SizedBox(width: 8),
-          if (app.isInstalled)
+          if (busy && paused)
+            const Tooltip(
+              message: 'Download paused',
+              child: Icon(Icons.pause_circle_outline, color: Colors.grey),
+            )
+          else if (busy && progress != null && progress! < 1)
+            Row(
+              mainAxisSize: MainAxisSize.min,
+              children: [
+                Text('${(progress! * 100).floor()}%', style: Theme.of(context).textTheme.bodySmall),
+                const SizedBox(width: 8),
+                SizedBox(
+                  width: 24,
+                  height: 24,
+                  child: CircularProgressIndicator(value: progress, strokeWidth: 2),
+                ),
+              ],
+            )
+          else if (busy)
+            // Installing, or downloading something of unknown size
+            const SizedBox(
+              width: 24,
+              height: 24,
+              child: CircularProgressIndicator(strokeWidth: 2),
+            )
+          else if (app.isInstalled)
             const Icon(Icons.check_circle, color: Colors.green)
           else
             const Icon(Icons.circle_outlined, color: Colors.grey),
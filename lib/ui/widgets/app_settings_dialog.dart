@@ -0,0 +1,432 @@
+import 'package:flutter/material.dart';
+import 'package:provider/provider.dart';
+import '../../models/install_type.dart';
+import '../../models/release.dart';
+import '../../models/release_channel.dart';
+import '../../models/tracked_app.dart';
+import '../../services/github_service.dart';
+import '../../services/install_manager.dart';
+import '../../services/installer_service.dart';
+import '../../services/release_selector.dart';
+
+class AppSettingsDialog extends StatefulWidget {
+  final TrackedApp app;
+
+  const AppSettingsDialog({super.key, required this.app});
+
+  /// Parses one `NAME=value` per line, skipping blank lines.
+  static Map<String, String> parseEnvLines(String text) {
+    final env = <String, String>{};
+    for (final line in text.split('\n')) {
+      if (line.trim().isEmpty) continue;
+      final eq = line.indexOf('=');
+      final name = eq > 0 ? line.substring(0, eq).trim() : '';
+      if (!RegExp(r'^[A-Za-z_][A-Za-z0-9_]*$').hasMatch(name)) {
+        throw FormatException('Expected NAME=value', line);
+      }
+      env[name] = line.substring(eq + 1);
+    }
+    return env;
+  }
+
+  static String formatEnvLines(Map<String, String>? env) =>
+      env?.entries.map((e) => '${e.key}=${e.value}').join('\n') ?? '';
+
+  /// Why [key] can't be a signing key, or null if it looks like one: an
+  /// armored OpenPGP public key or a minisign public key.
+  static String? signingKeyProblem(String key) {
+    if (key.trim().isEmpty || !InstallerService.isMinisignKey(key)) return null;
+    final line = InstallerService.minisignKeyOf(key);
+    if (RegExp(r'^RW[A-Za-z0-9+/]{54}$').hasMatch(line)) return null;
+    return 'Expected a minisign public key (RW...) or an armored OpenPGP public key';
+  }
+
+  /// Why [pattern] can't be used as an asset pattern, or null if it
+  /// compiles (or is empty).
+  static String? assetPatternProblem(String pattern) {
+    if (pattern.trim().isEmpty) return null;
+    try {
+      ReleaseAsset.patternRegExp(pattern);
+      return null;
+    } on FormatException catch (e) {
+      return 'Invalid pattern: ${e.message}';
+    }
+  }
+
+  @override
+  State<AppSettingsDialog> createState() => _AppSettingsDialogState();
+}
+
+class _AppSettingsDialogState extends State<AppSettingsDialog> {
+  static const Map<String, Duration?> _checkIntervalPresets = {
+    // Follows "Check for updates automatically" in the main menu
+    'Use global setting': null,
+    'Hourly': Duration(hours: 1),
+    'Daily': Duration(days: 1),
+    'Weekly': Duration(days: 7),
+  };
+
+  final _formKey = GlobalKey<FormState>();
+  late Duration? _checkInterval = widget.app.checkInterval;
+  late String? _preferredAssetSignature = widget.app.preferredAssetSignature;
+  late ReleaseChannel? _channel = widget.app.channel;
+  late final TextEditingController _changelogUrlController;
+  late final TextEditingController _tagPrefixController;
+  late final TextEditingController _versionCommandController;
+  late final TextEditingController _includeKeywordsController;
+  late final TextEditingController _excludeKeywordsController;
+  late final TextEditingController _assetPatternController;
+  late final TextEditingController _envController;
+  late final TextEditingController _postUpdateController;
+  late final TextEditingController _signingKeyController;
+
+  // Fetched once so the tag preview can update as the user types.
+  List<Release>? _releases;
+  String? _releasesError;
+  bool _releasesTruncated = false;
+  int _nextReleasePage = 1;
+  bool _loadingMore = false;
+  Set<InstallType>? _available;
+
+  @override
+  void initState() {
+    super.initState();
+    _changelogUrlController = TextEditingController(text: widget.app.changelogUrl ?? '');
+    _tagPrefixController = TextEditingController(text: widget.app.tagPrefix ?? '');
+    _versionCommandController = TextEditingController(text: widget.app.versionCommand ?? '');
+    _includeKeywordsController =
+        TextEditingController(text: widget.app.assetIncludeKeywords?.join(', ') ?? '');
+    _excludeKeywordsController =
+        TextEditingController(text: widget.app.assetExcludeKeywords?.join(', ') ?? '');
+    _assetPatternController = TextEditingController(text: widget.app.assetPattern ?? '');
+    _envController = TextEditingController(text: AppSettingsDialog.formatEnvLines(widget.app.env));
+    _postUpdateController = TextEditingController(text: widget.app.postUpdateCommand ?? '');
+    _signingKeyController = TextEditingController(text: widget.app.signingKey ?? '');
+    _fetchReleases();
+    context.read<InstallerService>().detectAvailableInstallers().then((available) {
+      if (mounted) setState(() => _available = available);
+    });
+  }
+
+  @override
+  void dispose() {
+    _changelogUrlController.dispose();
+    _tagPrefixController.dispose();
+    _versionCommandController.dispose();
+    _includeKeywordsController.dispose();
+    _excludeKeywordsController.dispose();
+    _assetPatternController.dispose();
+    _envController.dispose();
+    _postUpdateController.dispose();
+    _signingKeyController.dispose();
+    super.dispose();
+  }
+
+  Future<void> _loadMoreReleases() async {
+    setState(() => _loadingMore = true);
+    await _fetchReleases();
+  }
+
+  Future<void> _fetchReleases() async {
+    try {
+      final gh = context.read<GitHubService>();
+      final result = await gh.getReleasesPaginated(
+        widget.app.repoOwner,
+        widget.app.repoName,
+        source: gh.sourceFor(widget.app),
+        startPage: _nextReleasePage,
+      );
+      if (mounted) {
+        setState(() {
+          _releases = [...?_releases, ...result.releases];
+          _releasesTruncated = result.truncated;
+          _nextReleasePage = result.nextPage;
+        });
+      }
+    } catch (e) {
+      if (mounted) setState(() => _releasesError = e.toString());
+    } finally {
+      if (mounted) setState(() => _loadingMore = false);
+    }
+  }
+
+  /// The app as it would be saved with the current form values.
+  TrackedApp get _draft => TrackedApp.fromMap({
+        ...widget.app.toMap(),
+        'changelog_url': _emptyToNull(_changelogUrlController.text),
+        'tag_prefix': _emptyToNull(_tagPrefixController.text),
+        'check_interval': _checkInterval?.inSeconds,
+        'version_command': _emptyToNull(_versionCommandController.text),
+        'asset_include_keywords': _keywords(_includeKeywordsController.text),
+        'asset_exclude_keywords': _keywords(_excludeKeywordsController.text),
+        'asset_pattern': _assetPattern,
+        'env': _env,
+        'post_update_command': _emptyToNull(_postUpdateController.text),
+        'signing_key': _emptyToNull(_signingKeyController.text),
+        'preferred_asset_signature': _preferredAssetSignature,
+        'channel': _channel?.name,
+      });
+
+  /// Null when the field is empty or (until validation flags it) invalid.
+  String? get _assetPattern {
+    final pattern = _emptyToNull(_assetPatternController.text);
+    if (pattern == null || AppSettingsDialog.assetPatternProblem(pattern) != null) return null;
+    return pattern;
+  }
+
+  /// Null when the field is empty or (until validation flags it) invalid.
+  Map<String, String>? get _env {
+    try {
+      final env = AppSettingsDialog.parseEnvLines(_envController.text);
+      return env.isEmpty ? null : env;
+    } on FormatException {
+      return null;
+    }
+  }
+
+  String? _validateEnv(String? value) {
+    try {
+      AppSettingsDialog.parseEnvLines(value ?? '');
+      return null;
+    } on FormatException catch (e) {
+      return 'Invalid line: ${e.source}';
+    }
+  }
+
+  String get _tagPreview {
+    if (_releasesError != null) return 'Could not load releases';
+    if (_releases == null) return 'Loading releases...';
+    final release = ReleaseSelector.selectLatest(_releases!, _draft);
+    if (release != null) return 'Currently matches ${release.tagName}';
+    return _releasesTruncated
+        ? 'No match in the newest ${_releases!.length} releases'
+        : 'No release matches';
+  }
+
+  /// What would be installed with the current form values, and why.
+  String get _selectionPreview {
+    if (_releases == null || _available == null) return 'Loading...';
+    final draft = _draft;
+    final release = ReleaseSelector.selectLatest(_releases!, draft);
+    if (release == null) return 'No release matches these settings';
+    final choice = context.read<InstallManager>().choose(draft, release, _available!);
+    if (choice == null) return '${release.tagName}: no installable asset matches these settings';
+    return '${release.tagName}: ${choice.asset.name}\n${choice.reason}';
+  }
+
+  String? _validateUrl(String? value) {
+    final v = value?.trim() ?? '';
+    if (v.isEmpty) return null;
+    final uri = Uri.tryParse(v);
+    if (uri == null || !(uri.scheme == 'http' || uri.scheme == 'https')) {
+      return 'Must be an http(s) URL';
+    }
+    return null;
+  }
+
+  String? _emptyToNull(String value) {
+    final v = value.trim();
+    return v.isEmpty ? null : v;
+  }
+
+  List<String>? _keywords(String value) {
+    final keywords = value.split(',').map((k) => k.trim()).where((k) => k.isNotEmpty).toList();
+    return keywords.isEmpty ? null : keywords;
+  }
+
+  Future<void> _save() async {
+    if (!_formKey.currentState!.validate()) return;
+
+    // A new hook runs arbitrary code on every update, so make sure it's meant
+    final command = _emptyToNull(_postUpdateController.text);
+    if (command != null && command != widget.app.postUpdateCommand && !await _confirmPostUpdateCommand(command)) {
+      return;
+    }
+
+    // _draft round-trips through the map so cleared fields become null.
+    if (mounted) Navigator.pop(context, _draft);
+  }
+
+  Future<bool> _confirmPostUpdateCommand(String command) async {
+    final confirmed = await showDialog<bool>(
+      context: context,
+      builder: (context) => AlertDialog(
+        title: const Text('Run this command after updates?'),
+        content: Text('Every time ${widget.app.displayName} is installed or updated, Autonomix will run\n\n'
+            '$command\n\nwith your permissions. Only continue if you trust it.'),
+        actions: [
+          TextButton(
+            onPressed: () => Navigator.pop(context, false),
+            child: const Text('Cancel'),
+          ),
+          FilledButton(
+            onPressed: () => Navigator.pop(context, true),
+            child: const Text('Allow'),
+          ),
+        ],
+      ),
+    );
+    return confirmed ?? false;
+  }
+
+  @override
+  Widget build(BuildContext context) {
+    return AlertDialog(
+      // The form has outgrown small screens
+      scrollable: true,
+      title: Text('${widget.app.displayName} Settings'),
+      content: SizedBox(
+        width: 400,
+        child: Form(
+          key: _formKey,
+          child: Column(
+            mainAxisSize: MainAxisSize.min,
+            crossAxisAlignment: CrossAxisAlignment.stretch,
+            children: [
+              TextFormField(
+                controller: _changelogUrlController,
+                decoration: const InputDecoration(
+                  labelText: 'Changelog URL',
+                  hintText: 'Defaults to the GitHub release page',
+                ),
+                validator: _validateUrl,
+              ),
+              TextFormField(
+                controller: _tagPrefixController,
+                decoration: InputDecoration(
+                  labelText: 'Tag prefix',
+                  hintText: 'e.g. lts-',
+                  helperText: _tagPreview,
+                ),
+                onChanged: (_) => setState(() {}),
+              ),
+              TextFormField(
+                controller: _versionCommandController,
+                decoration: const InputDecoration(
+                  labelText: 'Version command',
+                  hintText: '{path} --version',
+                  helperText: 'Run after install to read the real version',
+                ),
+              ),
+              TextFormField(
+                controller: _includeKeywordsController,
+                decoration: const InputDecoration(
+                  labelText: 'Asset must contain one of',
+                  hintText: 'Comma-separated, e.g. gtk, x11',
+                ),
+                onChanged: (_) => setState(() {}),
+              ),
+              TextFormField(
+                controller: _excludeKeywordsController,
+                decoration: const InputDecoration(
+                  labelText: 'Asset must not contain',
+                  hintText: 'Comma-separated, e.g. debug, symbols, unsigned',
+                ),
+                onChanged: (_) => setState(() {}),
+              ),
+              TextFormField(
+                controller: _assetPatternController,
+                autocorrect: false,
+                decoration: const InputDecoration(
+                  labelText: 'Asset name pattern',
+                  hintText: '*-gtk-*.AppImage',
+                  helperText: r'Glob, or a regex between slashes like /gtk.*\.AppImage$/',
+                ),
+                validator: (value) => AppSettingsDialog.assetPatternProblem(value ?? ''),
+                onChanged: (_) => setState(() {}),
+              ),
+              TextFormField(
+                controller: _envController,
+                minLines: 1,
+                maxLines: 4,
+                decoration: const InputDecoration(
+                  labelText: 'Environment',
+                  hintText: 'APPIMAGE_EXTRACT_AND_RUN=1',
+                  helperText: 'One NAME=value per line, used to launch and run the version command',
+                ),
+                validator: _validateEnv,
+              ),
+              TextFormField(
+                controller: _postUpdateController,
+                decoration: const InputDecoration(
+                  labelText: 'After update command',
+                  hintText: 'systemctl --user restart myservice',
+                  helperText: r'Runs via sh after each successful update; $AUTONOMIX_VERSION is the new version',
+                ),
+              ),
+              TextFormField(
+                controller: _signingKeyController,
+                minLines: 1,
+                maxLines: 4,
+                style: const TextStyle(fontFamily: 'monospace'),
+                decoration: const InputDecoration(
+                  labelText: 'Signing key',
+                  hintText: 'RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3',
+                  helperText: 'minisign or OpenPGP public key; downloads without a valid signature are refused',
+                ),
+                validator: (value) => AppSettingsDialog.signingKeyProblem(value ?? ''),
+              ),
+              DropdownButtonFormField<ReleaseChannel?>(
+                value: _channel,
+                decoration: const InputDecoration(labelText: 'Release channel'),
+                items: [
+                  DropdownMenuItem(
+                    value: null,
+                    child: Text('Default (${ReleaseSelector.defaultChannel.displayName})'),
+                  ),
+                  for (final channel in ReleaseChannel.values)
+                    DropdownMenuItem(value: channel, child: Text(channel.displayName)),
+                ],
+                onChanged: (value) => setState(() => _channel = value),
+              ),
+              DropdownButtonFormField<Duration?>(
+                value: _checkIntervalPresets.containsValue(_checkInterval) ? _checkInterval : null,
+                decoration: const InputDecoration(labelText: 'Automatic update checks'),
+                items: _checkIntervalPresets.entries
+                    .map((e) => DropdownMenuItem(value: e.value, child: Text(e.key)))
+                    .toList(),
+                onChanged: (value) => setState(() => _checkInterval = value),
+              ),
+              if (_preferredAssetSignature != null)
+                Row(
+                  children: [
+                    Expanded(
+                      child: Text(
+                        'Preferred asset: $_preferredAssetSignature',
+                        overflow: TextOverflow.ellipsis,
+                      ),
+                    ),
+                    TextButton(
+                      onPressed: () => setState(() => _preferredAssetSignature = null),
+                      child: const Text('Ask again'),
+                    ),
+                  ],
+                ),
+              const SizedBox(height: 16),
+              Text('Would install', style: Theme.of(context).textTheme.titleSmall),
+              Text(_releasesError != null ? 'Could not load releases' : _selectionPreview),
+              if (_releasesTruncated)
+                Align(
+                  alignment: Alignment.centerRight,
+                  child: TextButton(
+                    onPressed: _loadingMore ? null : _loadMoreReleases,
+                    child: const Text('Load more releases'),
+                  ),
+                ),
+            ],
+          ),
+        ),
+      ),
+      actions: [
+        TextButton(
+          onPressed: () => Navigator.pop(context),
+          child: const Text('Cancel'),
+        ),
+        FilledButton(
+          onPressed: _save,
+          child: const Text('Save'),
+        ),
+      ],
+    );
+  }
+}
@@ -0,0 +1,73 @@
+import 'package:flutter/material.dart';
+import '../../services/github_service.dart';
+
+/// Times a request to each endpoint Autonomix talks to, so users can see
+/// which hosts are slow or unreachable.
+class ConnectionTestDialog extends StatefulWidget {
+  final GitHubService gh;
+
+  const ConnectionTestDialog({super.key, required this.gh});
+
+  @override
+  State<ConnectionTestDialog> createState() => _ConnectionTestDialogState();
+}
+
+class _ConnectionTestDialogState extends State<ConnectionTestDialog> {
+  late Map<Uri, Future<Duration>> _results;
+
+  @override
+  void initState() {
+    super.initState();
+    _run();
+  }
+
+  void _run() {
+    _results = {
+      for (final url in widget.gh.diagnosticEndpoints) url: widget.gh.measureRoundTrip(url),
+    };
+  }
+
+  @override
+  Widget build(BuildContext context) {
+    return AlertDialog(
+      title: const Text('Connection Test'),
+      content: SizedBox(
+        width: 400,
+        child: Column(
+          mainAxisSize: MainAxisSize.min,
+          children: _results.entries.map((entry) {
+            return FutureBuilder<Duration>(
+              future: entry.value,
+              builder: (context, snapshot) {
+                final String result;
+                if (snapshot.hasError) {
+                  result = 'unreachable';
+                } else if (snapshot.hasData) {
+                  result = '${snapshot.data!.inMilliseconds} ms';
+                } else {
+                  result = 'testing...';
+                }
+                return ListTile(
+                  dense: true,
+                  leading: Icon(snapshot.hasError ? Icons.cloud_off : Icons.cloud_done_outlined),
+                  title: Text(entry.key.host),
+                  trailing: Text(result),
+                );
+              },
+            );
+          }).toList(),
+        ),
+      ),
+      actions: [
+        TextButton(
+          onPressed: () => setState(_run),
+          child: const Text('Run again'),
+        ),
+        FilledButton(
+          onPressed: () => Navigator.pop(context),
+          child: const Text('Close'),
+        ),
+      ],
+    );
+  }
+}
@@ -0,0 +1,76 @@
+import 'package:flutter/material.dart';
+import 'package:intl/intl.dart';
+import '../../services/autonomix_exception.dart';
+import '../../services/error_log_service.dart';
+
+class ErrorLogDialog extends StatelessWidget {
+  final ErrorLogService log;
+
+  const ErrorLogDialog({super.key, required this.log});
+
+  static IconData iconFor(ErrorKind kind) {
+    switch (kind) {
+      case ErrorKind.network:
+        return Icons.wifi_off;
+      case ErrorKind.rateLimited:
+        return Icons.hourglass_top;
+      case ErrorKind.notFound:
+        return Icons.search_off;
+      case ErrorKind.noCompatibleAsset:
+        return Icons.block;
+      case ErrorKind.authCancelled:
+        return Icons.lock_outline;
+      case ErrorKind.unsupported:
+        return Icons.do_not_disturb_alt;
+      case ErrorKind.installFailed:
+      case ErrorKind.unknown:
+        return Icons.error_outline;
+    }
+  }
+
+  @override
+  Widget build(BuildContext context) {
+    final entries = log.entries;
+    final timeFormat = DateFormat('HH:mm:ss');
+
+    return AlertDialog(
+      title: const Text('Recent Errors'),
+      content: SizedBox(
+        width: 500,
+        child: entries.isEmpty
+            ? const Text('No errors recorded.')
+            : ListView.builder(
+                shrinkWrap: true,
+                itemCount: entries.length,
+                itemBuilder: (context, index) {
+                  final entry = entries[index];
+                  return ListTile(
+                    dense: true,
+                    leading: Tooltip(
+                      message: entry.kind.title,
+                      child: Icon(iconFor(entry.kind)),
+                    ),
+                    title: Text(entry.operation),
+                    subtitle: SelectableText(entry.message),
+                    trailing: Text(timeFormat.format(entry.timestamp)),
+                  );
+                },
+              ),
+      ),
+      actions: [
+        if (entries.isNotEmpty)
+          TextButton(
+            onPressed: () {
+              log.clear();
+              Navigator.pop(context);
+            },
+            child: const Text('Clear'),
+          ),
+        FilledButton(
+          onPressed: () => Navigator.pop(context),
+          child: const Text('Close'),
+        ),
+      ],
+    );
+  }
+}
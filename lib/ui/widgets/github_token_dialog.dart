@@ -0,0 +1,81 @@
+import 'package:flutter/material.dart';
+
+/// Asks for a GitHub personal access token. Pops with the trimmed token, an
+/// empty string to remove it, or null when cancelled.
+class GitHubTokenDialog extends StatefulWidget {
+  final String? token;
+
+  /// Set when `GITHUB_TOKEN` is in the environment, which overrides
+  /// whatever is saved here.
+  final bool overriddenByEnvironment;
+
+  const GitHubTokenDialog({super.key, this.token, this.overriddenByEnvironment = false});
+
+  @override
+  State<GitHubTokenDialog> createState() => _GitHubTokenDialogState();
+}
+
+class _GitHubTokenDialogState extends State<GitHubTokenDialog> {
+  late final _controller = TextEditingController(text: widget.token ?? '');
+  bool _obscured = true;
+
+  @override
+  void dispose() {
+    _controller.dispose();
+    super.dispose();
+  }
+
+  @override
+  Widget build(BuildContext context) {
+    return AlertDialog(
+      title: const Text('GitHub Token'),
+      content: SizedBox(
+        width: 400,
+        child: Column(
+          mainAxisSize: MainAxisSize.min,
+          crossAxisAlignment: CrossAxisAlignment.stretch,
+          children: [
+            const Text('A personal access token lets Autonomix track private repositories '
+                'and raises the API rate limit. Read-only access to contents is enough.'),
+            if (widget.overriddenByEnvironment)
+              Padding(
+                padding: const EdgeInsets.only(top: 8),
+                child: Text(
+                  'GITHUB_TOKEN is set in the environment and takes precedence.',
+                  style: TextStyle(color: Theme.of(context).colorScheme.error),
+                ),
+              ),
+            TextField(
+              controller: _controller,
+              obscureText: _obscured,
+              autocorrect: false,
+              decoration: InputDecoration(
+                labelText: 'Token',
+                hintText: 'github_pat_...',
+                suffixIcon: IconButton(
+                  icon: Icon(_obscured ? Icons.visibility : Icons.visibility_off),
+                  onPressed: () => setState(() => _obscured = !_obscured),
+                ),
+              ),
+            ),
+          ],
+        ),
+      ),
+      actions: [
+        if (widget.token != null)
+          TextButton(
+            onPressed: () => Navigator.pop(context, ''),
+            child: const Text('Remove'),
+          ),
+        TextButton(
+          onPressed: () => Navigator.pop(context),
+          child: const Text('Cancel'),
+        ),
+        FilledButton(
+          onPressed: () => Navigator.pop(context, _controller.text.trim()),
+          child: const Text('Save'),
+        ),
+      ],
+    );
+  }
+}
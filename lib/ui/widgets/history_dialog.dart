@@ -0,0 +1,75 @@
+import 'package:flutter/material.dart';
+import 'package:intl/intl.dart';
+import '../../models/history_entry.dart';
+
+/// Timeline of an app's installs, updates, uninstalls and corrections,
+/// newest first. Entries with details expand to show them.
+class HistoryDialog extends StatelessWidget {
+  final String appName;
+  final List<HistoryEntry> entries;
+
+  const HistoryDialog({super.key, required this.appName, required this.entries});
+
+  static IconData _iconFor(HistoryEntry entry) {
+    if (!entry.success) return Icons.error_outline;
+    switch (entry.action) {
+      case 'install':
+        return Icons.download;
+      case 'update':
+        return Icons.system_update;
+      case 'uninstall':
+        return Icons.delete_outline;
+      default:
+        return Icons.build_circle_outlined;
+    }
+  }
+
+  @override
+  Widget build(BuildContext context) {
+    final timeFormat = DateFormat('yyyy-MM-dd HH:mm');
+
+    return AlertDialog(
+      title: Text('History of $appName'),
+      content: SizedBox(
+        width: 500,
+        child: entries.isEmpty
+            ? const Text('Nothing recorded yet.')
+            : ListView(
+                shrinkWrap: true,
+                children: [
+                  for (final entry in entries)
+                    ExpansionTile(
+                      leading: Icon(
+                        _iconFor(entry),
+                        color: entry.success ? null : Theme.of(context).colorScheme.error,
+                      ),
+                      title: Text([
+                        '${entry.action[0].toUpperCase()}${entry.action.substring(1)}',
+                        if (entry.version != null) entry.version!,
+                        if (!entry.success) '(failed)',
+                      ].join(' ')),
+                      subtitle: Text([
+                        timeFormat.format(entry.timestamp.toLocal()),
+                        if (entry.installType != null) entry.installType!.displayName,
+                      ].join(' • ')),
+                      // Nothing to expand without details
+                      trailing: entry.details == null ? const SizedBox.shrink() : null,
+                      expandedCrossAxisAlignment: CrossAxisAlignment.start,
+                      childrenPadding: const EdgeInsets.fromLTRB(16, 0, 16, 12),
+                      children: [
+                        if (entry.details != null)
+                          SelectableText(entry.details!, style: Theme.of(context).textTheme.bodySmall),
+                      ],
+                    ),
+                ],
+              ),
+      ),
+      actions: [
+        FilledButton(
+          onPressed: () => Navigator.pop(context),
+          child: const Text('Close'),
+        ),
+      ],
+    );
+  }
+}
@@ -0,0 +1,58 @@
+import 'package:flutter/material.dart';
+import '../../models/tracked_app.dart';
+
+class ImportSnippetDialog extends StatefulWidget {
+  const ImportSnippetDialog({super.key});
+
+  @override
+  State<ImportSnippetDialog> createState() => _ImportSnippetDialogState();
+}
+
+class _ImportSnippetDialogState extends State<ImportSnippetDialog> {
+  final _snippetController = TextEditingController();
+  String? _error;
+
+  @override
+  void dispose() {
+    _snippetController.dispose();
+    super.dispose();
+  }
+
+  void _import() {
+    try {
+      final app = TrackedApp.fromSnippet(_snippetController.text);
+      Navigator.pop(context, app);
+    } on FormatException catch (e) {
+      setState(() => _error = e.message);
+    }
+  }
+
+  @override
+  Widget build(BuildContext context) {
+    return AlertDialog(
+      title: const Text('Import from Snippet'),
+      content: SizedBox(
+        width: 400,
+        child: TextField(
+          controller: _snippetController,
+          maxLines: 4,
+          decoration: InputDecoration(
+            labelText: 'Snippet',
+            hintText: '{"repo": "owner/repo", ...}',
+            errorText: _error,
+          ),
+        ),
+      ),
+      actions: [
+        TextButton(
+          onPressed: () => Navigator.pop(context),
+          child: const Text('Cancel'),
+        ),
+        FilledButton(
+          onPressed: _import,
+          child: const Text('Import'),
+        ),
+      ],
+    );
+  }
+}
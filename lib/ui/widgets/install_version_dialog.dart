@@ -0,0 +1,83 @@
+import 'package:flutter/material.dart';
+import '../../models/release.dart';
+
+/// Lists the recent [releases] of an app so an older or pre-release version
+/// can be installed, e.g. to roll back a broken update. A tag that is not in
+/// the list can be typed in. Pops with the chosen tag, or null when cancelled.
+class InstallVersionDialog extends StatefulWidget {
+  final List<Release> releases;
+
+  /// Marked in the list so it is clear what a pick would replace.
+  final String? installedVersion;
+
+  const InstallVersionDialog({super.key, required this.releases, this.installedVersion});
+
+  @override
+  State<InstallVersionDialog> createState() => _InstallVersionDialogState();
+}
+
+class _InstallVersionDialogState extends State<InstallVersionDialog> {
+  final _controller = TextEditingController();
+
+  @override
+  void dispose() {
+    _controller.dispose();
+    super.dispose();
+  }
+
+  void _submit() {
+    final tag = _controller.text.trim();
+    if (tag.isNotEmpty) Navigator.pop(context, tag);
+  }
+
+  @override
+  Widget build(BuildContext context) {
+    final releases = widget.releases.where((r) => !r.draft).toList();
+    return AlertDialog(
+      title: const Text('Install Specific Version'),
+      content: SizedBox(
+        width: 400,
+        child: Column(
+          mainAxisSize: MainAxisSize.min,
+          crossAxisAlignment: CrossAxisAlignment.stretch,
+          children: [
+            Flexible(
+              child: releases.isEmpty
+                  ? const Text('No releases found.')
+                  : ListView(
+                      shrinkWrap: true,
+                      children: [
+                        for (final release in releases)
+                          ListTile(
+                            title: Text(release.tagName),
+                            subtitle: Text([
+                              if (release.tagName == widget.installedVersion) 'Installed',
+                              if (release.prerelease) 'Pre-release',
+                            ].join(' • ')),
+                            onTap: () => Navigator.pop(context, release.tagName),
+                          ),
+                      ],
+                    ),
+            ),
+            TextField(
+              controller: _controller,
+              autocorrect: false,
+              decoration: const InputDecoration(labelText: 'Other tag', hintText: 'v1.2.3'),
+              onSubmitted: (_) => _submit(),
+            ),
+          ],
+        ),
+      ),
+      actions: [
+        TextButton(
+          onPressed: () => Navigator.pop(context),
+          child: const Text('Cancel'),
+        ),
+        FilledButton(
+          onPressed: _submit,
+          child: const Text('Install tag'),
+        ),
+      ],
+    );
+  }
+}
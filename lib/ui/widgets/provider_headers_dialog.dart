@@ -0,0 +1,116 @@
+import 'package:flutter/material.dart';
+import '../../services/github_service.dart';
+
+/// Edits the extra headers sent to each API host. Pops with the full
+/// host-to-headers map so hosts not currently shown are kept.
+class ProviderHeadersDialog extends StatefulWidget {
+  final Map<String, Map<String, String>> headers;
+
+  const ProviderHeadersDialog({super.key, required this.headers});
+
+  @override
+  State<ProviderHeadersDialog> createState() => _ProviderHeadersDialogState();
+}
+
+class _ProviderHeadersDialogState extends State<ProviderHeadersDialog> {
+  static const _defaultHost = 'api.github.com';
+
+  late final Map<String, Map<String, String>> _headers = {
+    for (final e in widget.headers.entries) e.key: Map.of(e.value),
+  };
+  final _hostController = TextEditingController(text: _defaultHost);
+  late final _headersController = TextEditingController(
+    text: GitHubService.formatHeaderLines(_headers[_defaultHost] ?? const {}),
+  );
+  String? _error;
+
+  @override
+  void dispose() {
+    _hostController.dispose();
+    _headersController.dispose();
+    super.dispose();
+  }
+
+  void _onHostChanged(String host) {
+    setState(() {
+      _error = null;
+      _headersController.text =
+          GitHubService.formatHeaderLines(_headers[host.trim()] ?? const {});
+    });
+  }
+
+  /// Folds the text field into [_headers]; returns false on a parse error.
+  bool _commit() {
+    final host = _hostController.text.trim();
+    if (host.isEmpty) return true;
+    try {
+      final parsed = GitHubService.parseHeaderLines(_headersController.text);
+      if (parsed.isEmpty) {
+        _headers.remove(host);
+      } else {
+        _headers[host] = parsed;
+      }
+      return true;
+    } on FormatException catch (e) {
+      setState(() => _error = '${e.message}: ${e.source}');
+      return false;
+    }
+  }
+
+  @override
+  Widget build(BuildContext context) {
+    return AlertDialog(
+      title: const Text('Custom Request Headers'),
+      content: SizedBox(
+        width: 400,
+        child: Column(
+          mainAxisSize: MainAxisSize.min,
+          crossAxisAlignment: CrossAxisAlignment.stretch,
+          children: [
+            if (_headers.isNotEmpty)
+              Wrap(
+                spacing: 8,
+                children: _headers.keys
+                    .map((host) => ActionChip(
+                          label: Text(host),
+                          onPressed: () {
+                            if (!_commit()) return;
+                            _hostController.text = host;
+                            _onHostChanged(host);
+                          },
+                        ))
+                    .toList(),
+              ),
+            TextField(
+              controller: _hostController,
+              decoration: const InputDecoration(labelText: 'Host'),
+              onChanged: _onHostChanged,
+            ),
+            TextField(
+              controller: _headersController,
+              maxLines: 4,
+              decoration: InputDecoration(
+                labelText: 'Headers',
+                hintText: 'X-Custom-Auth: secret',
+                helperText: 'One "Name: Value" per line',
+                errorText: _error,
+              ),
+            ),
+          ],
+        ),
+      ),
+      actions: [
+        TextButton(
+          onPressed: () => Navigator.pop(context),
+          child: const Text('Cancel'),
+        ),
+        FilledButton(
+          onPressed: () {
+            if (_commit()) Navigator.pop(context, _headers);
+          },
+          child: const Text('Save'),
+        ),
+      ],
+    );
+  }
+}
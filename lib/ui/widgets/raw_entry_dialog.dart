@@ -0,0 +1,79 @@
+import 'dart:convert';
+import 'package:flutter/material.dart';
+import '../../models/tracked_app.dart';
+
+/// Power-user escape hatch: shows every stored field of [app] as JSON and
+/// pops with the edited app. The id can't be changed.
+class RawEntryDialog extends StatefulWidget {
+  final TrackedApp app;
+
+  const RawEntryDialog({super.key, required this.app});
+
+  /// Parses [text] back into an app with [id], or throws a
+  /// [FormatException] describing what is wrong.
+  static TrackedApp parse(String text, int? id) {
+    final decoded = jsonDecode(text);
+    if (decoded is! Map<String, dynamic>) {
+      throw const FormatException('Entry must be a JSON object');
+    }
+    try {
+      return TrackedApp.fromMap({...decoded, 'id': id});
+    } catch (e) {
+      throw FormatException('Invalid entry: $e');
+    }
+  }
+
+  @override
+  State<RawEntryDialog> createState() => _RawEntryDialogState();
+}
+
+class _RawEntryDialogState extends State<RawEntryDialog> {
+  late final _controller = TextEditingController(
+    text: const JsonEncoder.withIndent('  ').convert(widget.app.toMap()),
+  );
+  String? _error;
+
+  @override
+  void dispose() {
+    _controller.dispose();
+    super.dispose();
+  }
+
+  void _save() {
+    try {
+      Navigator.pop(context, RawEntryDialog.parse(_controller.text, widget.app.id));
+    } on FormatException catch (e) {
+      setState(() => _error = e.message);
+    }
+  }
+
+  @override
+  Widget build(BuildContext context) {
+    return AlertDialog(
+      title: Text('${widget.app.displayName} Database Entry'),
+      content: SizedBox(
+        width: 500,
+        child: TextField(
+          controller: _controller,
+          maxLines: 20,
+          style: const TextStyle(fontFamily: 'monospace', fontSize: 12),
+          decoration: InputDecoration(
+            border: const OutlineInputBorder(),
+            errorText: _error,
+            errorMaxLines: 3,
+          ),
+        ),
+      ),
+      actions: [
+        TextButton(
+          onPressed: () => Navigator.pop(context),
+          child: const Text('Cancel'),
+        ),
+        FilledButton(
+          onPressed: _save,
+          child: const Text('Save'),
+        ),
+      ],
+    );
+  }
+}
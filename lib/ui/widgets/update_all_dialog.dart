@@ -0,0 +1,96 @@
+import 'package:flutter/material.dart';
+import '../../models/tracked_app.dart';
+import '../../services/autonomix_exception.dart';
+import '../../services/install_manager.dart';
+import '../../services/installer_service.dart';
+
+/// Lists the pending updates with their downloads so the user can untick
+/// any before confirming. Apps whose download could not be worked out
+/// (e.g. after a rate limit) are listed with the error and ticked too;
+/// they are checked again while updating. Pops with the ids of the apps to
+/// update.
+class UpdateAllDialog extends StatefulWidget {
+  final List<({TrackedApp app, InstallChoice choice})> updates;
+  final List<({TrackedApp app, Object error})> unresolved;
+
+  const UpdateAllDialog({super.key, required this.updates, this.unresolved = const []});
+
+  /// Subtitle of an app in [unresolved].
+  static String describeUnresolved(TrackedApp app, Object error) =>
+      '${app.installedVersion} → ${app.latestVersion} • ${AutonomixException.from(error).describe()}; '
+      'checked again when updating';
+
+  @override
+  State<UpdateAllDialog> createState() => _UpdateAllDialogState();
+}
+
+class _UpdateAllDialogState extends State<UpdateAllDialog> {
+  late final Set<int> _selected = {
+    ...widget.updates.map((u) => u.app.id!),
+    ...widget.unresolved.map((u) => u.app.id!),
+  };
+
+  int get _uncheckedSelected => widget.unresolved.where((u) => _selected.contains(u.app.id)).length;
+
+  int get _totalSize => widget.updates
+      .where((u) => _selected.contains(u.app.id))
+      .fold(0, (sum, u) => sum + u.choice.asset.size);
+
+  Widget _tile(TrackedApp app, String subtitle, {bool warning = false}) {
+    final id = app.id!;
+    return CheckboxListTile(
+      dense: true,
+      value: _selected.contains(id),
+      onChanged: (checked) => setState(() {
+        checked == true ? _selected.add(id) : _selected.remove(id);
+      }),
+      title: Text(app.displayName),
+      subtitle: Text(subtitle),
+      secondary: warning ? Icon(Icons.warning_amber, color: Theme.of(context).colorScheme.error) : null,
+    );
+  }
+
+  @override
+  Widget build(BuildContext context) {
+    return AlertDialog(
+      title: const Text('Update All'),
+      content: SizedBox(
+        width: 500,
+        child: Column(
+          mainAxisSize: MainAxisSize.min,
+          crossAxisAlignment: CrossAxisAlignment.stretch,
+          children: [
+            Flexible(
+              child: ListView(
+                shrinkWrap: true,
+                children: [
+                  for (final u in widget.updates)
+                    _tile(
+                      u.app,
+                      '${u.app.installedVersion} → ${u.choice.release.tagName}'
+                      ' • ${u.choice.asset.name} (${InstallerService.formatSize(u.choice.asset.size)})',
+                    ),
+                  for (final u in widget.unresolved)
+                    _tile(u.app, UpdateAllDialog.describeUnresolved(u.app, u.error), warning: true),
+                ],
+              ),
+            ),
+            const SizedBox(height: 8),
+            Text('Total download: ${InstallerService.formatSize(_totalSize)}'
+                '${_uncheckedSelected == 0 ? '' : ', plus $_uncheckedSelected not yet checked'}'),
+          ],
+        ),
+      ),
+      actions: [
+        TextButton(
+          onPressed: () => Navigator.pop(context),
+          child: const Text('Cancel'),
+        ),
+        FilledButton(
+          onPressed: _selected.isEmpty ? null : () => Navigator.pop(context, _selected),
+          child: Text('Update ${_selected.length}'),
+        ),
+      ],
+    );
+  }
+}
@@ -1,6 +1,40 @@
 #include "my_application.h"
 
+#include <cstring>
+#include <linux/limits.h>
+#include <unistd.h>
+
+// Keep in sync with Cli.commands in lib/cli.dart.
+static const char* const kCliCommands[] = {
+    "add", "check", "export", "import", "install", "list", "update-all"};
+
+// Replaces this process with the bundled command line interface when the
+// first argument is one of its commands, so they run before GTK needs a
+// display. Returns when that isn't the case or the CLI isn't bundled (e.g.
+// under `flutter run`); lib/main.dart then runs the command itself.
+static void exec_cli(int argc, char** argv) {
+  if (argc < 2) return;
+  gboolean is_command = FALSE;
+  for (const char* command : kCliCommands) {
+    if (strcmp(argv[1], command) == 0) is_command = TRUE;
+  }
+  if (!is_command) return;
+
+  char self_path[PATH_MAX];
+  ssize_t count = readlink("/proc/self/exe", self_path, PATH_MAX - 1);
+  if (count == -1) return;
+  self_path[count] = '\0';
+  char* path_end = strrchr(self_path, '/');
+  if (path_end == nullptr) return;
+  *path_end = '\0';
+
+  g_autofree gchar* cli = g_build_filename(self_path, "lib", CLI_NAME, nullptr);
+  argv[0] = cli;
+  execv(cli, argv);
+}
+
 int main(int argc, char** argv) {
+  exec_cli(argc, argv);
   g_autoptr(MyApplication) app = my_application_new();
   return g_application_run(G_APPLICATION(app), argc, argv);
 }
@@ -5,22 +5,141 @@
 #include <gdk/gdkx.h>
 #endif
 
+#ifdef HAVE_APPINDICATOR
+#include <libayatana-appindicator/app-indicator.h>
+#endif
+
 #include "flutter/generated_plugin_registrant.h"
+#include <cstring>
 #include <linux/limits.h>
 #include <unistd.h>
 
 struct _MyApplication {
   GtkApplication parent_instance;
   char** dart_entrypoint_arguments;
+  FlMethodChannel* window_channel;
+  GtkWindow* window;
+  // Closing the window hides it to the tray instead of quitting.
+  gboolean run_in_background;
+#ifdef HAVE_APPINDICATOR
+  AppIndicator* indicator;
+#endif
 };
 
 G_DEFINE_TYPE(MyApplication, my_application, GTK_TYPE_APPLICATION)
 
+#ifdef HAVE_APPINDICATOR
+static void tray_show_cb(GtkMenuItem* item, gpointer user_data) {
+  MyApplication* self = MY_APPLICATION(user_data);
+  gtk_window_present(self->window);
+}
+
+// Asks the Dart side to check every app, as the refresh button does.
+static void tray_check_cb(GtkMenuItem* item, gpointer user_data) {
+  MyApplication* self = MY_APPLICATION(user_data);
+  fl_method_channel_invoke_method(self->window_channel, "checkNow", nullptr,
+                                  nullptr, nullptr, nullptr);
+}
+
+static void tray_quit_cb(GtkMenuItem* item, gpointer user_data) {
+  g_application_quit(G_APPLICATION(user_data));
+}
+
+static void tray_add_item(GtkWidget* menu, const gchar* label, GCallback cb,
+                          MyApplication* self) {
+  GtkWidget* item = gtk_menu_item_new_with_label(label);
+  g_signal_connect(item, "activate", cb, self);
+  gtk_widget_show(item);
+  gtk_menu_shell_append(GTK_MENU_SHELL(menu), item);
+}
+
+static void tray_create(MyApplication* self) {
+  if (self->indicator != nullptr) return;
+  self->indicator = app_indicator_new(APPLICATION_ID, APPLICATION_ID,
+                                      APP_INDICATOR_CATEGORY_APPLICATION_STATUS);
+  app_indicator_set_title(self->indicator, "Autonomix");
+
+  GtkWidget* menu = gtk_menu_new();
+  tray_add_item(menu, "Show Autonomix", G_CALLBACK(tray_show_cb), self);
+  tray_add_item(menu, "Check for updates", G_CALLBACK(tray_check_cb), self);
+  tray_add_item(menu, "Quit", G_CALLBACK(tray_quit_cb), self);
+  app_indicator_set_menu(self->indicator, GTK_MENU(menu));
+  app_indicator_set_status(self->indicator, APP_INDICATOR_STATUS_ACTIVE);
+}
+#endif
+
+// Without a tray to bring the window back, closing it really quits.
+static gboolean window_delete_cb(GtkWidget* widget, GdkEvent* event,
+                                 gpointer user_data) {
+  MyApplication* self = MY_APPLICATION(user_data);
+  if (!self->run_in_background) return FALSE;
+  gtk_widget_hide(widget);
+  return TRUE;
+}
+
+// Handles calls on the "autonomix/window" channel. "setTitle" updates the
+// header bar (or plain title bar) so pending updates show in the title;
+// "present" raises the window, e.g. from a notification.
+// "setRunInBackground" turns hiding to the tray on or off and answers
+// whether this build has a tray at all.
+static void window_method_call_cb(FlMethodChannel* channel,
+                                  FlMethodCall* method_call,
+                                  gpointer user_data) {
+  MyApplication* self = MY_APPLICATION(user_data);
+  GtkWindow* window = self->window;
+  g_autoptr(FlMethodResponse) response = nullptr;
+
+  if (strcmp(fl_method_call_get_name(method_call), "setTitle") == 0) {
+    FlValue* args = fl_method_call_get_args(method_call);
+    if (fl_value_get_type(args) == FL_VALUE_TYPE_STRING) {
+      const gchar* title = fl_value_get_string(args);
+      GtkWidget* titlebar = gtk_window_get_titlebar(window);
+      if (titlebar != nullptr && GTK_IS_HEADER_BAR(titlebar)) {
+        gtk_header_bar_set_title(GTK_HEADER_BAR(titlebar), title);
+      } else {
+        gtk_window_set_title(window, title);
+      }
+      response = FL_METHOD_RESPONSE(fl_method_success_response_new(nullptr));
+    } else {
+      response = FL_METHOD_RESPONSE(fl_method_error_response_new(
+          "bad-args", "setTitle expects a string", nullptr));
+    }
+  } else if (strcmp(fl_method_call_get_name(method_call), "present") == 0) {
+    gtk_window_present(window);
+    response = FL_METHOD_RESPONSE(fl_method_success_response_new(nullptr));
+  } else if (strcmp(fl_method_call_get_name(method_call),
+                    "setRunInBackground") == 0) {
+    FlValue* args = fl_method_call_get_args(method_call);
+    gboolean enabled = fl_value_get_type(args) == FL_VALUE_TYPE_BOOL &&
+                       fl_value_get_bool(args);
+#ifdef HAVE_APPINDICATOR
+    if (enabled) tray_create(self);
+    if (self->indicator != nullptr) {
+      app_indicator_set_status(self->indicator,
+                               enabled ? APP_INDICATOR_STATUS_ACTIVE
+                                       : APP_INDICATOR_STATUS_PASSIVE);
+    }
+    self->run_in_background = enabled;
+    g_autoptr(FlValue) supported = fl_value_new_bool(TRUE);
+#else
+    self->run_in_background = FALSE;
+    g_autoptr(FlValue) supported = fl_value_new_bool(FALSE);
+#endif
+    response = FL_METHOD_RESPONSE(fl_method_success_response_new(supported));
+  } else {
+    response = FL_METHOD_RESPONSE(fl_method_not_implemented_response_new());
+  }
+
+  fl_method_call_respond(method_call, response, nullptr);
+}
+
 // Implements GApplication::activate.
 static void my_application_activate(GApplication* application) {
   MyApplication* self = MY_APPLICATION(application);
   GtkWindow* window =
       GTK_WINDOW(gtk_application_window_new(GTK_APPLICATION(application)));
+  self->window = window;
+  g_signal_connect(window, "delete-event", G_CALLBACK(window_delete_cb), self);
 
   char self_path[PATH_MAX];
   ssize_t count = readlink("/proc/self/exe", self_path, PATH_MAX);
@@ -74,6 +193,13 @@ static void my_application_activate(GApplication* application) {
 
   fl_register_plugins(FL_PLUGIN_REGISTRY(view));
 
+  g_autoptr(FlStandardMethodCodec) codec = fl_standard_method_codec_new();
+  self->window_channel = fl_method_channel_new(
+      fl_engine_get_binary_messenger(fl_view_get_engine(view)),
+      "autonomix/window", FL_METHOD_CODEC(codec));
+  fl_method_channel_set_method_call_handler(
+      self->window_channel, window_method_call_cb, self, nullptr);
+
   gtk_widget_realize(GTK_WIDGET(view));
   gtk_widget_show(GTK_WIDGET(window));
   gtk_widget_grab_focus(GTK_WIDGET(view));
@@ -122,6 +248,10 @@ static void my_application_shutdown(GApplication* application) {
 static void my_application_dispose(GObject* object) {
   MyApplication* self = MY_APPLICATION(object);
   g_clear_pointer(&self->dart_entrypoint_arguments, g_strfreev);
+  g_clear_object(&self->window_channel);
+#ifdef HAVE_APPINDICATOR
+  g_clear_object(&self->indicator);
+#endif
   G_OBJECT_CLASS(my_application_parent_class)->dispose(object);
 }
 
@@ -0,0 +1,123 @@
+import 'dart:io';
+import 'package:flutter_test/flutter_test.dart';
+import 'package:autonomix/cli.dart';
+import 'package:autonomix/models/install_type.dart';
+import 'package:autonomix/models/release_provider.dart';
+import 'package:autonomix/models/tracked_app.dart';
+
+void main() {
+  group('Cli', () {
+    test('handles only known subcommands', () {
+      expect(Cli.handles(['export', '--script']), isTrue);
+      expect(Cli.handles([]), isFalse);
+      expect(Cli.handles(['--some-engine-flag']), isFalse);
+    });
+
+    test('the Linux runner hands every command to the command line interface', () {
+      final runner = File('linux/runner/main.cc').readAsStringSync();
+      final list = RegExp(r'kCliCommands\[\] = \{([^}]*)\}').firstMatch(runner)!.group(1)!;
+
+      expect(RegExp(r'"([^"]+)"').allMatches(list).map((m) => m.group(1)).toSet(), Cli.commands);
+    });
+
+    test('nothing the command line interface imports needs Flutter', () {
+      final seen = <String>{};
+      final pending = ['lib/cli.dart'];
+      final flutterImports = <String>[];
+      while (pending.isNotEmpty) {
+        final path = pending.removeLast();
+        if (!seen.add(path)) continue;
+        // Conditional exports pick their plain Dart side without dart:ui
+        final source = File(path).readAsStringSync().replaceAll(RegExp(r"if \(dart\.library\.ui\) '[^']*'"), '');
+        for (final match in RegExp(r"^(?:import|export) '([^']+)'", multiLine: true).allMatches(source)) {
+          final uri = match.group(1)!;
+          if (uri.startsWith('package:autonomix/')) {
+            pending.add('lib/${uri.substring('package:autonomix/'.length)}');
+          } else if (uri.startsWith('package:flutter') || uri.startsWith('package:path_provider')) {
+            flutterImports.add('$path: $uri');
+          } else if (!uri.contains(':')) {
+            pending.add(File(path).parent.uri.resolve(uri).toFilePath());
+          }
+        }
+      }
+
+      expect(flutterImports, isEmpty);
+    });
+
+    test('restore script reinstalls installed apps at their recorded version', () {
+      final apps = [
+        TrackedApp(
+          id: 1,
+          repoOwner: 'owner',
+          repoName: 'tool',
+          displayName: "Bob's Tool",
+          installedVersion: 'v1.2.0',
+          installType: InstallType.appImage,
+          tagPrefix: 'v',
+          createdAt: DateTime.now(),
+        ),
+        TrackedApp(
+          id: 2,
+          repoOwner: 'owner',
+          repoName: 'untried',
+          displayName: 'Untried',
+          createdAt: DateTime.now(),
+        ),
+      ];
+
+      final script = Cli.restoreScript(apps, generatedAt: DateTime(2024, 1, 2));
+
+      expect(script, startsWith('#!/bin/sh\n'));
+      expect(script, contains("autonomix install 'owner/tool' --tag 'v1.2.0'"));
+      expect(script, contains(r"Bob'\''s Tool"));
+      expect(script, contains('"tag_prefix":"v"'));
+      expect(script, isNot(contains('untried')));
+    });
+
+    test('shellQuote escapes single quotes', () {
+      expect(Cli.shellQuote("it's"), r"'it'\''s'");
+    });
+
+    test('parseRepoArgument reads owner/repo, host paths and URLs', () {
+      final github = Cli.parseRepoArgument('owner/tool@cli-');
+      expect(github?.provider, ReleaseProvider.github);
+      expect(github?.owner, 'owner');
+      expect(github?.repo, 'tool');
+      expect(github?.tagPrefix, 'cli-');
+
+      final gitlab = Cli.parseRepoArgument('gitlab.com/group/sub/tool');
+      expect(gitlab?.provider, ReleaseProvider.gitlab);
+      expect(gitlab?.owner, 'group/sub');
+      expect(gitlab?.tagPrefix, isNull);
+
+      final selfHosted = Cli.parseRepoArgument('https://git.example.com/owner/tool');
+      expect(selfHosted?.provider, isNull);
+      expect(selfHosted?.host, 'git.example.com');
+
+      expect(Cli.parseRepoArgument('tool'), isNull);
+      expect(Cli.parseRepoArgument('owner/'), isNull);
+      expect(Cli.parseRepoArgument('a/b/c'), isNull);
+    });
+
+    test('formatList shows versions and pending updates', () {
+      final now = DateTime(2024, 1, 2);
+      final lines = Cli.formatList([
+        TrackedApp(
+          id: 1,
+          repoOwner: 'owner',
+          repoName: 'tool',
+          displayName: 'Tool',
+          installedVersion: 'v1.0.0',
+          latestVersion: 'v1.1.0',
+          createdAt: now,
+        ),
+        TrackedApp(id: 2, repoOwner: 'owner', repoName: 'new', displayName: 'New', createdAt: now),
+      ], now);
+
+      expect(lines, [
+        'owner/tool\tv1.0.0\tv1.1.0\tupdate available',
+        'owner/new\tnot installed\tunknown',
+      ]);
+    });
+  });
+}
@@ -0,0 +1,40 @@
+import 'package:flutter_test/flutter_test.dart';
+import 'package:autonomix/models/app_metadata.dart';
+import 'package:autonomix/models/release.dart';
+
+void main() {
+  group('AppMetadata', () {
+    test('combines repository and release details', () {
+      final release = Release(
+        tagName: 'v1.0.0',
+        name: 'First stable',
+        publishedAt: DateTime.utc(2024, 3, 1),
+        prerelease: false,
+        draft: false,
+        assets: [],
+      );
+
+      final metadata = AppMetadata.fromJson(
+        {'stargazers_count': 42, 'description': 'A tool', 'homepage': ''},
+        release,
+      );
+
+      expect(metadata.stars, 42);
+      expect(metadata.description, 'A tool');
+      expect(metadata.homepage, isNull);
+      expect(metadata.latestReleaseName, 'First stable');
+      expect(metadata.latestPublishedAt, DateTime.utc(2024, 3, 1));
+    });
+
+    test('reads language and license', () {
+      final metadata = AppMetadata.fromJson(
+        {'language': 'Rust', 'license': {'key': 'mit', 'spdx_id': 'MIT'}},
+        null,
+      );
+
+      expect(metadata.languageAndLicense, 'Rust • MIT');
+      expect(AppMetadata.fromJson({'license': {'spdx_id': 'NOASSERTION'}}, null).languageAndLicense, isNull);
+      expect(AppMetadata.fromJson({'language': 'Go'}, null).languageAndLicense, 'Go');
+    });
+  });
+}
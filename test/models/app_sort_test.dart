@@ -0,0 +1,45 @@
+import 'package:flutter_test/flutter_test.dart';
+import 'package:autonomix/models/app_sort.dart';
+import 'package:autonomix/models/tracked_app.dart';
+
+void main() {
+  group('AppSort.sort', () {
+    final now = DateTime(2024, 6, 1);
+    TrackedApp app(String name, {String latest = 'v1.0.0', DateTime? checked}) => TrackedApp(
+          repoOwner: 'owner',
+          repoName: name.toLowerCase(),
+          displayName: name,
+          installedVersion: 'v1.0.0',
+          latestVersion: latest,
+          lastChecked: checked,
+          createdAt: now,
+        );
+
+    final apps = [
+      app('Zed', checked: now.subtract(const Duration(hours: 1))),
+      app('bat', latest: 'v2.0.0'),
+      app('Alacritty', checked: now.subtract(const Duration(hours: 3))),
+      app('Ripgrep', latest: 'v1.1.0', checked: now),
+    ];
+    List<String> names(List<TrackedApp> sorted) => sorted.map((a) => a.displayName).toList();
+
+    test('orders by name, ignoring case', () {
+      expect(names(AppSort.name.sort(apps, now)), ['Alacritty', 'bat', 'Ripgrep', 'Zed']);
+    });
+
+    test('puts apps with updates first', () {
+      expect(names(AppSort.updatesFirst.sort(apps, now)), ['bat', 'Ripgrep', 'Alacritty', 'Zed']);
+    });
+
+    test('puts recently checked apps first and unchecked ones last', () {
+      expect(names(AppSort.lastChecked.sort(apps, now)), ['Ripgrep', 'Zed', 'Alacritty', 'bat']);
+    });
+  });
+
+  test('fromString reads back the stored name', () {
+    for (final sort in AppSort.values) {
+      expect(AppSort.fromString(sort.name), sort);
+    }
+    expect(AppSort.fromString('bogus'), isNull);
+  });
+}
@@ -0,0 +1,41 @@
+import 'package:flutter_test/flutter_test.dart';
+import 'package:autonomix/models/release_provider.dart';
+
+void main() {
+  group('ReleaseProvider.parseRepoUrl', () {
+    test('reads GitHub repository and release page URLs', () {
+      final parsed = ReleaseProvider.parseRepoUrl('https://github.com/owner/repo/releases/latest');
+
+      expect(parsed?.provider, ReleaseProvider.github);
+      expect(parsed?.owner, 'owner');
+      expect(parsed?.repo, 'repo');
+    });
+
+    test('keeps nested GitLab groups in the owner', () {
+      final parsed = ReleaseProvider.parseRepoUrl('https://gitlab.com/group/sub/tool.git');
+      final page = ReleaseProvider.parseRepoUrl('https://gitlab.com/group/tool/-/releases/v1.0');
+
+      expect(parsed?.provider, ReleaseProvider.gitlab);
+      expect(parsed?.owner, 'group/sub');
+      expect(parsed?.repo, 'tool');
+      expect(page?.owner, 'group');
+      expect(page?.repo, 'tool');
+    });
+
+    test('rejects other hosts and URLs without a repo', () {
+      expect(ReleaseProvider.parseRepoUrl('https://example.com/owner/repo'), isNull);
+      expect(ReleaseProvider.parseRepoUrl('https://github.com/owner'), isNull);
+    });
+
+    test('leaves the provider of unknown hosts open', () {
+      final parsed = ReleaseProvider.parseRepoUrl('https://git.example.com:3000/owner/repo/releases');
+      final codeberg = ReleaseProvider.parseRepoUrl('https://codeberg.org/owner/repo');
+
+      expect(parsed?.provider, isNull);
+      expect(parsed?.host, 'git.example.com:3000');
+      expect(parsed?.owner, 'owner');
+      expect(parsed?.repo, 'repo');
+      expect(codeberg?.provider, ReleaseProvider.gitea);
+    });
+  });
+}
@@ -0,0 +1,44 @@
+import 'package:flutter_test/flutter_test.dart';
+import 'package:autonomix/models/release.dart';
+
+void main() {
+  group('ReleaseAsset', () {
+    test('architectureOf recognises common aliases', () {
+      expect(ReleaseAsset.architectureOf('tool-linux-amd64.tar.gz'), 'x86_64');
+      expect(ReleaseAsset.architectureOf('tool-x86_64-unknown-linux-musl.tar.gz'), 'x86_64');
+      expect(ReleaseAsset.architectureOf('tool_arm64.deb'), 'aarch64');
+      expect(ReleaseAsset.architectureOf('tool-i686.AppImage'), 'x86');
+    });
+
+    test('architectureOf recognises 32-bit ARM', () {
+      expect(ReleaseAsset.architectureOf('tool-armv7l.AppImage'), 'armv7');
+      expect(ReleaseAsset.architectureOf('tool_1.0_armhf.deb'), 'armv7');
+      expect(ReleaseAsset.architectureOf('tool-linux-arm.tar.gz'), 'armv7');
+      expect(ReleaseAsset.architectureOf('tool-armv7-unknown-linux-gnueabihf.tar.gz'), 'armv7');
+      // Not mistaken for 64-bit ARM
+      expect(ReleaseAsset.architectureOf('tool-linux-arm64.tar.gz'), 'aarch64');
+    });
+
+    test('architectureOf recognises RISC-V, POWER and IBM Z', () {
+      expect(ReleaseAsset.architectureOf('tool-riscv64gc-unknown-linux-gnu.tar.gz'), 'riscv64');
+      expect(ReleaseAsset.architectureOf('tool_1.0_riscv64.deb'), 'riscv64');
+      expect(ReleaseAsset.architectureOf('tool-linux-ppc64le.tar.gz'), 'ppc64le');
+      expect(ReleaseAsset.architectureOf('tool_1.0_ppc64el.deb'), 'ppc64le');
+      expect(ReleaseAsset.architectureOf('tool-1.0.s390x.rpm'), 's390x');
+    });
+
+    test('architectureOf does not mistake x86_64 for x86', () {
+      expect(ReleaseAsset.architectureOf('tool-x86_64.AppImage'), 'x86_64');
+    });
+
+    test('architectureOf returns null for arch-less names', () {
+      expect(ReleaseAsset.architectureOf('tool.AppImage'), isNull);
+    });
+
+    test('libcOf distinguishes musl and glibc builds', () {
+      expect(ReleaseAsset.libcOf('tool-x86_64-unknown-linux-musl.tar.gz'), 'musl');
+      expect(ReleaseAsset.libcOf('tool-x86_64-unknown-linux-gnu.tar.gz'), 'glibc');
+      expect(ReleaseAsset.libcOf('tool.AppImage'), isNull);
+    });
+  });
+}
@@ -1,6 +1,7 @@
 import 'package:flutter_test/flutter_test.dart';
 import 'package:autonomix/models/tracked_app.dart';
 import 'package:autonomix/models/install_type.dart';
+import 'package:autonomix/models/release_provider.dart';
 
 void main() {
   group('TrackedApp', () {
@@ -79,5 +80,272 @@ void main() {
 
       expect(app.repoUrl, 'https://github.com/owner/repo');
     });
+
+    test('releaseNotesUrl prefers the configured changelog URL', () {
+      final app = TrackedApp(
+        repoOwner: 'owner',
+        repoName: 'repo',
+        displayName: 'App',
+        latestVersion: 'v1.2.0',
+        changelogUrl: 'https://example.com/changelog',
+        createdAt: DateTime.now(),
+      );
+
+      expect(app.releaseNotesUrl, 'https://example.com/changelog');
+    });
+
+    test('releaseNotesUrl falls back to the GitHub release page', () {
+      final app = TrackedApp(
+        repoOwner: 'owner',
+        repoName: 'repo',
+        displayName: 'App',
+        latestVersion: 'v1.2.0',
+        createdAt: DateTime.now(),
+      );
+
+      expect(app.releaseNotesUrl, 'https://github.com/owner/repo/releases/tag/v1.2.0');
+    });
+
+    test('GitLab apps link to GitLab and carry the host in their key', () {
+      final app = TrackedApp(
+        repoOwner: 'group/sub',
+        repoName: 'tool',
+        displayName: 'Tool',
+        latestVersion: 'v1.2.0',
+        provider: ReleaseProvider.gitlab,
+        createdAt: DateTime.now(),
+      );
+
+      expect(app.releaseNotesUrl, 'https://gitlab.com/group/sub/tool/-/releases/v1.2.0');
+      expect(app.trackingKey, 'gitlab.com/group/sub/tool');
+      expect(TrackedApp.fromMap(app.toMap()).provider, ReleaseProvider.gitlab);
+    });
+
+    test('self-hosted apps use their own host', () {
+      final app = TrackedApp(
+        repoOwner: 'owner',
+        repoName: 'tool',
+        displayName: 'Tool',
+        provider: ReleaseProvider.gitea,
+        host: 'git.example.com',
+        createdAt: DateTime.now(),
+      );
+
+      expect(app.repoUrl, 'https://git.example.com/owner/tool');
+      expect(app.trackingKey, 'git.example.com/owner/tool');
+    });
+
+    test('snippet round-trips repo and per-app settings', () {
+      final app = TrackedApp(
+        id: 7,
+        repoOwner: 'owner',
+        repoName: 'repo',
+        displayName: 'App',
+        installedVersion: '1.0.0',
+        tagPrefix: 'lts-',
+        assetIncludeKeywords: ['gtk'],
+        assetExcludeKeywords: ['debug', 'symbols'],
+        assetPattern: '*-gtk-*.AppImage',
+        createdAt: DateTime.now(),
+      );
+
+      final imported = TrackedApp.fromSnippet(app.toSnippet());
+
+      expect(imported.id, isNull);
+      expect(imported.repoOwner, 'owner');
+      expect(imported.repoName, 'repo');
+      expect(imported.tagPrefix, 'lts-');
+      expect(imported.assetIncludeKeywords, ['gtk']);
+      expect(imported.assetExcludeKeywords, ['debug', 'symbols']);
+      expect(imported.assetPattern, '*-gtk-*.AppImage');
+      expect(imported.installedVersion, isNull);
+    });
+
+    test('snippet round-trips a GitLab project in a subgroup', () {
+      final app = TrackedApp(
+        repoOwner: 'group/subgroup',
+        repoName: 'repo',
+        displayName: 'App',
+        provider: ReleaseProvider.gitlab,
+        createdAt: DateTime.now(),
+      );
+
+      final imported = TrackedApp.fromSnippet(app.toSnippet());
+
+      expect(imported.repoOwner, 'group/subgroup');
+      expect(imported.repoName, 'repo');
+      expect(imported.provider, ReleaseProvider.gitlab);
+    });
+
+    test('fromSnippet drops keyword lists that are not all strings', () {
+      final imported = TrackedApp.fromSnippet('{"repo": "owner/repo", "asset_include_keywords": ["gtk", 1]}');
+
+      expect(imported.assetIncludeKeywords, isNull);
+    });
+
+    test('fromSnippet drops an asset pattern that does not compile', () {
+      expect(TrackedApp.fromSnippet('{"repo": "owner/repo", "asset_pattern": "[abc"}').assetPattern, isNull);
+      expect(TrackedApp.fromSnippet('{"repo": "owner/repo", "asset_pattern": "*.deb"}').assetPattern, '*.deb');
+    });
+
+    test('fromSnippet rejects a snippet without a repo', () {
+      expect(() => TrackedApp.fromSnippet('{"display_name": "App"}'), throwsFormatException);
+    });
+
+    test('isDueForCheck honours the per-app interval', () {
+      final now = DateTime(2024, 1, 10, 12);
+      final app = TrackedApp(
+        repoOwner: 'owner',
+        repoName: 'repo',
+        displayName: 'App',
+        lastChecked: now.subtract(const Duration(hours: 2)),
+        checkInterval: const Duration(hours: 1),
+        createdAt: now,
+      );
+
+      expect(app.isDueForCheck(now), isTrue);
+      expect(app.copyWith(checkInterval: const Duration(days: 7)).isDueForCheck(now), isFalse);
+    });
+
+    test('isDueForCheck falls back to the default interval', () {
+      final now = DateTime(2024, 1, 10, 12);
+      final app = TrackedApp(
+        repoOwner: 'owner',
+        repoName: 'repo',
+        displayName: 'App',
+        lastChecked: now.subtract(const Duration(hours: 2)),
+        createdAt: now,
+      );
+
+      expect(app.isDueForCheck(now), isFalse);
+    });
+
+    test('hasUpdate prefers the version reported by the binary', () {
+      final app = TrackedApp(
+        repoOwner: 'owner',
+        repoName: 'repo',
+        displayName: 'App',
+        installedVersion: 'release-42',
+        reportedVersion: '1.2.0',
+        latestVersion: 'v1.3.0',
+        createdAt: DateTime.now(),
+      );
+
+      expect(app.hasUpdate, isTrue);
+    });
+
+    test('deletedAt survives a map round trip and can be cleared', () {
+      final deletedAt = DateTime(2024, 5, 1, 12);
+      final app = TrackedApp(
+        id: 3,
+        repoOwner: 'owner',
+        repoName: 'repo',
+        displayName: 'App',
+        deletedAt: deletedAt,
+        createdAt: DateTime.now(),
+      );
+
+      expect(TrackedApp.fromMap(app.toMap()).deletedAt, deletedAt);
+      expect(TrackedApp.fromMap({...app.toMap(), 'deleted_at': null}).deletedAt, isNull);
+    });
+
+    test('trackingKey tells monorepo products apart', () {
+      TrackedApp app({String? tagPrefix}) => TrackedApp(
+            repoOwner: 'owner',
+            repoName: 'mono',
+            displayName: 'Mono',
+            tagPrefix: tagPrefix,
+            createdAt: DateTime.now(),
+          );
+
+      expect(app().trackingKey, 'owner/mono');
+      expect(app(tagPrefix: '').trackingKey, 'owner/mono');
+      expect(app(tagPrefix: 'cli-v').trackingKey, 'owner/mono@cli-v');
+    });
+
+    test('snooze hides the update until it expires or a newer release lands', () {
+      final now = DateTime(2024, 6, 1);
+      TrackedApp app(String latest, {required DateTime snoozeUntil}) => TrackedApp(
+            repoOwner: 'owner',
+            repoName: 'repo',
+            displayName: 'App',
+            installedVersion: 'v1.0.0',
+            latestVersion: latest,
+            snoozeUntil: snoozeUntil,
+            snoozedVersion: 'v1.1.0',
+            createdAt: now,
+          );
+
+      final snoozed = app('v1.1.0', snoozeUntil: now.add(const Duration(days: 7)));
+      expect(snoozed.hasUpdate, isTrue);
+      expect(snoozed.showsUpdate(now), isFalse);
+
+      expect(app('v1.1.0', snoozeUntil: now.subtract(const Duration(days: 1))).showsUpdate(now), isTrue);
+      expect(app('v1.2.0', snoozeUntil: now.add(const Duration(days: 7))).showsUpdate(now), isTrue);
+    });
+
+    test('pinned apps never show an update, even for newer releases', () {
+      final now = DateTime(2024, 6, 1);
+      final app = TrackedApp(
+        repoOwner: 'owner',
+        repoName: 'repo',
+        displayName: 'App',
+        installedVersion: 'v1.0.0',
+        latestVersion: 'v2.0.0',
+        pinned: true,
+        createdAt: now,
+      );
+
+      expect(app.hasUpdate, isTrue);
+      expect(app.showsUpdate(now), isFalse);
+      expect(TrackedApp.fromMap(app.toMap()).pinned, isTrue);
+      expect(app.copyWith(pinned: false).showsUpdate(now), isTrue);
+    });
+
+    test('ignoring a version hides it until a newer release appears', () {
+      final now = DateTime(2024, 6, 1);
+      TrackedApp app(String latest) => TrackedApp(
+            repoOwner: 'owner',
+            repoName: 'repo',
+            displayName: 'App',
+            installedVersion: 'v1.0.0',
+            latestVersion: latest,
+            ignoredVersions: const ['v2.0.0'],
+            createdAt: now,
+          );
+
+      expect(app('v2.0.0').hasUpdate, isTrue);
+      expect(app('v2.0.0').showsUpdate(now), isFalse);
+      expect(app('2.0.0').showsUpdate(now), isFalse);
+      expect(app('v3.0.0').showsUpdate(now), isTrue);
+      expect(TrackedApp.fromMap(app('v2.0.0').toMap()).ignoredVersions, ['v2.0.0']);
+    });
+
+    test('snippets never carry the post-update command', () {
+      final app = TrackedApp(
+        repoOwner: 'owner',
+        repoName: 'repo',
+        displayName: 'App',
+        postUpdateCommand: 'rm -rf ~/.cache/app',
+        createdAt: DateTime.now(),
+      );
+
+      expect(TrackedApp.fromMap(app.toMap()).postUpdateCommand, 'rm -rf ~/.cache/app');
+      expect(app.toSnippet(), isNot(contains('post_update_command')));
+    });
+
+    test('snippets never carry the version command', () {
+      final app = TrackedApp(
+        repoOwner: 'owner',
+        repoName: 'repo',
+        displayName: 'App',
+        versionCommand: '{path} --version',
+        createdAt: DateTime.now(),
+      );
+
+      expect(app.toSnippet(), isNot(contains('version_command')));
+      expect(TrackedApp.fromSnippet('{"repo": "owner/repo", "version_command": "curl x | sh"}').versionCommand,
+          isNull);
+    });
   });
 }
@@ -0,0 +1,30 @@
+import 'dart:io';
+import 'package:flutter_test/flutter_test.dart';
+import 'package:autonomix/services/autonomix_exception.dart';
+import 'package:autonomix/services/github_service.dart';
+
+void main() {
+  group('AutonomixException', () {
+    test('from sorts connection problems under network', () {
+      final error = AutonomixException.from(const SocketException('Connection refused'));
+
+      expect(error.kind, ErrorKind.network);
+      expect(error.cause, isA<SocketException>());
+    });
+
+    test('from keeps the kind of errors that already have one', () {
+      final rateLimit = RateLimitException(null);
+
+      expect(AutonomixException.from(rateLimit), same(rateLimit));
+      expect(AutonomixException.kindOf(ReleaseNotFoundException('gone')), ErrorKind.notFound);
+    });
+
+    test('from uses the fallback for anything else', () {
+      final error = AutonomixException.from(Exception('dpkg broke'), fallback: ErrorKind.installFailed);
+
+      expect(error.kind, ErrorKind.installFailed);
+      expect(error.message, 'dpkg broke');
+      expect(error.describe(), 'Install failed: dpkg broke');
+    });
+  });
+}
@@ -0,0 +1,124 @@
+import 'dart:io';
+import 'package:flutter_test/flutter_test.dart';
+import 'package:autonomix/models/history_entry.dart';
+import 'package:autonomix/models/install_type.dart';
+import 'package:autonomix/models/tracked_app.dart';
+import 'package:autonomix/services/database_service.dart';
+import 'package:autonomix/services/github_service.dart';
+
+TrackedApp _self({String? installedVersion, InstallType? installType, String? tagPrefix}) {
+  return TrackedApp(
+    id: 1,
+    repoOwner: 'plebone',
+    repoName: 'autonomix',
+    displayName: 'Autonomix',
+    installedVersion: installedVersion,
+    installType: installType,
+    tagPrefix: tagPrefix,
+    createdAt: DateTime.now(),
+  );
+}
+
+void main() {
+  group('DatabaseService.reconcileSelf', () {
+    test('returns null when the entry is already up to date', () {
+      final existing = _self(installedVersion: 'v0.3.5', installType: InstallType.deb);
+
+      expect(DatabaseService.reconcileSelf(existing, '0.3.5', InstallType.deb), isNull);
+    });
+
+    test('updates the version but keeps user preferences', () {
+      final existing = _self(
+        installedVersion: '0.3.4',
+        installType: InstallType.deb,
+        tagPrefix: 'lts-',
+      );
+
+      final updated = DatabaseService.reconcileSelf(existing, '0.3.5', InstallType.deb)!;
+
+      expect(updated.installedVersion, '0.3.5');
+      expect(updated.tagPrefix, 'lts-');
+    });
+
+    test('never flips a recorded install type', () {
+      final existing = _self(installedVersion: '0.3.5', installType: InstallType.rpm);
+
+      expect(DatabaseService.reconcileSelf(existing, '0.3.5', InstallType.appImage), isNull);
+    });
+
+    test('fills in a missing install type', () {
+      final existing = _self(installedVersion: '0.3.5');
+
+      final updated = DatabaseService.reconcileSelf(existing, '0.3.5', InstallType.appImage)!;
+
+      expect(updated.installType, InstallType.appImage);
+    });
+  });
+
+  group('DatabaseService settings parsing', () {
+    test('decodeSettings rejects garbage instead of returning it', () {
+      expect(DatabaseService.decodeSettings(''), isEmpty);
+      expect(DatabaseService.decodeSettings('{"a": 1}'), {'a': 1});
+      expect(() => DatabaseService.decodeSettings('{"a": 1'), throwsFormatException);
+      expect(() => DatabaseService.decodeSettings('[1, 2]'), throwsFormatException);
+    });
+
+    test('settingOr falls back to the default for malformed values', () {
+      final settings = <String, dynamic>{
+        'use_gui_installer': 'yes please',
+        'notifications_enabled': false,
+      };
+
+      expect(DatabaseService.settingOr(settings, 'use_gui_installer', false), isFalse);
+      expect(DatabaseService.settingOr(settings, 'notifications_enabled', true), isFalse);
+      expect(DatabaseService.settingOr(settings, 'missing', 42), 42);
+    });
+
+    test('malformed header settings are ignored', () {
+      expect(GitHubService.headersFromSetting(['not', 'a', 'map']), isEmpty);
+      expect(GitHubService.headersFromSetting({'host': 'garbage'}), isEmpty);
+    });
+  });
+
+  group('DatabaseService history', () {
+    late DatabaseService db;
+
+    setUp(() async {
+      final directory = await Directory.systemTemp.createTemp('autonomix_history');
+      addTearDown(() => directory.delete(recursive: true));
+      db = DatabaseService(directory: directory);
+    });
+
+    HistoryEntry entry(int appId, int minute) => HistoryEntry(
+          appId: appId,
+          action: 'update',
+          version: 'v$minute',
+          timestamp: DateTime.utc(2024, 1, 1, 0, minute),
+          success: true,
+        );
+
+    test('keeps only the newest entries of each app', () async {
+      for (var i = 0; i < DatabaseService.maxHistoryPerApp + 5; i++) {
+        await db.addHistory(entry(1, i));
+      }
+      await db.addHistory(entry(2, 0));
+
+      final history = await db.getHistory(1);
+      expect(history, hasLength(DatabaseService.maxHistoryPerApp));
+      expect(history.first.version, 'v${DatabaseService.maxHistoryPerApp + 4}');
+      expect(history.last.version, 'v5');
+      expect(await db.getHistory(2), hasLength(1));
+    });
+
+    test('deleting an app drops its history', () async {
+      final id = await db.addApp('owner', 'tool', 'Tool');
+      await db.addHistory(entry(id, 0));
+      await db.addHistory(entry(id + 1, 0));
+
+      await db.deleteApp(id);
+
+      expect(await db.getHistory(id), isEmpty);
+      expect(await db.getHistory(id + 1), hasLength(1));
+    });
+  });
+}
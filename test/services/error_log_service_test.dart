@@ -0,0 +1,31 @@
+import 'package:flutter_test/flutter_test.dart';
+import 'package:autonomix/services/autonomix_exception.dart';
+import 'package:autonomix/services/error_log_service.dart';
+
+void main() {
+  group('ErrorLogService', () {
+    test('keeps only the most recent entries', () {
+      final log = ErrorLogService(capacity: 2);
+      log.record('Install', 'first');
+      log.record('Install', 'second');
+      log.record('Refresh', 'third');
+
+      expect(log.entries.map((e) => e.message), ['third', 'second']);
+    });
+
+    test('format includes the failed operation', () {
+      final log = ErrorLogService();
+      log.record('Uninstall', Exception('boom'));
+
+      expect(log.format(), contains('Uninstall: Exception: boom'));
+    });
+
+    test('entries keep the kind of error', () {
+      final log = ErrorLogService();
+      log.record('Install', AutonomixException(ErrorKind.authCancelled, 'Dismissed'));
+      log.record('Refresh', 'plain');
+
+      expect(log.entries.map((e) => e.kind), [ErrorKind.unknown, ErrorKind.authCancelled]);
+    });
+  });
+}
@@ -0,0 +1,39 @@
+import 'package:flutter_test/flutter_test.dart';
+import 'package:autonomix/models/release.dart';
+import 'package:autonomix/services/gitea_service.dart';
+
+void main() {
+  group('GiteaService', () {
+    test('releases read like GitHub ones without a content type', () {
+      final release = Release.fromJson({
+        'tag_name': 'v1.0.0',
+        'prerelease': false,
+        'draft': false,
+        'assets': [
+          {
+            'name': 'tool_1.0.0_amd64.deb',
+            'browser_download_url': 'https://codeberg.org/owner/tool/releases/download/v1.0.0/tool_1.0.0_amd64.deb',
+            'size': 2048,
+          },
+        ],
+      });
+
+      expect(release.assets.single.contentType, 'application/octet-stream');
+      expect(release.assets.single.size, 2048);
+    });
+
+    test('repositoryFromJson renames stars and website', () {
+      final repository = GiteaService.repositoryFromJson({
+        'name': 'tool',
+        'description': 'A tool',
+        'owner': {'login': 'owner'},
+        'stars_count': 5,
+        'website': 'https://tool.example.com',
+      });
+
+      expect(repository['stargazers_count'], 5);
+      expect(repository['homepage'], 'https://tool.example.com');
+      expect(repository['owner']['login'], 'owner');
+    });
+  });
+}
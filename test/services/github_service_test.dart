@@ -0,0 +1,180 @@
+import 'package:flutter_test/flutter_test.dart';
+import 'package:http/http.dart' as http;
+import 'package:http/testing.dart';
+import 'package:autonomix/models/release.dart';
+import 'package:autonomix/services/github_service.dart';
+
+void main() {
+  group('GitHubService custom headers', () {
+    test('parses one header per line and skips blanks', () {
+      final headers = GitHubService.parseHeaderLines('X-Gitea-OTP: 123456\n\nAuthorization: token abc:def\n');
+
+      expect(headers, {'X-Gitea-OTP': '123456', 'Authorization': 'token abc:def'});
+    });
+
+    test('rejects lines without a header name', () {
+      expect(() => GitHubService.parseHeaderLines(': value'), throwsFormatException);
+      expect(() => GitHubService.parseHeaderLines('no colon here'), throwsFormatException);
+    });
+
+    test('round-trips through the text format', () {
+      const headers = {'X-One': '1', 'X-Two': 'two words'};
+
+      expect(GitHubService.parseHeaderLines(GitHubService.formatHeaderLines(headers)), headers);
+    });
+
+    test('ignores malformed settings entries', () {
+      final parsed = GitHubService.headersFromSetting({
+        'git.example.com': {'X-Auth': 'secret', 'X-Bad': 3},
+        'broken': 'not a map',
+      });
+
+      expect(parsed, {
+        'git.example.com': {'X-Auth': 'secret'},
+      });
+      expect(GitHubService.headersFromSetting(null), isEmpty);
+    });
+  });
+
+  group('GitHubService.parseCompareCommits', () {
+    test('keeps first lines, newest first', () {
+      final commits = GitHubService.parseCompareCommits({
+        'commits': [
+          {'commit': {'message': 'Fix crash on start\n\nLong explanation'}},
+          {'commit': {'message': ''}},
+          {'commit': {'message': 'Add dark mode'}},
+        ],
+      });
+
+      expect(commits, ['Add dark mode', 'Fix crash on start']);
+      expect(GitHubService.parseCompareCommits({}), isEmpty);
+    });
+  });
+
+  group('GitHubService.rateLimitFrom', () {
+    test('reads the reset time of the primary limit', () {
+      final limited = GitHubService.rateLimitFrom(403, {
+        'x-ratelimit-remaining': '0',
+        'x-ratelimit-reset': '1700000000',
+      });
+
+      expect(limited?.resetAt, DateTime.fromMillisecondsSinceEpoch(1700000000 * 1000, isUtc: true));
+    });
+
+    test('honours retry-after for secondary limits', () {
+      final now = DateTime.utc(2024, 1, 1);
+
+      final limited = GitHubService.rateLimitFrom(429, {'retry-after': '60'}, now: now);
+
+      expect(limited?.resetAt, now.add(const Duration(seconds: 60)));
+    });
+
+    test('a limited response surfaces the reset time and stops further requests', () async {
+      final reset = DateTime.now().add(const Duration(minutes: 20));
+      final resetSeconds = reset.millisecondsSinceEpoch ~/ 1000;
+      var requests = 0;
+      final gh = GitHubService(
+        client: MockClient((request) async {
+          requests++;
+          return http.Response('{"message": "API rate limit exceeded"}', 403, headers: {
+            'x-ratelimit-remaining': '0',
+            'x-ratelimit-reset': '$resetSeconds',
+          });
+        }),
+      );
+
+      await expectLater(
+        gh.getLatestRelease('owner', 'repo'),
+        throwsA(isA<RateLimitException>().having(
+          (e) => e.resetAt,
+          'resetAt',
+          DateTime.fromMillisecondsSinceEpoch(resetSeconds * 1000, isUtc: true),
+        )),
+      );
+      expect(gh.rateLimit?.remaining, 0);
+
+      await expectLater(gh.getLatestRelease('owner', 'other'), throwsA(isA<RateLimitException>()));
+      expect(requests, 1);
+    });
+
+    test('ignores other errors', () {
+      expect(GitHubService.rateLimitFrom(403, {'x-ratelimit-remaining': '12'}), isNull);
+      expect(GitHubService.rateLimitFrom(404, {'x-ratelimit-remaining': '0'}), isNull);
+    });
+  });
+
+  group('GitHubService token', () {
+    test('the environment wins over the stored setting', () {
+      expect(GitHubService.tokenFrom({'github_token': 'stored'}, {'GITHUB_TOKEN': 'env'}), 'env');
+      expect(GitHubService.tokenFrom({'github_token': 'stored'}, {}), 'stored');
+      expect(GitHubService.tokenFrom({'github_token': '  '}, {'GITHUB_TOKEN': ''}), isNull);
+    });
+
+    test('downloads go through the API endpoint only with a token', () {
+      final asset = ReleaseAsset(
+        name: 'tool.deb',
+        browserDownloadUrl: 'https://github.com/o/r/releases/download/v1/tool.deb',
+        apiUrl: 'https://api.github.com/repos/o/r/releases/assets/1',
+        contentType: 'application/octet-stream',
+        size: 1,
+      );
+      final gh = GitHubService();
+
+      expect(gh.downloadRequestFor(asset).url, asset.browserDownloadUrl);
+
+      gh.token = 'secret';
+      final request = gh.downloadRequestFor(asset);
+      expect(request.url, asset.apiUrl);
+      expect(request.headers['Authorization'], 'Bearer secret');
+      expect(request.headers['Accept'], 'application/octet-stream');
+    });
+
+    test('public downloads send the host\'s custom headers', () {
+      final asset = ReleaseAsset(
+        name: 'tool.deb',
+        browserDownloadUrl: 'https://github.com/o/r/releases/download/v1/tool.deb',
+        contentType: 'application/octet-stream',
+        size: 1,
+      );
+      final gh = GitHubService()
+        ..token = 'secret'
+        ..customHeaders = {
+          'github.com': {'X-Proxy-Auth': 'abc'},
+        };
+
+      final request = gh.downloadRequestFor(asset);
+      expect(request.url, asset.browserDownloadUrl);
+      expect(request.headers['X-Proxy-Auth'], 'abc');
+      expect(request.headers['User-Agent'], isNotNull);
+      expect(request.headers.containsKey('Authorization'), isFalse);
+    });
+  });
+
+  group('GitHubService ETag cache', () {
+    const release = '{"tag_name": "v1.2.0", "assets": []}';
+
+    test('reuses the cached body when the server answers 304', () async {
+      final sentTags = <String?>[];
+      final gh = GitHubService(
+        client: MockClient((request) async {
+          sentTags.add(request.headers['If-None-Match']);
+          if (request.headers['If-None-Match'] == '"abc"') return http.Response('', 304);
+          return http.Response(release, 200, headers: {'etag': '"abc"'});
+        }),
+      );
+
+      expect((await gh.getLatestRelease('owner', 'repo')).tagName, 'v1.2.0');
+      expect((await gh.getLatestRelease('owner', 'repo')).tagName, 'v1.2.0');
+      expect(sentTags, [null, '"abc"']);
+    });
+
+    test('round-trips through JSON and skips malformed entries', () {
+      final etags = {'https://api.github.com/repos/o/r': (etag: 'W/"1"', body: '{}')};
+      final json = GitHubService.etagsToJson(etags);
+
+      expect(GitHubService.etagsFromJson(json), etags);
+      expect(GitHubService.etagsFromJson({'x': {'etag': 1}, 'y': 'z'}), isEmpty);
+      expect(GitHubService.etagsFromJson(null), isEmpty);
+    });
+  });
+}
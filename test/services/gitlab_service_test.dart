@@ -0,0 +1,46 @@
+import 'package:flutter_test/flutter_test.dart';
+import 'package:autonomix/services/gitlab_service.dart';
+
+void main() {
+  group('GitLabService', () {
+    test('releaseFromJson reads tag, notes and asset links', () {
+      final release = GitLabService.releaseFromJson({
+        'tag_name': 'v2.0.0',
+        'name': 'Tool 2.0',
+        'description': 'Notes',
+        'released_at': '2024-05-01T12:00:00Z',
+        'upcoming_release': false,
+        'assets': {
+          'links': [
+            {
+              'name': 'tool-x86_64.AppImage',
+              'url': 'https://gitlab.com/group/tool/-/package_files/1/download',
+              'direct_asset_url': 'https://gitlab.com/group/tool/-/releases/v2.0.0/downloads/tool-x86_64.AppImage',
+            },
+          ],
+          'sources': [],
+        },
+      });
+
+      expect(release.tagName, 'v2.0.0');
+      expect(release.body, 'Notes');
+      expect(release.prerelease, isFalse);
+      expect(release.assets.single.name, 'tool-x86_64.AppImage');
+      expect(release.assets.single.browserDownloadUrl, endsWith('/downloads/tool-x86_64.AppImage'));
+    });
+
+    test('repositoryFromJson maps a project onto GitHub repository fields', () {
+      final repository = GitLabService.repositoryFromJson({
+        'path': 'tool',
+        'description': '',
+        'star_count': 12,
+        'namespace': {'full_path': 'group/sub'},
+      });
+
+      expect(repository['name'], 'tool');
+      expect(repository['description'], isNull);
+      expect(repository['owner']['login'], 'group/sub');
+      expect(repository['stargazers_count'], 12);
+    });
+  });
+}
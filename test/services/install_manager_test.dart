@@ -0,0 +1,263 @@
+import 'dart:io';
+import 'package:flutter_test/flutter_test.dart';
+import 'package:autonomix/models/install_type.dart';
+import 'package:autonomix/models/release.dart';
+import 'package:autonomix/models/tracked_app.dart';
+import 'package:autonomix/services/database_service.dart';
+import 'package:autonomix/services/github_service.dart';
+import 'package:autonomix/services/install_manager.dart';
+import 'package:autonomix/services/install_tracker.dart';
+import 'package:autonomix/services/installer_service.dart';
+
+/// Answers each app's latest release after a short, varying delay so
+/// concurrent checks finish out of order.
+class _SlowGitHubService extends GitHubService {
+  @override
+  Future<Release> getLatestReleaseFor(TrackedApp app) async {
+    await Future.delayed(Duration(milliseconds: 5 * (app.id! % 3)));
+    return Release(tagName: 'v${app.id}.0.0', prerelease: false, draft: false, assets: const []);
+  }
+}
+
+/// Always out of API requests, without saying when that ends.
+class _LimitedGitHubService extends GitHubService {
+  @override
+  Future<Release> getLatestReleaseFor(TrackedApp app) async => throw RateLimitException(null);
+}
+
+void main() {
+  group('InstallManager.rateLimitWait', () {
+    final now = DateTime.utc(2024, 1, 1, 12);
+
+    test('waits until the reset plus a small margin', () {
+      final wait = InstallManager.rateLimitWait(now.add(const Duration(minutes: 10)), now);
+
+      expect(wait, const Duration(minutes: 10, seconds: 5));
+    });
+
+    test('never waits a negative time and defaults to a minute', () {
+      expect(InstallManager.rateLimitWait(now.subtract(const Duration(minutes: 1)), now),
+          const Duration(seconds: 5));
+      expect(InstallManager.rateLimitWait(null, now), const Duration(minutes: 1));
+    });
+  });
+
+  group('InstallManager.describeCorrections', () {
+    final before = TrackedApp(
+      repoOwner: 'owner',
+      repoName: 'tool',
+      displayName: 'Tool',
+      installedVersion: 'v1.0.0',
+      installType: InstallType.appImage,
+      launchCommand: '/home/me/appimages/Tool.AppImage',
+      createdAt: DateTime.utc(2024),
+    );
+
+    test('lists each corrected field', () {
+      final after = TrackedApp.fromMap({
+        ...before.toMap(),
+        'install_type': 'flatpak',
+        'package_name': 'org.example.Tool',
+        'launch_command': null,
+      });
+
+      expect(InstallManager.describeCorrections(before, after), [
+        'install type AppImage -> Flatpak',
+        'package none -> org.example.Tool',
+        'launch command /home/me/appimages/Tool.AppImage -> none',
+      ]);
+    });
+
+    test('is empty when the record was already right', () {
+      expect(InstallManager.describeCorrections(before, before), isEmpty);
+    });
+  });
+
+  group('InstallManager.runBounded', () {
+    test('never runs more than the limit at once and waits for all', () async {
+      var running = 0;
+      var peak = 0;
+      final done = <int>[];
+
+      await InstallManager.runBounded(List.generate(12, (i) => i), 3, (i) async {
+        running++;
+        peak = running > peak ? running : peak;
+        await Future.delayed(Duration(milliseconds: 5 + i % 4));
+        running--;
+        done.add(i);
+      });
+
+      expect(peak, 3);
+      expect(done..sort(), List.generate(12, (i) => i));
+    });
+
+    test('runs everything for a limit below one', () async {
+      final done = <int>[];
+      await InstallManager.runBounded([1, 2], 0, (i) async => done.add(i));
+
+      expect(done, [1, 2]);
+    });
+  });
+
+  group('InstallManager.installAll', () {
+    test('gives up on an app after the last rate limit retry and moves on', () async {
+      final manager = InstallManager(DatabaseService(), _LimitedGitHubService(), InstallerService(), InstallTracker())
+        ..maxRateLimitRetries = 0;
+      final apps = [
+        for (final id in [1, 2])
+          TrackedApp(
+            id: id,
+            repoOwner: 'owner',
+            repoName: 'tool$id',
+            displayName: 'Tool $id',
+            createdAt: DateTime.now(),
+          ),
+      ];
+      var pauses = 0;
+
+      final summary = await manager.installAll(apps, onPaused: (_) => pauses++);
+
+      expect(summary.failed.keys, ['Tool 1', 'Tool 2']);
+      expect(summary.installed, isEmpty);
+      expect(pauses, 0);
+    });
+  });
+
+  group('InstallManager.refreshAll', () {
+    test('records every app when checks overlap', () async {
+      final directory = await Directory.systemTemp.createTemp('autonomix_refresh');
+      addTearDown(() => directory.delete(recursive: true));
+      final db = DatabaseService(directory: directory);
+      for (var i = 0; i < 8; i++) {
+        await db.addApp('owner', 'tool$i', 'Tool $i');
+      }
+      final manager = InstallManager(db, _SlowGitHubService(), InstallerService(), InstallTracker())
+        ..refreshConcurrency = 4;
+
+      final summary = await manager.refreshAll(await db.getAllApps());
+
+      expect(summary.refreshed, hasLength(8));
+      final stored = await db.getAllApps();
+      expect(stored, hasLength(8));
+      for (final app in stored) {
+        expect(app.latestVersion, 'v${app.id}.0.0', reason: app.repoName);
+        expect(app.lastChecked, isNotNull, reason: app.repoName);
+      }
+    });
+  });
+
+  group('RefreshSummary.completionMessage', () {
+    final now = DateTime.utc(2024, 1, 1, 12);
+    TrackedApp app(String installed, String latest) => TrackedApp(
+          repoOwner: 'owner',
+          repoName: 'tool-$installed-$latest',
+          displayName: 'Tool',
+          installedVersion: installed,
+          latestVersion: latest,
+          createdAt: now,
+        );
+
+    test('counts updates and failures', () {
+      final summary = RefreshSummary()
+        ..refreshed.addAll([app('v1', 'v2'), app('v1', 'v1'), app('v2', 'v3')])
+        ..failed[app('v1', 'v1')] = Exception('offline');
+
+      expect(summary.completionMessage(now), 'Update check complete: 2 updates available, 1 failed');
+    });
+
+    test('says when everything is up to date', () {
+      final summary = RefreshSummary()..refreshed.add(app('v1', 'v1'));
+
+      expect(summary.completionMessage(now), 'Update check complete: everything is up to date');
+    });
+  });
+
+  group('InstallManager.releaseNotesOf', () {
+    Release release(String? body) =>
+        Release(tagName: 'v1.0.0', body: body, prerelease: false, draft: false, assets: const []);
+
+    test('keeps published notes and drops empty ones', () {
+      expect(InstallManager.releaseNotesOf(release('  Fixed the crash on start\n')), 'Fixed the crash on start');
+      expect(InstallManager.releaseNotesOf(release('   ')), isNull);
+      expect(InstallManager.releaseNotesOf(release(null)), isNull);
+    });
+
+    test('cuts very long notes short', () {
+      final notes = InstallManager.releaseNotesOf(release('x' * (InstallManager.maxReleaseNotesLength + 10)))!;
+
+      expect(notes.length, lessThan(InstallManager.maxReleaseNotesLength + 10));
+      expect(notes, endsWith('…'));
+    });
+  });
+
+  group('InstallManager.fallbackReason', () {
+    test('names the release that was passed over', () {
+      expect(InstallManager.fallbackReason('v2.0.0', 'best match for x86_64'),
+          'fell back from v2.0.0, which has no compatible package; best match for x86_64');
+    });
+  });
+
+  group('InstallManager.candidatesFor', () {
+    final manager = InstallManager(DatabaseService(), GitHubService(), InstallerService(), InstallTracker());
+    Release release(List<String> names) => Release(
+          tagName: 'v1.0.0',
+          prerelease: false,
+          draft: false,
+          assets: [
+            for (final name in names)
+              ReleaseAsset(
+                name: name,
+                browserDownloadUrl: 'https://example.com/$name',
+                contentType: 'application/octet-stream',
+                size: 1,
+              ),
+          ],
+        );
+    TrackedApp app({String? assetPattern}) => TrackedApp(
+          repoOwner: 'owner',
+          repoName: 'tool',
+          displayName: 'Tool',
+          assetPattern: assetPattern,
+          createdAt: DateTime.utc(2024),
+        );
+
+    test('keeps the best ranked asset of each type, skipping other architectures', () {
+      final candidates = manager.candidatesFor(
+          app(), release(['tool_1.0_amd64.deb', 'tool_1.0_arm64.deb', 'tool-1.0-x86_64.AppImage']),
+          hostArch: 'x86_64');
+
+      expect(candidates.keys, [InstallType.deb, InstallType.appImage]);
+      expect(candidates[InstallType.deb]?.name, 'tool_1.0_amd64.deb');
+    });
+
+    test('applies the app\'s asset pattern', () {
+      final candidates = manager.candidatesFor(app(assetPattern: '*.AppImage'),
+          release(['tool_1.0_amd64.deb', 'tool-1.0-x86_64.AppImage']), hostArch: 'x86_64');
+
+      expect(candidates.keys, [InstallType.appImage]);
+    });
+  });
+
+  group('InstallManager.needsAvailabilityCheck', () {
+    final app = TrackedApp(
+      repoOwner: 'owner',
+      repoName: 'tool',
+      displayName: 'Tool',
+      installedVersion: 'v1.0.0',
+      latestVersion: 'v2.0.0',
+      checkedInstalledVersion: 'v1.0.0',
+      createdAt: DateTime.utc(2024),
+    );
+
+    test('skips the check while neither version changed', () {
+      expect(InstallManager.needsAvailabilityCheck(app, 'v2.0.0'), isFalse);
+    });
+
+    test('checks again after a new release or a different install', () {
+      expect(InstallManager.needsAvailabilityCheck(app, 'v2.1.0'), isTrue);
+      expect(InstallManager.needsAvailabilityCheck(app.copyWith(installedVersion: 'v1.1.0'), 'v2.0.0'), isTrue);
+      final unknown = TrackedApp.fromMap({...app.toMap(), 'checked_installed_version': null});
+      expect(InstallManager.needsAvailabilityCheck(unknown, 'v2.0.0'), isTrue);
+    });
+  });
+}
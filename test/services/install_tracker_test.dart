@@ -0,0 +1,64 @@
+import 'package:flutter_test/flutter_test.dart';
+import 'package:autonomix/services/install_tracker.dart';
+
+void main() {
+  group('InstallTracker', () {
+    test('refuses a second operation for the same app', () {
+      final tracker = InstallTracker();
+
+      expect(tracker.tryStart(1), isTrue);
+      expect(tracker.tryStart(1), isFalse);
+      expect(tracker.tryStart(2), isTrue);
+      expect(tracker.isBusy(1), isTrue);
+    });
+
+    test('finish allows the app to be installed again', () {
+      final tracker = InstallTracker();
+      tracker.tryStart(1);
+      tracker.finish(1);
+
+      expect(tracker.isBusy(1), isFalse);
+      expect(tracker.tryStart(1), isTrue);
+    });
+
+    test('pauses and resumes the download of a running operation', () {
+      final tracker = InstallTracker();
+      var notified = 0;
+      tracker.addListener(() => notified++);
+
+      tracker.pauseDownload(1);
+      expect(tracker.isPaused(1), isFalse);
+
+      tracker.tryStart(1);
+      tracker.pauseDownload(1);
+      expect(tracker.isPaused(1), isTrue);
+      expect(tracker.downloadFor(1)!.isPaused, isTrue);
+
+      tracker.resumeDownload(1);
+      expect(tracker.isPaused(1), isFalse);
+      expect(notified, 3);
+    });
+
+    test('reports download progress once per percent until finished', () {
+      final tracker = InstallTracker();
+      var notified = 0;
+
+      tracker.reportProgress(1, 10, 100);
+      expect(tracker.progressOf(1), isNull);
+
+      tracker.tryStart(1);
+      tracker.addListener(() => notified++);
+      tracker.reportProgress(1, 1000, 10000);
+      tracker.reportProgress(1, 1050, 10000);
+      tracker.reportProgress(1, 1100, 10000);
+      expect(tracker.progressOf(1), closeTo(0.11, 1e-9));
+      expect(notified, 2);
+
+      tracker.reportProgress(1, 500, null);
+      expect(tracker.progressOf(1), closeTo(0.11, 1e-9));
+
+      tracker.finish(1);
+      expect(tracker.progressOf(1), isNull);
+    });
+  });
+}
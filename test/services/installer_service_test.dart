@@ -0,0 +1,753 @@
+import 'dart:convert';
+import 'dart:io';
+import 'package:flutter_test/flutter_test.dart';
+import 'package:http/http.dart' as http;
+import 'package:http/testing.dart';
+import 'package:autonomix/models/install_type.dart';
+import 'package:autonomix/models/release.dart';
+import 'package:autonomix/models/tracked_app.dart';
+import 'package:autonomix/services/autonomix_exception.dart';
+import 'package:autonomix/services/installer_service.dart';
+
+/// [text], then a dropped connection.
+Stream<List<int>> _cutOff(String text) async* {
+  yield utf8.encode(text);
+  throw http.ClientException('Connection closed');
+}
+
+ReleaseAsset _asset(String name) {
+  return ReleaseAsset(
+    name: name,
+    browserDownloadUrl: 'https://example.com/$name',
+    contentType: 'application/octet-stream',
+    size: 1,
+  );
+}
+
+void main() {
+  group('InstallerService', () {
+    test('parseFlatpakRef reads the Flatpak Ref group', () {
+      const ref = '''
+[Flatpak Ref]
+Name=org.example.App
+Url=https://dl.example.org/repo/
+SuggestRemoteName=example
+RuntimeRepo=https://dl.flathub.org/repo/flathub.flatpakrepo
+
+[Other]
+Url=https://ignored.example.org
+''';
+
+      final fields = InstallerService.parseFlatpakRef(ref);
+
+      expect(fields['Name'], 'org.example.App');
+      expect(fields['Url'], 'https://dl.example.org/repo/');
+      expect(fields['SuggestRemoteName'], 'example');
+      expect(fields['RuntimeRepo'], 'https://dl.flathub.org/repo/flathub.flatpakrepo');
+    });
+
+    test('escapeDesktopExecArg quotes paths with spaces', () {
+      expect(
+        InstallerService.escapeDesktopExecArg('/home/me/My Apps/Foo.AppImage'),
+        '"/home/me/My Apps/Foo.AppImage"',
+      );
+    });
+
+    test('escapeDesktopExecArg leaves plain paths alone', () {
+      expect(
+        InstallerService.escapeDesktopExecArg('/opt/apps/Foo.AppImage'),
+        '/opt/apps/Foo.AppImage',
+      );
+    });
+
+    test('escapeDesktopExecArg doubles percent signs', () {
+      expect(
+        InstallerService.escapeDesktopExecArg('/opt/100%/Foo.AppImage'),
+        '/opt/100%%/Foo.AppImage',
+      );
+    });
+
+    test(r'escapeDesktopExecArg escapes $ inside quotes for both parsing passes', () {
+      expect(
+        InstallerService.escapeDesktopExecArg(r'/opt/a $b/Foo.AppImage'),
+        r'"/opt/a \\$b/Foo.AppImage"',
+      );
+    });
+
+    test('identifyAssetType recognises zstd tarballs', () {
+      final installer = InstallerService();
+
+      expect(installer.identifyAssetType('tool-1.0-linux-x86_64.tar.zst'), InstallType.binary);
+      expect(installer.identifyAssetType('tool-1.0-linux-x86_64.tzst'), InstallType.binary);
+      expect(installer.identifyAssetType('tool-1.0.tar.zst'), InstallType.source);
+      expect(InstallerService.isArchive('tool.tar.zst'), isTrue);
+    });
+
+    test('describeAssetSelection ranks candidates and notes the choice', () {
+      final installer = InstallerService();
+      final deb = _asset('tool_1.0_amd64.deb');
+
+      final description = installer.describeAssetSelection(
+        assets: [_asset('tool_1.0_all.deb'), deb, _asset('tool-1.0.x86_64.rpm'), _asset('checksums.txt')],
+        chosen: deb,
+        reason: 'picked DEB in the package chooser',
+        available: {InstallType.deb},
+        hostArch: 'x86_64',
+      );
+
+      expect(description, contains('Chose tool_1.0_amd64.deb: picked DEB in the package chooser'));
+      expect(description, contains('1. tool_1.0_amd64.deb [deb] (chosen) score '));
+      expect(description, contains('2. tool_1.0_all.deb [deb] score '));
+      expect(description, contains('- tool-1.0.x86_64.rpm [rpm] (not installable here)'));
+      expect(description, contains('Ignored: checksums.txt'));
+    });
+
+    test('describeAssetSelection applies the pick\'s filters and says what they left out', () {
+      final installer = InstallerService();
+      final gtk = _asset('tool-gtk-1.0-x86_64.AppImage');
+
+      final description = installer.describeAssetSelection(
+        assets: [
+          _asset('tool-debug-1.0-x86_64.AppImage'),
+          _asset('tool-qt-1.0-x86_64.AppImage'),
+          _asset('tool-gtk-1.0-aarch64.AppImage'),
+          gtk,
+        ],
+        chosen: gtk,
+        reason: 'best match',
+        available: {InstallType.appImage},
+        hostArch: 'x86_64',
+        exclude: ['debug'],
+        pattern: '*-gtk-*',
+      );
+
+      expect(description, contains('1. tool-gtk-1.0-x86_64.AppImage [appImage] (chosen)'));
+      expect(description, isNot(contains('2.')));
+      expect(description, contains('- tool-debug-1.0-x86_64.AppImage [appImage] (filtered out by the asset keywords)'));
+      expect(description, contains('- tool-qt-1.0-x86_64.AppImage [appImage] (does not match the asset pattern)'));
+      expect(description, contains('- tool-gtk-1.0-aarch64.AppImage [appImage] (built for aarch64)'));
+    });
+
+    test('findBestAsset follows type priority among compatible assets', () {
+      final installer = InstallerService();
+      final assets = [
+        _asset('tool-1.0-x86_64.AppImage'),
+        _asset('tool_1.0_arm64.deb'),
+        _asset('tool_1.0_amd64.deb'),
+        _asset('tool-1.0-windows-x86_64.exe'),
+      ];
+
+      final best = installer.findBestAsset(
+        assets,
+        {InstallType.deb, InstallType.appImage},
+        hostArch: 'x86_64',
+      );
+
+      expect(best?.asset.name, 'tool_1.0_amd64.deb');
+      expect(best?.type, InstallType.deb);
+    });
+
+    test('findBestAsset takes the musl build when musl is preferred', () {
+      final installer = InstallerService();
+      final assets = [
+        _asset('foo-x86_64-unknown-linux-gnu.tar.gz'),
+        _asset('foo-x86_64-unknown-linux-musl.tar.gz'),
+      ];
+
+      final preferred = installer.findBestAsset(assets, {InstallType.binary}, hostArch: 'x86_64', preferMusl: true);
+      final plain = installer.findBestAsset(assets, {InstallType.binary}, hostArch: 'x86_64', preferMusl: false);
+
+      expect(preferred?.asset.name, 'foo-x86_64-unknown-linux-musl.tar.gz');
+      expect(preferred?.reason, contains('musl'));
+      expect(plain?.asset.name, 'foo-x86_64-unknown-linux-gnu.tar.gz');
+    });
+
+    test('isPortableBuild spots musl and static builds', () {
+      expect(InstallerService.isPortableBuild('foo-x86_64-unknown-linux-musl.tar.gz'), isTrue);
+      expect(InstallerService.isPortableBuild('foo-linux-amd64-static.tar.gz'), isTrue);
+      expect(InstallerService.isPortableBuild('foo-x86_64-unknown-linux-gnu.tar.gz'), isFalse);
+      expect(InstallerService.isPortableBuild('staticky-1.0-x86_64.tar.gz'), isFalse);
+    });
+
+    test('findBestAsset returns null when nothing fits the host', () {
+      final installer = InstallerService();
+      final assets = [
+        ReleaseAsset(
+          name: 'tool_1.0_arm64.deb',
+          browserDownloadUrl: 'https://example.com/tool_1.0_arm64.deb',
+          contentType: 'application/octet-stream',
+          size: 1,
+        ),
+      ];
+
+      expect(installer.findBestAsset(assets, {InstallType.deb}, hostArch: 'x86_64'), isNull);
+    });
+
+    test('parseVersionOutput extracts the version from typical output', () {
+      expect(InstallerService.parseVersionOutput('mytool 1.4.2 (abc123)'), '1.4.2');
+      expect(InstallerService.parseVersionOutput('mytool version v2.0.0-rc.1\n'), '2.0.0-rc.1');
+      expect(InstallerService.parseVersionOutput('no version here'), isNull);
+    });
+
+    test('findBestAsset applies include and exclude keywords together', () {
+      final installer = InstallerService();
+      final assets = [
+        _asset('tool-qt-debug_1.0_amd64.deb'),
+        _asset('tool-gtk_1.0_amd64.deb'),
+        _asset('tool-qt_1.0_amd64.deb'),
+      ];
+
+      final best = installer.findBestAsset(
+        assets,
+        {InstallType.deb},
+        hostArch: 'x86_64',
+        include: ['QT'],
+        exclude: ['debug'],
+      );
+
+      expect(best?.asset.name, 'tool-qt_1.0_amd64.deb');
+      expect(best?.reason, contains('keyword filters'));
+      expect(
+        installer.findBestAsset(assets, {InstallType.deb},
+            hostArch: 'x86_64', include: ['gtk'], exclude: ['gtk']),
+        isNull,
+      );
+    });
+
+    test('matchesKeywords ignores empty lists and blank keywords', () {
+      expect(InstallerService.matchesKeywords('tool.AppImage', include: [], exclude: ['']), isTrue);
+      expect(InstallerService.matchesKeywords('tool-symbols.tar.gz', exclude: ['Symbols']), isFalse);
+    });
+
+    test('matchesAssetPattern takes globs over the whole name and regexes between slashes', () {
+      expect(InstallerService.matchesAssetPattern('Tool-gtk-1.0-x86_64.AppImage', '*-gtk-*.AppImage'), isTrue);
+      expect(InstallerService.matchesAssetPattern('tool-gtk-1.0-x86_64.appimage', '*-GTK-*.AppImage'), isTrue);
+      expect(InstallerService.matchesAssetPattern('tool-gtk-1.0.AppImage.zsync', '*-gtk-*.AppImage'), isFalse);
+      expect(InstallerService.matchesAssetPattern('tool-qt-1.0.AppImage', '*-gtk-*.AppImage'), isFalse);
+      expect(InstallerService.matchesAssetPattern('tool-v2.deb', 'tool-v?.deb'), isTrue);
+      expect(InstallerService.matchesAssetPattern('tool-a.deb', 'tool-[!a].deb'), isFalse);
+      expect(InstallerService.matchesAssetPattern('tool-debug.deb', r'/^tool-(?!debug)/'), isFalse);
+      expect(InstallerService.matchesAssetPattern('tool.deb', r'/^tool-(?!debug)/'), isFalse);
+      expect(InstallerService.matchesAssetPattern('tool-gtk.deb', r'/^tool-(?!debug)/'), isTrue);
+      expect(InstallerService.matchesAssetPattern('anything', null), isTrue);
+      expect(InstallerService.matchesAssetPattern('anything', '  '), isTrue);
+    });
+
+    test('patternRegExp rejects patterns that do not compile', () {
+      expect(() => ReleaseAsset.patternRegExp('tool-[gtk'), throwsFormatException);
+      expect(() => ReleaseAsset.patternRegExp('/tool-(gtk/'), throwsFormatException);
+    });
+
+    test('matchesAssetPattern ignores a pattern that does not compile', () {
+      expect(InstallerService.matchesAssetPattern('tool.deb', 'tool-[gtk'), isTrue);
+      final best = InstallerService()
+          .findBestAsset([_asset('tool_1.0_amd64.deb')], {InstallType.deb}, hostArch: 'x86_64', pattern: '[abc');
+      expect(best?.asset.name, 'tool_1.0_amd64.deb');
+    });
+
+    test('findBestAsset only considers assets matching the pattern', () {
+      final installer = InstallerService();
+      final assets = [
+        _asset('tool-debug-1.0-x86_64.AppImage'),
+        _asset('tool-gtk-1.0-x86_64.AppImage'),
+      ];
+
+      final best = installer.findBestAsset(assets, {InstallType.appImage},
+          hostArch: 'x86_64', pattern: '*-gtk-*.AppImage');
+
+      expect(best?.asset.name, 'tool-gtk-1.0-x86_64.AppImage');
+      expect(best?.reason, contains('matching *-gtk-*.AppImage'));
+      expect(installer.findBestAsset(assets, {InstallType.appImage}, hostArch: 'x86_64', pattern: '*.deb'), isNull);
+    });
+
+    test('checkDownloadLength tolerates a missing Content-Length', () {
+      expect(InstallerService.checkDownloadLength(received: 10), isNull);
+      expect(InstallerService.checkDownloadLength(received: 10, expectedSize: 10), isNull);
+      expect(InstallerService.checkDownloadLength(received: 10, expectedSize: 0), isNull);
+      expect(InstallerService.checkDownloadLength(received: 8, expectedSize: 10), contains('10'));
+      expect(InstallerService.checkDownloadLength(received: 8, contentLength: 10), contains('8 of 10'));
+    });
+
+    test('zsync and torrent companions are never installable', () {
+      final installer = InstallerService();
+      final appImage = _asset('Tool-x86_64.AppImage');
+      final zsync = _asset('Tool-x86_64.AppImage.zsync');
+      final assets = [zsync, _asset('tool_1.0_amd64.deb.torrent'), appImage];
+
+      expect(installer.identifyAssetType(zsync.name), isNull);
+      expect(installer.identifyAssetType('tool_1.0_amd64.deb.torrent'), isNull);
+      expect(
+        installer.findBestAsset(assets, {InstallType.deb, InstallType.appImage}, hostArch: 'x86_64')?.asset,
+        appImage,
+      );
+      expect(InstallerService.zsyncFor(assets, appImage), zsync);
+    });
+
+    test('shadowingBinary reports an earlier copy on PATH', () {
+      const installed = '/home/me/.local/bin/tool';
+
+      expect(InstallerService.shadowingBinary('/usr/bin/tool\n$installed\n', installed), '/usr/bin/tool');
+      expect(InstallerService.shadowingBinary('$installed\n/usr/bin/tool\n', installed), isNull);
+      expect(InstallerService.shadowingBinary('', installed), isNull);
+    });
+
+    test('assetSignature recognises the same asset in a later release', () {
+      final signature = InstallerService.assetSignature('tool-qt_1.2.0_amd64.deb', 'v1.2.0');
+      final next = [_asset('tool-gtk_1.3.0_amd64.deb'), _asset('tool-qt_1.3.0_amd64.deb')];
+
+      expect(signature, 'tool-qt_{version}_amd64.deb');
+      expect(InstallerService.findBySignature(next, signature, 'v1.3.0')?.name, 'tool-qt_1.3.0_amd64.deb');
+      expect(InstallerService.findBySignature([_asset('tool-qt_1.3.0_arm64.deb')], signature, 'v1.3.0'), isNull);
+    });
+
+    test('multi-part assets are installed through their first piece', () {
+      final installer = InstallerService();
+      final assets = [
+        _asset('Tool-x86_64.AppImage.part2'),
+        _asset('Tool-x86_64.AppImage.part1'),
+        _asset('Tool-x86_64.AppImage.part3'),
+        _asset('tool.tar.gz.001'),
+        _asset('tool.tar.gz.003'),
+      ];
+
+      expect(InstallerService.splitPart('tool.tar.gz.002'), (joinedName: 'tool.tar.gz', part: 2));
+      expect(InstallerService.splitPart('tool-1.2.3.deb'), isNull);
+      expect(installer.identifyAssetType('Tool-x86_64.AppImage.part1'), InstallType.appImage);
+      expect(installer.identifyAssetType('Tool-x86_64.AppImage.part2'), isNull);
+      expect(installer.findBestAsset(assets, {InstallType.appImage}, hostArch: 'x86_64')?.asset.name,
+          'Tool-x86_64.AppImage.part1');
+      expect(InstallerService.partsOf(assets, assets[1]).map((a) => a.name), [
+        'Tool-x86_64.AppImage.part1',
+        'Tool-x86_64.AppImage.part2',
+        'Tool-x86_64.AppImage.part3',
+      ]);
+      expect(() => InstallerService.partsOf(assets, assets[3]), throwsException);
+    });
+
+    test('elfArchitecture reads the machine type from the ELF header', () {
+      List<int> header(int machine) => [
+            0x7F, 0x45, 0x4C, 0x46, 2, 1, 1, 0, //
+            0, 0, 0, 0, 0, 0, 0, 0, //
+            2, 0, machine, 0,
+          ];
+
+      expect(InstallerService.elfArchitecture(header(0x3E)), 'x86_64');
+      expect(InstallerService.elfArchitecture(header(0xB7)), 'aarch64');
+      expect(InstallerService.elfArchitecture(header(0x16)), 's390x');
+      expect(InstallerService.elfArchitecture(header(0x15)), 'ppc64le');
+      expect(InstallerService.elfArchitecture(header(0x99)), isNull);
+      expect(InstallerService.elfArchitecture('#!/bin/sh\necho not an elf\n'.codeUnits), isNull);
+      expect(InstallerService.elfArchitecture([0x7F, 0x45]), isNull);
+    });
+
+    test('binaryNameOwner finds another app already installed under a name', () {
+      TrackedApp app(int id, String owner, {String? launchCommand}) => TrackedApp(
+            id: id,
+            repoOwner: owner,
+            repoName: 'server',
+            displayName: '$owner server',
+            installType: launchCommand == null ? null : InstallType.binary,
+            launchCommand: launchCommand,
+            createdAt: DateTime.utc(2024),
+          );
+      final acme = app(1, 'acme', launchCommand: '/home/me/.local/bin/server');
+      final other = app(2, 'other');
+      final apps = [acme, other];
+
+      expect(InstallerService.binaryNameOwner(apps, other, 'server'), acme);
+      expect(InstallerService.binaryNameOwner(apps, acme, 'server'), isNull);
+      expect(InstallerService.binaryNameOwner(apps, other, 'other-server'), isNull);
+      expect(InstallerService.disambiguatedBinaryName(other), 'other-server');
+    });
+
+    test('finds the checksum listed for an asset', () {
+      final hash = 'ab' * 32;
+      final other = 'cd' * 32;
+      final sums = '$other  tool-arm64.tar.gz\n$hash *dist/tool-x86_64.tar.gz\n';
+
+      expect(InstallerService.expectedSha256(sums, 'tool-x86_64.tar.gz'), hash);
+      expect(InstallerService.expectedSha256(sums, 'tool.deb'), isNull);
+      expect(InstallerService.expectedSha256('${hash.toUpperCase()}\n', 'tool.deb'), hash);
+    });
+
+    test('checksumAssetFor prefers a dedicated .sha256 file', () {
+      final assets = [_asset('tool.deb'), _asset('SHA256SUMS'), _asset('tool.deb.sha256')];
+
+      expect(InstallerService.checksumAssetFor(assets, 'tool.deb')?.name, 'tool.deb.sha256');
+      expect(InstallerService.checksumAssetFor(assets, 'tool.rpm')?.name, 'SHA256SUMS');
+      expect(InstallerService.checksumAssetFor([_asset('tool.deb')], 'tool.deb'), isNull);
+    });
+
+    test('checksumAssetFor takes <asset>.sha256sum as the dedicated file', () {
+      final assets = [_asset('checksums.txt'), _asset('tool.deb'), _asset('tool.deb.sha256sum')];
+
+      expect(InstallerService.checksumAssetFor(assets, 'tool.deb')?.name, 'tool.deb.sha256sum');
+    });
+
+    test('checksumAssetFor never takes another asset\'s checksum file', () {
+      final assets = [_asset('tool.deb'), _asset('tool.deb.sha256sum'), _asset('tool.rpm')];
+
+      expect(InstallerService.checksumAssetFor(assets, 'tool.rpm'), isNull);
+      expect(InstallerService.checksumAssetFor([...assets, _asset('SHA256SUMS')], 'tool.rpm')?.name, 'SHA256SUMS');
+    });
+
+    test('checksumAssetFor skips signatures of the checksum list', () {
+      for (final signature in ['checksums.txt.sig', 'checksums.txt.pem', 'SHA256SUMS.gpg', 'SHA256SUMS.asc',
+          'checksums.txt.minisig']) {
+        expect(InstallerService.checksumAssetFor([_asset(signature), _asset('tool.deb')], 'tool.deb'), isNull,
+            reason: signature);
+      }
+      final assets = [_asset('SHA256SUMS.gpg'), _asset('SHA256SUMS'), _asset('tool.deb')];
+      expect(InstallerService.checksumAssetFor(assets, 'tool.deb')?.name, 'SHA256SUMS');
+    });
+
+    test('rangeTotal reads the full size from Content-Range', () {
+      expect(InstallerService.rangeTotal('bytes 100-199/200'), 200);
+      expect(InstallerService.rangeTotal('bytes 100-199/*'), isNull);
+      expect(InstallerService.rangeTotal(null), isNull);
+    });
+
+    test('downloadFile resumes a dropped download of the same file', () async {
+      final dir = await Directory.systemTemp.createTemp('autonomix-test-');
+      addTearDown(() => dir.delete(recursive: true));
+      const url = 'https://example.com/v2/tool-linux-x86_64.tar.gz';
+      final requests = <http.BaseRequest>[];
+      final installer = InstallerService(
+        downloadsDirectory: dir,
+        client: MockClient.streaming((request, _) async {
+          requests.add(request);
+          if (request.headers['Range'] == null) {
+            return http.StreamedResponse(_cutOff('hello '), 200, contentLength: 11, headers: {'etag': '"v2"'});
+          }
+          return http.StreamedResponse(Stream.value(utf8.encode('world')), 206,
+              headers: {'content-range': 'bytes 6-10/11'});
+        }),
+      );
+
+      await expectLater(installer.downloadFile(url, 'tool.tar.gz', retries: 0), throwsA(isA<http.ClientException>()));
+      final file = await installer.downloadFile(url, 'tool.tar.gz', retries: 0);
+
+      expect(await file.readAsString(), 'hello world');
+      expect(requests.last.headers['Range'], 'bytes=6-');
+      expect(requests.last.headers['If-Range'], '"v2"');
+    });
+
+    test('downloadFile does not resume a partial file from another release', () async {
+      final dir = await Directory.systemTemp.createTemp('autonomix-test-');
+      addTearDown(() => dir.delete(recursive: true));
+      final requests = <http.BaseRequest>[];
+      final installer = InstallerService(
+        downloadsDirectory: dir,
+        client: MockClient.streaming((request, _) async {
+          requests.add(request);
+          if (request.url.path.startsWith('/v1/')) {
+            return http.StreamedResponse(_cutOff('old '), 200, contentLength: 8, headers: {'etag': '"v1"'});
+          }
+          if (request.headers['Range'] != null) {
+            return http.StreamedResponse(Stream.value(utf8.encode('tail')), 206,
+                headers: {'content-range': 'bytes 4-7/8'});
+          }
+          return http.StreamedResponse(Stream.value(utf8.encode('new file')), 200, contentLength: 8);
+        }),
+      );
+
+      await expectLater(
+        installer.downloadFile('https://example.com/v1/tool.tar.gz', 'tool.tar.gz', retries: 0),
+        throwsA(isA<http.ClientException>()),
+      );
+      final file = await installer.downloadFile('https://example.com/v2/tool.tar.gz', 'tool.tar.gz', retries: 0);
+
+      expect(await file.readAsString(), 'new file');
+      expect(requests.last.headers['Range'], isNull);
+    });
+
+    test('downloadFile starts over when the file changed on the server', () async {
+      final dir = await Directory.systemTemp.createTemp('autonomix-test-');
+      addTearDown(() => dir.delete(recursive: true));
+      const url = 'https://example.com/latest/tool.tar.gz';
+      var calls = 0;
+      final installer = InstallerService(
+        downloadsDirectory: dir,
+        client: MockClient.streaming((request, _) async {
+          if (++calls == 1) {
+            return http.StreamedResponse(_cutOff('old '), 200, contentLength: 8, headers: {'etag': '"a"'});
+          }
+          // If-Range no longer matches, so the whole new file comes back
+          if (request.headers['If-Range'] == '"b"') {
+            return http.StreamedResponse(Stream.value(utf8.encode('tail')), 206,
+                headers: {'content-range': 'bytes 4-7/8'});
+          }
+          return http.StreamedResponse(Stream.value(utf8.encode('new file')), 200,
+              contentLength: 8, headers: {'etag': '"b"'});
+        }),
+      );
+
+      await expectLater(installer.downloadFile(url, 'tool.tar.gz', retries: 0), throwsA(isA<http.ClientException>()));
+      final file = await installer.downloadFile(url, 'tool.tar.gz', retries: 0);
+
+      expect(await file.readAsString(), 'new file');
+    });
+
+    test('resumeValidator prefers a strong ETag', () {
+      expect(InstallerService.resumeValidator({'etag': '"x"', 'last-modified': 'Mon'}), '"x"');
+      expect(InstallerService.resumeValidator({'etag': 'W/"x"', 'last-modified': 'Mon'}), 'Mon');
+      expect(InstallerService.resumeValidator({}), isNull);
+    });
+
+    test('BuildSystem.detect prefers configure over its Makefile', () {
+      expect(BuildSystem.detect(['configure', 'Makefile', 'README']), BuildSystem.autotools);
+      expect(BuildSystem.detect(['meson.build', 'src']), BuildSystem.meson);
+      expect(BuildSystem.detect(['Cargo.toml', 'Cargo.lock']), BuildSystem.cargo);
+      expect(BuildSystem.detect(['GNUmakefile']), BuildSystem.make);
+      expect(BuildSystem.detect(['setup.py']), isNull);
+    });
+
+    test('source builds install under the given prefix', () {
+      expect(BuildSystem.cargo.commands('/home/u/.local').single,
+          ['cargo', 'install', '--path', '.', '--root', '/home/u/.local']);
+      expect(BuildSystem.autotools.commands('/home/u/.local').first, ['./configure', '--prefix=/home/u/.local']);
+    });
+
+    test('installArchiveBinaries installs the programs from a tarball', () async {
+      final work = await Directory.systemTemp.createTemp('autonomix-test-');
+      addTearDown(() => work.delete(recursive: true));
+      final tree = await Directory('${work.path}/tool-1.0').create();
+      await File('${tree.path}/tool').writeAsString('#!/bin/sh\necho tool\n');
+      await Process.run('chmod', ['755', '${tree.path}/tool']);
+      await File('${tree.path}/README.md').writeAsString('docs');
+      final archive = File('${work.path}/tool-linux-x86_64.tar.gz');
+      await Process.run('tar', ['-czf', archive.path, '-C', work.path, 'tool-1.0']);
+      final binDir = Directory('${work.path}/bin');
+
+      final result = await InstallerService().installArchiveBinaries(archive, binDir: binDir);
+
+      expect(result.installedFiles, ['${binDir.path}/tool']);
+      expect(result.launchCommand, '${binDir.path}/tool');
+      expect(File('${binDir.path}/tool').existsSync(), isTrue);
+      expect(File('${binDir.path}/README.md').existsSync(), isFalse);
+    });
+
+    test('installArchiveBinaries leaves other apps\' binaries and unknown files alone', () async {
+      final work = await Directory.systemTemp.createTemp('autonomix-test-');
+      addTearDown(() => work.delete(recursive: true));
+      await Directory('${work.path}/tool-1.0/extra').create(recursive: true);
+      for (final name in ['tool-1.0/tool', 'tool-1.0/install.sh', 'tool-1.0/helper', 'tool-1.0/extra/helper']) {
+        await File('${work.path}/$name').writeAsString('#!/bin/sh\n');
+        await Process.run('chmod', ['755', '${work.path}/$name']);
+      }
+      final archive = File('${work.path}/tool-linux-x86_64.tar.gz');
+      await Process.run('tar', ['-czf', archive.path, '-C', work.path, 'tool-1.0']);
+      final binDir = await Directory('${work.path}/bin').create();
+      await File('${binDir.path}/tool').writeAsString('other');
+      await File('${binDir.path}/install.sh').writeAsString('mine');
+      final other = TrackedApp(
+        id: 2,
+        repoOwner: 'someone',
+        repoName: 'tool',
+        displayName: 'Other Tool',
+        installType: InstallType.binary,
+        launchCommand: '${binDir.path}/tool',
+        createdAt: DateTime.now(),
+      );
+      final app =
+          TrackedApp(id: 1, repoOwner: 'Acme', repoName: 'tool', displayName: 'Tool', createdAt: DateTime.now());
+      final warnings = <String>[];
+
+      final result = await InstallerService().installArchiveBinaries(
+        archive,
+        binDir: binDir,
+        app: app,
+        tracked: [app, other],
+        onWarning: warnings.add,
+      );
+
+      expect(File('${binDir.path}/tool').readAsStringSync(), 'other');
+      expect(File('${binDir.path}/install.sh').readAsStringSync(), 'mine');
+      expect(result.installedFiles, unorderedEquals([
+        '${binDir.path}/acme-tool',
+        '${binDir.path}/acme-install.sh',
+        '${binDir.path}/helper',
+      ]));
+      expect(result.launchCommand, '${binDir.path}/acme-tool');
+      expect(warnings, contains('"tool" already belongs to Other Tool, so it was installed as "acme-tool" instead.'));
+      expect(warnings, hasLength(2));
+      expect(result.failures.keys, ['tool-1.0/helper']);
+    });
+
+    test('installArchiveBinaries keeps zip programs clear of other apps\' binaries', () async {
+      final work = await Directory.systemTemp.createTemp('autonomix-test-');
+      addTearDown(() => work.delete(recursive: true));
+      await File('${work.path}/tool').writeAsString('#!/bin/sh\n');
+      await Process.run('chmod', ['755', '${work.path}/tool']);
+      final archive = File('${work.path}/tool_1.0_linux_amd64.zip');
+      await Process.run('zip', ['-q', archive.path, 'tool'], workingDirectory: work.path);
+      final binDir = await Directory('${work.path}/bin').create();
+      await File('${binDir.path}/tool').writeAsString('other');
+      final other = TrackedApp(
+        id: 2,
+        repoOwner: 'someone',
+        repoName: 'tool',
+        displayName: 'Other Tool',
+        installType: InstallType.binary,
+        installedFiles: ['${binDir.path}/tool'],
+        createdAt: DateTime.now(),
+      );
+      final app =
+          TrackedApp(id: 1, repoOwner: 'acme', repoName: 'tool', displayName: 'Tool', createdAt: DateTime.now());
+
+      final result =
+          await InstallerService().installArchiveBinaries(archive, binDir: binDir, app: app, tracked: [other]);
+
+      expect(File('${binDir.path}/tool').readAsStringSync(), 'other');
+      expect(result.installedFiles, ['${binDir.path}/acme-tool']);
+    }, skip: Process.runSync('which', ['zip']).exitCode != 0);
+
+    test('isPrebuiltArchive tells binaries from source', () {
+      expect(InstallerService.isPrebuiltArchive('ripgrep-14.1.0-x86_64-unknown-linux-musl.tar.gz'), isTrue);
+      expect(InstallerService.isPrebuiltArchive('tool_linux.tar.xz'), isTrue);
+      expect(InstallerService.isPrebuiltArchive('tool-2.3.1.tar.gz'), isFalse);
+    });
+
+    test('zips are binaries, unless built for another OS', () {
+      final installer = InstallerService();
+
+      expect(installer.identifyAssetType('tool_1.2.0_linux_amd64.zip'), InstallType.binary);
+      expect(InstallerService.isLinuxAsset('tool_1.2.0_linux_amd64.zip'), isTrue);
+      expect(InstallerService.isLinuxAsset('tool_1.2.0_windows_amd64.zip'), isFalse);
+      expect(InstallerService.isLinuxAsset('tool-win-x64.zip'), isFalse);
+      expect(InstallerService.isLinuxAsset('darwin-tools-linux.zip'), isFalse);
+    });
+
+    test('Arch packages are not mistaken for tarballs', () {
+      final installer = InstallerService();
+
+      expect(installer.identifyAssetType('tool-1.0-1-x86_64.pkg.tar.zst'), InstallType.pacman);
+      expect(installer.identifyAssetType('tool-1.0-1-x86_64.pkg.tar.xz'), InstallType.pacman);
+      expect(InstallType.fromString(InstallType.pacman.name), InstallType.pacman);
+    });
+
+    test('describeUninstall says what will be removed', () {
+      TrackedApp app(InstallType type, {String? package, String? launch, List<String>? files}) => TrackedApp(
+            repoOwner: 'owner',
+            repoName: 'tool',
+            displayName: 'Tool',
+            installedVersion: 'v1.0.0',
+            installType: type,
+            packageName: package,
+            launchCommand: launch,
+            installedFiles: files,
+            createdAt: DateTime(2024),
+          );
+
+      expect(InstallerService.describeUninstall(app(InstallType.deb, package: 'tool')),
+          'Removes the package tool with dpkg, as administrator.');
+      expect(
+          InstallerService.describeUninstall(
+              app(InstallType.binary, launch: '/home/me/.local/bin/tool', files: ['/a/tool', '/a/toolctl'])),
+          'Deletes /a/tool, /a/toolctl.');
+      expect(InstallerService.describeUninstall(app(InstallType.flatpak)), contains('package manager'));
+      expect(InstallerService.describeUninstall(app(InstallType.rpm)),
+          'Removes the package tool (assumed from the repo name) with rpm, as administrator.');
+    });
+
+    test('privilegedFailure tells a dismissed password prompt from a failed command', () {
+      expect(InstallerService.privilegedFailure('pkexec', 'dpkg', 126, '').kind, ErrorKind.authCancelled);
+      expect(InstallerService.privilegedFailure('pkexec', 'dpkg', 127, '').kind, ErrorKind.authCancelled);
+      // Only pkexec gives these codes that meaning
+      expect(InstallerService.privilegedFailure('sudo', 'dpkg', 127, '').kind, ErrorKind.installFailed);
+
+      final failed = InstallerService.privilegedFailure('pkexec', 'dpkg', 1, 'dependency problems\n');
+      expect(failed.kind, ErrorKind.installFailed);
+      expect(failed.message, 'dpkg failed with exit code 1: dependency problems');
+    });
+
+    test('chooseEscalator prefers pkexec and falls back to sudo', () {
+      expect(InstallerService.chooseEscalator(hasPkexec: true, hasSudo: true, environment: {}), ['pkexec']);
+      expect(
+          InstallerService.chooseEscalator(
+              hasPkexec: false, hasSudo: true, environment: {'SUDO_ASKPASS': '/usr/bin/ssh-askpass'}),
+          ['sudo', '-A']);
+      expect(InstallerService.chooseEscalator(hasPkexec: false, hasSudo: true, environment: {}), ['sudo', '-n']);
+      expect(InstallerService.chooseEscalator(hasPkexec: false, hasSudo: false, environment: {}), isNull);
+    });
+
+    test('parseDfAvailable reads the available column in bytes', () {
+      const output = 'Filesystem     1024-blocks      Used Available Capacity Mounted on\n'
+          '/dev/nvme0n1p2   488245288 401234567  62113344      87% /home\n';
+
+      expect(InstallerService.parseDfAvailable(output), 62113344 * 1024);
+      expect(InstallerService.parseDfAvailable('df: /nowhere: No such file or directory'), isNull);
+    });
+
+    test('spaceShortage names what is needed and what is left', () {
+      expect(InstallerService.spaceShortage(needed: 1024, available: 2048), isNull);
+      expect(InstallerService.spaceShortage(needed: 3 * 1024 * 1024, available: 512 * 1024),
+          'Not enough disk space (need 3.0 MB, have 512.0 KB)');
+    });
+
+    test('signatureAssetFor picks the signature the key can check', () {
+      final assets = [_asset('tool.AppImage'), _asset('tool.AppImage.minisig'), _asset('tool.AppImage.asc')];
+      const pgpKey = '-----BEGIN PGP PUBLIC KEY BLOCK-----\n...\n-----END PGP PUBLIC KEY BLOCK-----';
+
+      expect(InstallerService.signatureAssetFor(assets, 'tool.AppImage', 'RWQ...')?.name, 'tool.AppImage.minisig');
+      expect(InstallerService.signatureAssetFor(assets, 'tool.AppImage', pgpKey)?.name, 'tool.AppImage.asc');
+      expect(InstallerService.signatureAssetFor(assets, 'other.AppImage', pgpKey), isNull);
+    });
+
+    test('minisignKeyOf drops the comment line of a .pub file', () {
+      expect(InstallerService.minisignKeyOf('untrusted comment: minisign public key 1234\nRWQabc\n'), 'RWQabc');
+    });
+
+    test('matchesArchitecture rejects other architectures on ARM and RISC-V hosts', () {
+      expect(InstallerService.matchesArchitecture('tool-x86_64.AppImage', 'armv7'), isFalse);
+      expect(InstallerService.matchesArchitecture('tool-linux-arm64.tar.gz', 'armv7'), isFalse);
+      expect(InstallerService.matchesArchitecture('tool_1.0_armhf.deb', 'armv7'), isTrue);
+      expect(InstallerService.matchesArchitecture('tool_1.0_armhf.deb', 'x86_64'), isFalse);
+      expect(InstallerService.matchesArchitecture('tool-linux-amd64.tar.gz', 'riscv64'), isFalse);
+      expect(InstallerService.matchesArchitecture('tool-linux-riscv64.tar.gz', 'riscv64'), isTrue);
+      expect(InstallerService.matchesArchitecture('tool.AppImage', 'riscv64'), isTrue);
+    });
+
+    test('findBestAsset weighs the host architecture and distribution', () {
+      final installer = InstallerService();
+      final assets = [
+        _asset('tool-1.0.AppImage'),
+        _asset('tool-1.0-x86_64.AppImage'),
+        _asset('tool_1.0_debian12_amd64.deb'),
+        _asset('tool_1.0_ubuntu22.04_amd64.deb'),
+      ];
+      final available = {InstallType.deb, InstallType.appImage};
+
+      expect(
+          installer.findBestAsset(assets, available, hostArch: 'x86_64', distros: ['ubuntu', 'debian'])?.asset.name,
+          'tool_1.0_ubuntu22.04_amd64.deb');
+      expect(installer.findBestAsset(assets, available, hostArch: 'x86_64', distros: ['debian'])?.asset.name,
+          'tool_1.0_debian12_amd64.deb');
+      final appImage = installer.findBestAsset(assets, {InstallType.appImage}, hostArch: 'x86_64', distros: []);
+      expect(appImage?.asset.name, 'tool-1.0-x86_64.AppImage');
+      expect(appImage?.reason, contains('built for x86_64'));
+    });
+
+    test('rankAssets orders by score and keeps release order on ties', () {
+      final installer = InstallerService();
+
+      final ranked = installer.rankAssets(
+        [_asset('tool-linux.tar.gz'), _asset('tool-b.AppImage'), _asset('tool-a.AppImage')],
+        {InstallType.binary, InstallType.appImage},
+        hostArch: 'x86_64',
+        distros: [],
+      );
+
+      expect(ranked.map((r) => r.asset.name), ['tool-b.AppImage', 'tool-a.AppImage', 'tool-linux.tar.gz']);
+      expect(ranked.first.score, greaterThan(ranked.last.score));
+    });
+
+    test('parseOsRelease reads ID and ID_LIKE', () {
+      expect(InstallerService.parseOsRelease('NAME="Ubuntu"\nID=ubuntu\nID_LIKE=debian\n'), ['ubuntu', 'debian']);
+      expect(InstallerService.parseOsRelease('ID="opensuse-leap"\nID_LIKE="suse opensuse"\n'),
+          ['opensuse-leap', 'opensuse', 'suse']);
+      expect(InstallerService.distroOf('tool-1.0-1.el8.x86_64.rpm'), 'rhel');
+      expect(InstallerService.distroOf('tool_1.0_amd64.deb'), isNull);
+    });
+  });
+}
@@ -0,0 +1,39 @@
+import 'package:flutter_test/flutter_test.dart';
+import 'package:autonomix/models/tracked_app.dart';
+import 'package:autonomix/services/notification_service.dart';
+
+TrackedApp _app(String name, String latest) {
+  return TrackedApp(
+    repoOwner: 'owner',
+    repoName: name.toLowerCase(),
+    displayName: name,
+    installedVersion: 'v1.0.0',
+    latestVersion: latest,
+    createdAt: DateTime.now(),
+  );
+}
+
+void main() {
+  group('NotificationService', () {
+    test('describeUpdates lists each app with its new version', () {
+      final message = NotificationService.describeUpdates([_app('Foo', 'v1.1.0'), _app('Bar', 'v2.0.0')]);
+
+      expect(message?.summary, '2 updates available');
+      expect(message?.body, 'Foo v1.1.0\nBar v2.0.0');
+      expect(NotificationService.describeUpdates([_app('Foo', 'v1.1.0')])?.summary, 'Update available');
+      expect(NotificationService.describeUpdates([]), isNull);
+    });
+
+    test('notifications are on unless disabled in settings', () {
+      expect(NotificationService.enabledIn({}), isTrue);
+      expect(NotificationService.enabledIn({NotificationService.settingKey: false}), isFalse);
+    });
+
+    test('sessionBusAddress fills in the bus cron leaves out', () {
+      expect(NotificationService.sessionBusAddress({}, '1000'), 'unix:path=/run/user/1000/bus');
+      expect(NotificationService.sessionBusAddress({'XDG_RUNTIME_DIR': '/tmp/rt'}, '1000'), 'unix:path=/tmp/rt/bus');
+      expect(NotificationService.sessionBusAddress({'DBUS_SESSION_BUS_ADDRESS': 'unix:path=/x'}, '1000'), isNull);
+      expect(NotificationService.sessionBusAddress({}, null), isNull);
+    });
+  });
+}
@@ -0,0 +1,99 @@
+import 'package:flutter_test/flutter_test.dart';
+import 'package:autonomix/models/release.dart';
+import 'package:autonomix/models/release_channel.dart';
+import 'package:autonomix/models/tracked_app.dart';
+import 'package:autonomix/services/release_selector.dart';
+
+Release _release(String tag, {bool prerelease = false, bool draft = false}) {
+  return Release(
+    tagName: tag,
+    prerelease: prerelease,
+    draft: draft,
+    assets: [],
+  );
+}
+
+TrackedApp _app({String? tagPrefix, ReleaseChannel? channel}) {
+  return TrackedApp(
+    repoOwner: 'owner',
+    repoName: 'repo',
+    displayName: 'App',
+    tagPrefix: tagPrefix,
+    channel: channel,
+    createdAt: DateTime.now(),
+  );
+}
+
+void main() {
+  group('ReleaseSelector', () {
+    final releases = [
+      _release('v3.0.0'),
+      _release('lts-2.1.0'),
+      _release('lts-2.0.0'),
+    ];
+
+    test('selectLatest picks the newest release without a prefix', () {
+      expect(ReleaseSelector.selectLatest(releases, _app())?.tagName, 'v3.0.0');
+    });
+
+    test('selectLatest only considers tags with the configured prefix', () {
+      final app = _app(tagPrefix: 'lts-');
+      expect(ReleaseSelector.selectLatest(releases, app)?.tagName, 'lts-2.1.0');
+    });
+
+    test('selectLatest returns null when nothing matches the prefix', () {
+      expect(ReleaseSelector.selectLatest(releases, _app(tagPrefix: 'beta-')), isNull);
+    });
+
+    test('drafts are skipped unless explicitly allowed', () {
+      final withDraft = [_release('v4.0.0', draft: true), ...releases];
+
+      expect(ReleaseSelector.selectLatest(withDraft, _app())?.tagName, 'v3.0.0');
+      expect(ReleaseSelector.selectable(withDraft).map((r) => r.tagName),
+          isNot(contains('v4.0.0')));
+
+      ReleaseSelector.allowDrafts = true;
+      addTearDown(() => ReleaseSelector.allowDrafts = false);
+      expect(ReleaseSelector.selectLatest(withDraft, _app())?.tagName, 'v4.0.0');
+    });
+
+    test('the app\'s channel overrides the global default', () {
+      final withBeta = [_release('v4.0.0-beta.1', prerelease: true), ...releases];
+      addTearDown(() => ReleaseSelector.defaultChannel = ReleaseChannel.stable);
+
+      expect(ReleaseSelector.selectLatest(withBeta, _app())?.tagName, 'v3.0.0');
+      expect(ReleaseSelector.selectLatest(withBeta, _app(channel: ReleaseChannel.prerelease))?.tagName,
+          'v4.0.0-beta.1');
+
+      ReleaseSelector.defaultChannel = ReleaseChannel.prerelease;
+      expect(ReleaseSelector.selectLatest(withBeta, _app())?.tagName, 'v4.0.0-beta.1');
+      expect(ReleaseSelector.selectLatest(withBeta, _app(channel: ReleaseChannel.stable))?.tagName, 'v3.0.0');
+      expect(ReleaseSelector.needsReleaseList(_app()), isTrue);
+    });
+
+    test('channelIn defaults to stable', () {
+      expect(ReleaseSelector.channelIn({}), ReleaseChannel.stable);
+      expect(ReleaseSelector.channelIn({'release_channel': 'prerelease'}), ReleaseChannel.prerelease);
+      expect(ReleaseSelector.channelIn({'release_channel': 42}), ReleaseChannel.stable);
+    });
+
+    test('olderThan lists accepted releases after the latest, newest first', () {
+      final list = [
+        _release('v3.1.0-rc1', prerelease: true),
+        _release('v3.0.0'),
+        _release('v2.9.0', draft: true),
+        _release('v2.8.0'),
+        _release('v2.7.0'),
+      ];
+
+      expect(ReleaseSelector.olderThan(list, list[1], _app()).map((r) => r.tagName), ['v2.8.0', 'v2.7.0']);
+      expect(ReleaseSelector.olderThan(list, list.last, _app()), isEmpty);
+    });
+
+    test('olderThan honours the tag prefix', () {
+      final older = ReleaseSelector.olderThan(releases, releases[1], _app(tagPrefix: 'lts-'));
+
+      expect(older.map((r) => r.tagName), ['lts-2.0.0']);
+    });
+  });
+}
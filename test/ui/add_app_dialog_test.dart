@@ -0,0 +1,38 @@
+import 'package:flutter_test/flutter_test.dart';
+import 'package:autonomix/models/release.dart';
+import 'package:autonomix/ui/widgets/add_app_dialog.dart';
+
+ReleaseAsset _asset(String name) {
+  return ReleaseAsset(
+    name: name,
+    browserDownloadUrl: 'https://example.com/$name',
+    contentType: 'application/octet-stream',
+    size: 1,
+  );
+}
+
+void main() {
+  group('AddAppDialog.suggestDisplayName', () {
+    test('keeps the existing behaviour for descriptive repo names', () {
+      expect(
+        AddAppDialog.suggestDisplayName(owner: 'acme', repo: 'rocket', description: 'Rocket launcher'),
+        'Rocket launcher',
+      );
+    });
+
+    test('derives the name from the primary asset for generic repos', () {
+      expect(
+        AddAppDialog.suggestDisplayName(
+          owner: 'acme',
+          repo: 'releases',
+          assets: [_asset('MyTool-1.2.0-x86_64.AppImage')],
+        ),
+        'MyTool',
+      );
+    });
+
+    test('falls back to the owner when no asset name helps', () {
+      expect(AddAppDialog.suggestDisplayName(owner: 'acme', repo: 'dist'), 'acme');
+    });
+  });
+}
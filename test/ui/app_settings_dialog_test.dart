@@ -0,0 +1,57 @@
+import 'package:flutter_test/flutter_test.dart';
+import 'package:autonomix/ui/widgets/app_settings_dialog.dart';
+
+void main() {
+  group('AppSettingsDialog environment lines', () {
+    test('parses NAME=value lines and keeps "=" in values', () {
+      final env = AppSettingsDialog.parseEnvLines('APPIMAGE_EXTRACT_AND_RUN=1\n\nOPTS=--a=b\n');
+
+      expect(env, {'APPIMAGE_EXTRACT_AND_RUN': '1', 'OPTS': '--a=b'});
+    });
+
+    test('rejects lines without a valid variable name', () {
+      expect(() => AppSettingsDialog.parseEnvLines('=1'), throwsFormatException);
+      expect(() => AppSettingsDialog.parseEnvLines('MY VAR=1'), throwsFormatException);
+      expect(() => AppSettingsDialog.parseEnvLines('NOVALUE'), throwsFormatException);
+    });
+
+    test('round-trips through the text format', () {
+      const env = {'A': '1', 'LD_LIBRARY_PATH': '/opt/lib'};
+
+      expect(AppSettingsDialog.parseEnvLines(AppSettingsDialog.formatEnvLines(env)), env);
+      expect(AppSettingsDialog.formatEnvLines(null), '');
+    });
+  });
+}
+
+  group('AppSettingsDialog signing key', () {
+    test('accepts minisign keys, with or without their comment line, and OpenPGP keys', () {
+      const minisign = 'RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3';
+
+      expect(AppSettingsDialog.signingKeyProblem(''), isNull);
+      expect(AppSettingsDialog.signingKeyProblem(minisign), isNull);
+      expect(AppSettingsDialog.signingKeyProblem('untrusted comment: minisign public key\n$minisign\n'), isNull);
+      expect(
+          AppSettingsDialog.signingKeyProblem('-----BEGIN PGP PUBLIC KEY BLOCK-----\n\nmQINBF...\n'
+              '-----END PGP PUBLIC KEY BLOCK-----'),
+          isNull);
+    });
+
+    test('rejects anything else', () {
+      expect(AppSettingsDialog.signingKeyProblem('not a key'), isNotNull);
+    });
+  });
+
+  group('AppSettingsDialog asset pattern', () {
+    test('accepts globs, regexes and nothing', () {
+      expect(AppSettingsDialog.assetPatternProblem(''), isNull);
+      expect(AppSettingsDialog.assetPatternProblem('*-gtk-*.AppImage'), isNull);
+      expect(AppSettingsDialog.assetPatternProblem(r'/gtk.*\.AppImage$/'), isNull);
+    });
+
+    test('flags patterns that do not compile', () {
+      expect(AppSettingsDialog.assetPatternProblem('tool-[gtk'), startsWith('Invalid pattern'));
+      expect(AppSettingsDialog.assetPatternProblem('/(gtk/'), startsWith('Invalid pattern'));
+    });
+  });
+}
@@ -3,8 +3,15 @@ import 'package:flutter_test/flutter_test.dart';
 import 'package:provider/provider.dart';
 import 'package:autonomix/ui/home_screen.dart';
 import 'package:autonomix/services/database_service.dart';
+import 'package:autonomix/services/error_log_service.dart';
 import 'package:autonomix/services/github_service.dart';
+import 'package:autonomix/services/install_manager.dart';
+import 'package:autonomix/services/install_tracker.dart';
 import 'package:autonomix/services/installer_service.dart';
+import 'package:autonomix/services/notification_service.dart';
+import 'package:autonomix/services/self_update_service.dart';
+import 'package:autonomix/models/release_channel.dart';
+import 'package:autonomix/models/release_provider.dart';
 import 'package:autonomix/models/tracked_app.dart';
 
 class MockDatabaseService extends DatabaseService {
@@ -24,7 +31,15 @@ class MockDatabaseService extends DatabaseService {
   }
 
   @override
-  Future<int> addApp(String repoOwner, String repoName, String displayName) async {
+  Future<int> addApp(
+    String repoOwner,
+    String repoName,
+    String displayName, {
+    String? tagPrefix,
+    ReleaseChannel? channel,
+    ReleaseProvider? provider,
+    String? host,
+  }) async {
     return 1;
   }
 }
@@ -40,6 +55,14 @@ void main() {
           Provider<DatabaseService>(create: (_) => MockDatabaseService()),
           Provider<GitHubService>(create: (_) => MockGitHubService()),
           Provider<InstallerService>(create: (_) => MockInstallerService()),
+          Provider<ErrorLogService>(create: (_) => ErrorLogService()),
+          Provider<SelfUpdateService>(create: (_) => SelfUpdateService()),
+          Provider<NotificationService>(create: (_) => NotificationService()),
+          ChangeNotifierProvider<InstallTracker>(create: (_) => InstallTracker()),
+          ProxyProvider4<DatabaseService, GitHubService, InstallerService, InstallTracker, InstallManager>(
+            update: (_, db, gh, installer, tracker, previous) =>
+                previous ?? InstallManager(db, gh, installer, tracker),
+          ),
         ],
         child: const MaterialApp(
           home: HomeScreen(),
@@ -64,6 +87,14 @@ void main() {
           Provider<DatabaseService>(create: (_) => MockDatabaseService()),
           Provider<GitHubService>(create: (_) => MockGitHubService()),
           Provider<InstallerService>(create: (_) => MockInstallerService()),
+          Provider<ErrorLogService>(create: (_) => ErrorLogService()),
+          Provider<SelfUpdateService>(create: (_) => SelfUpdateService()),
+          Provider<NotificationService>(create: (_) => NotificationService()),
+          ChangeNotifierProvider<InstallTracker>(create: (_) => InstallTracker()),
+          ProxyProvider4<DatabaseService, GitHubService, InstallerService, InstallTracker, InstallManager>(
+            update: (_, db, gh, installer, tracker, previous) =>
+                previous ?? InstallManager(db, gh, installer, tracker),
+          ),
         ],
         child: const MaterialApp(
           home: HomeScreen(),
@@ -78,6 +109,47 @@ void main() {
     await tester.pumpAndSettle();
 
     expect(find.text('Add App'), findsOneWidget);
-    expect(find.text('GitHub URL'), findsOneWidget);
+    expect(find.text('Repository URL'), findsOneWidget);
+  });
+
+  test('windowTitle reflects the number of pending updates', () {
+    expect(HomeScreen.windowTitle(0), 'Autonomix');
+    expect(HomeScreen.windowTitle(1), 'Autonomix (1 update)');
+    expect(HomeScreen.windowTitle(4), 'Autonomix (4 updates)');
+  });
+
+  test('matchesSearch looks at the name and repo, ignoring case', () {
+    final app = TrackedApp(
+      repoOwner: 'sharkdp',
+      repoName: 'bat',
+      displayName: 'Bat',
+      createdAt: DateTime.now(),
+    );
+
+    expect(HomeScreen.matchesSearch(app, ''), isTrue);
+    expect(HomeScreen.matchesSearch(app, 'BAT'), isTrue);
+    expect(HomeScreen.matchesSearch(app, 'sharkdp/b'), isTrue);
+    expect(HomeScreen.matchesSearch(app, 'fd'), isFalse);
+  });
+
+  test('newUpdates only reports releases that are new since the last check', () {
+    final now = DateTime.now();
+    TrackedApp app(int id, String latest) => TrackedApp(
+          id: id,
+          repoOwner: 'owner',
+          repoName: 'repo$id',
+          displayName: 'App $id',
+          installedVersion: 'v1.0.0',
+          latestVersion: latest,
+          createdAt: now,
+        );
+
+    final found = HomeScreen.newUpdates(
+      [app(1, 'v1.0.0'), app(2, 'v1.1.0'), app(3, 'v1.0.0')],
+      [app(1, 'v1.2.0'), app(2, 'v1.1.0'), app(3, 'v1.0.0')],
+      now,
+    );
+
+    expect(found.map((a) => a.id), [1]);
   });
 }
@@ -0,0 +1,34 @@
+import 'dart:convert';
+import 'package:flutter_test/flutter_test.dart';
+import 'package:autonomix/models/tracked_app.dart';
+import 'package:autonomix/ui/widgets/raw_entry_dialog.dart';
+
+void main() {
+  group('RawEntryDialog.parse', () {
+    final app = TrackedApp(
+      id: 7,
+      repoOwner: 'owner',
+      repoName: 'repo',
+      displayName: 'App',
+      installedVersion: 'v1.0.0',
+      createdAt: DateTime(2024, 1, 1),
+    );
+
+    test('applies edits but keeps the original id', () {
+      final edited = {...app.toMap(), 'id': 99, 'installed_version': 'v0.9.0'};
+
+      final parsed = RawEntryDialog.parse(jsonEncode(edited), app.id);
+
+      expect(parsed.id, 7);
+      expect(parsed.installedVersion, 'v0.9.0');
+    });
+
+    test('rejects entries that do not form an app', () {
+      expect(() => RawEntryDialog.parse('[]', app.id), throwsFormatException);
+      expect(
+        () => RawEntryDialog.parse(jsonEncode({...app.toMap(), 'created_at': 'yesterday'}), app.id),
+        throwsFormatException,
+      );
+    });
+  });
+}
@@ -0,0 +1,24 @@
+import 'package:flutter_test/flutter_test.dart';
+import 'package:autonomix/models/tracked_app.dart';
+import 'package:autonomix/services/github_service.dart';
+import 'package:autonomix/ui/widgets/update_all_dialog.dart';
+
+void main() {
+  group('UpdateAllDialog.describeUnresolved', () {
+    final app = TrackedApp(
+      id: 7,
+      repoOwner: 'owner',
+      repoName: 'repo',
+      displayName: 'App',
+      installedVersion: 'v1.0.0',
+      latestVersion: 'v2.0.0',
+      createdAt: DateTime(2024, 1, 1),
+    );
+
+    test('names the error and says the app is checked again', () {
+      final subtitle = UpdateAllDialog.describeUnresolved(app, RateLimitException(null));
+
+      expect(subtitle, 'v1.0.0 → v2.0.0 • Rate limited: GitHub API rate limit exceeded; checked again when updating');
+    });
+  });
+}